@@ -14,7 +14,8 @@ fn main() {
         pixels_height,
         screen_destination_area_upperleftcorner_x,
         screen_destination_area_upperleftcorner_y,
-    );
+    )
+    .unwrap();
 
     println!("\r\nA pixel area of the size of a rectangle with width : {}px and height : {}px, will now be copied from the following screen location :", pixels_width, pixels_height);
     println!(
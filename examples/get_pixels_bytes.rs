@@ -8,7 +8,7 @@ fn main() {
 
     // u8 Screen variant
     let mut screen_u8: Screen<u8> = Screen::new(80, 2, 4, 1);
-    screen_u8.scan_area();
+    screen_u8.scan_area().unwrap();
 
     println!("Each pixel's color is obtained by its BGRA values combination, in a Vector of u8 those 4 values occupy 1 position each, in a Vector of u32 those 4 values occupy together just one position.");
     println!("To contain the BGRA (Blue, Green, Red, Alpha) values of a single pixel a Vec<u8> would have a lenght of 4, a Vec<u32> would have a lenght of just 1");
@@ -29,7 +29,7 @@ fn main() {
 
     // u32 Screen variant (the variant can also be specified using the turbofish ::<>, as in this case)
     let mut screen_u32 = Screen::<u32>::new(80, 2, 4, 1);
-    screen_u32.scan_area();
+    screen_u32.scan_area().unwrap();
     let vec_u32_first_value = screen_u32.get_bytes()[0];
     let bgra = <u32>::u8_u32_casting(&[vec_u32_first_value]);
     println!(
@@ -31,7 +31,8 @@ fn main() {
         pixels_area_width as u32,
         pixels_area_height as u32,
         pixels_send_mode,
-    );
+    )
+    .unwrap();
 
     println!("\r\nA Vec<u8> containing 64 bytes, representing a qube of 4 x 4 (16) pixels, where the first 2 are red, the other 14 blue, will now be sent to the screen at the location :");
     println!(
@@ -57,5 +57,6 @@ fn main() {
         area_width,
         area_height,
         PixelsSendMode::AlphaDisabledHideBGR(255, 255, 255),
-    );
+    )
+    .unwrap();
 }
@@ -27,7 +27,7 @@ fn main() {
                 transparent_green_string_transparent_background_from_chars_sample_40px_green_whitebackground.pixels.width as u32,
                 transparent_green_string_transparent_background_from_chars_sample_40px_green_whitebackground.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled
-            );
+            ).unwrap();
             // export the string as .png
             // image::save_buffer_with_format(format!("{}{}", "fonts/exports/", "test_improvement.png"), &vec_u8_managing::<u8>::swap_blue_with_red(&string_from_string_png.pixels.bytes), string_from_string_png.pixels.width as u32, string_from_string_png.pixels.height as u32, image::ColorType::Rgba8, image::ImageFormat::Png).unwrap();
 
@@ -68,7 +68,8 @@ fn main() {
                 string_from_string_png.pixels.width as u32,
                 string_from_string_png.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
 
             opaque_grey_scale_chars_white_background.export_as_pngs("fonts/exports/from_opaque_grey_scale_12px_chars_sample__white_background/opaque_grey_scale__white_background").unwrap();
         }
@@ -100,7 +101,8 @@ fn main() {
                 string_from_string_png.pixels.width as u32,
                 string_from_string_png.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
 
             transparent_black_chars_transparent_background.export_as_pngs("fonts/exports/from_opaque_grey_scale_12px_chars_sample__white_background/transparent_black__transparent_background").unwrap();
         }
@@ -132,7 +134,8 @@ fn main() {
                 string_from_string_png.pixels.width as u32,
                 string_from_string_png.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
 
             threshold_black_chars_transparent_background.export_as_pngs("fonts/exports/from_opaque_grey_scale_12px_chars_sample__white_background/threshold_black__transparent_background").unwrap();
 
@@ -157,7 +160,8 @@ fn main() {
         pixels_string_black_transparent_background.pixels.width as u32,
         pixels_string_black_transparent_background.pixels.height as u32,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 
     let mut threshold_black_chars_white_background =
         threshold_black_chars_transparent_background.clone();
@@ -176,7 +180,8 @@ fn main() {
         pixels_string_black_white_background.pixels.width as u32,
         pixels_string_black_white_background.pixels.height as u32,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 
     threshold_black_chars_white_background.export_as_pngs("fonts/exports/from_threshold_black__transparent_background/threshold_black_chars__white_background").unwrap();
 
@@ -192,7 +197,8 @@ fn main() {
         pixels_string_red_transparent_background.pixels.width as u32,
         pixels_string_red_transparent_background.pixels.height as u32,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 
     transparent_black_chars_transparent_background.export_as_pngs("fonts/exports/from_transparent_black__transparent_background/transparent_red_chars__transparent_background").unwrap();
 }
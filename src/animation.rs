@@ -0,0 +1,594 @@
+use std::time::Duration;
+
+#[cfg(feature = "json")]
+use serde::Deserialize;
+
+use crate::{
+    clock::{Clock, SystemClock},
+    pixels::ResizeSize,
+    PixelCasterError, PixelsCollection, ResizeFilter, ScreenArea, Sprite,
+};
+
+/// Frames sliced from a single PixelsCollection (e.g.: a .png holding all the frames of an animation)
+#[derive(Clone)]
+pub struct SpriteSheet {
+    frames: Vec<PixelsCollection<u8>>,
+}
+
+/// Frame rectangle of a JSON sprite sheet
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+struct JsonRect {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFrame {
+    Packed { frame: JsonRect },
+    Rect(JsonRect),
+}
+
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFrames {
+    List(Vec<JsonFrame>),
+    Named(JsonNamedFrames),
+}
+
+/// Frames of a `"frames"` object, in the order the JSON lists them (texture packers write them in the animation's order)
+#[cfg(feature = "json")]
+struct JsonNamedFrames(Vec<JsonFrame>);
+
+#[cfg(feature = "json")]
+impl<'de> Deserialize<'de> for JsonNamedFrames {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NamedFramesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for NamedFramesVisitor {
+            type Value = JsonNamedFrames;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("an object of frames")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<JsonNamedFrames, A::Error> {
+                let mut frames = Vec::new();
+                while let Some((_, frame)) = map.next_entry::<serde::de::IgnoredAny, JsonFrame>()? {
+                    frames.push(frame);
+                }
+                Ok(JsonNamedFrames(frames))
+            }
+        }
+
+        deserializer.deserialize_map(NamedFramesVisitor)
+    }
+}
+
+#[cfg(feature = "json")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonSheet {
+    Frames(Vec<JsonFrame>),
+    Packed { frames: JsonFrames },
+}
+
+impl SpriteSheet {
+    /// Slices the pixels into frame_width x frame_height frames (at least 1 x 1), from the top left, row by row.
+    /// The pixels left over on the right and bottom edges, not enough for a whole frame, are ignored.
+    /// Returns an OutOfBounds error if not even a frame fits in the pixels
+    pub fn from_grid(
+        pixels: &PixelsCollection<u8>,
+        frame_width: usize,
+        frame_height: usize,
+    ) -> Result<SpriteSheet, PixelCasterError> {
+        let (frame_width, frame_height) = (frame_width.max(1), frame_height.max(1));
+        PixelCasterError::check_bounds(
+            0,
+            0,
+            frame_width,
+            frame_height,
+            pixels.width,
+            pixels.height,
+        )?;
+        let mut rects = Vec::new();
+        for row in 0..pixels.height / frame_height {
+            for column in 0..pixels.width / frame_width {
+                rects.push(ScreenArea::new(
+                    (column * frame_width) as i32,
+                    (row * frame_height) as i32,
+                    frame_width as u32,
+                    frame_height as u32,
+                ));
+            }
+        }
+        Self::from_rects(pixels, &rects)
+    }
+
+    /// Slices the given rectangles of the pixels, in their order.
+    /// Returns an OutOfBounds error if one of them is not inside the pixels, a NoFrames error if there are none
+    pub fn from_rects(
+        pixels: &PixelsCollection<u8>,
+        rects: &[ScreenArea],
+    ) -> Result<SpriteSheet, PixelCasterError> {
+        if rects.is_empty() {
+            return Err(PixelCasterError::NoFrames);
+        }
+        let frames = rects
+            .iter()
+            .map(|rect| {
+                PixelCasterError::check_bounds(
+                    rect.upperleftcorner_x() as i64,
+                    rect.upperleftcorner_y() as i64,
+                    rect.width() as usize,
+                    rect.height() as usize,
+                    pixels.width,
+                    pixels.height,
+                )?;
+                Ok(pixels
+                    .sub_view(
+                        rect.upperleftcorner_x() as usize,
+                        rect.upperleftcorner_y() as usize,
+                        rect.width() as usize,
+                        rect.height() as usize,
+                    )?
+                    .to_owned())
+            })
+            .collect::<Result<_, PixelCasterError>>()?;
+        Ok(SpriteSheet { frames })
+    }
+
+    /// Slices the rectangles listed by the JSON, either an array of `{"x", "y", "w", "h"}` objects
+    /// or the `"frames"` (an array, or an object of named frames, taken in the JSON's order) of a texture packer's export, whose items hold them in `"frame"`.
+    /// Returns a Decode error if the JSON can't be read as such
+    #[cfg(feature = "json")]
+    pub fn from_json(
+        pixels: &PixelsCollection<u8>,
+        json: &str,
+    ) -> Result<SpriteSheet, PixelCasterError> {
+        let frames = match serde_json::from_str(json).map_err(PixelCasterError::decode)? {
+            JsonSheet::Frames(frames)
+            | JsonSheet::Packed {
+                frames: JsonFrames::List(frames),
+            } => frames,
+            JsonSheet::Packed {
+                frames: JsonFrames::Named(JsonNamedFrames(frames)),
+            } => frames,
+        };
+        let rects: Vec<ScreenArea> = frames
+            .into_iter()
+            .map(|frame| {
+                let (JsonFrame::Packed { frame: rect } | JsonFrame::Rect(rect)) = frame;
+                ScreenArea::new(rect.x as i32, rect.y as i32, rect.w as u32, rect.h as u32)
+            })
+            .collect();
+        Self::from_rects(pixels, &rects)
+    }
+
+    /// Number of frames, at least 1
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Always false, a SpriteSheet has at least a frame
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frame(&self, index: usize) -> Option<&PixelsCollection<u8>> {
+        self.frames.get(index)
+    }
+
+    pub fn frames(&self) -> &[PixelsCollection<u8>] {
+        &self.frames
+    }
+}
+
+/// How an Animation goes on after its last frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayMode {
+    /// Starts again from the first frame
+    Loop,
+    /// Goes back to the first frame and then forward again (0 1 2 1 0 1 ...)
+    PingPong,
+    /// Stays on the last frame
+    Once,
+}
+
+/// The frames of a SpriteSheet played at a fixed rate
+#[derive(Clone)]
+pub struct Animation {
+    sheet: SpriteSheet,
+    frame_duration: Duration,
+    mode: PlayMode,
+}
+
+impl Animation {
+    /// Plays the sheet's frames `fps` per second
+    ///
+    /// # Panics
+    ///
+    /// If fps is not positive and finite
+    pub fn new(sheet: SpriteSheet, fps: f64, mode: PlayMode) -> Animation {
+        assert!(
+            fps > 0.0 && fps.is_finite(),
+            "fps must be positive and finite"
+        );
+        Animation {
+            sheet,
+            frame_duration: Duration::from_secs_f64(1.0 / fps),
+            mode,
+        }
+    }
+
+    pub fn sheet(&self) -> &SpriteSheet {
+        &self.sheet
+    }
+
+    /// Returns the index of the frame shown once `elapsed` has passed from the animation's start
+    pub fn frame_index_at(&self, elapsed: Duration) -> usize {
+        let count = self.sheet.len();
+        let played = (elapsed.as_nanos() / self.frame_duration.as_nanos().max(1)) as usize;
+        match self.mode {
+            PlayMode::Loop => played % count,
+            PlayMode::Once => played.min(count - 1),
+            PlayMode::PingPong if count == 1 => 0,
+            PlayMode::PingPong => {
+                let step = played % (2 * (count - 1));
+                if step < count {
+                    step
+                } else {
+                    2 * (count - 1) - step
+                }
+            }
+        }
+    }
+
+    /// Returns the frame shown once `elapsed` has passed from the animation's start
+    pub fn frame_at(&self, elapsed: Duration) -> &PixelsCollection<u8> {
+        &self.sheet.frames[self.frame_index_at(elapsed)]
+    }
+
+    /// Returns whether a PlayMode::Once animation has shown its last frame for a whole frame's time, the others never finish
+    pub fn is_finished_at(&self, elapsed: Duration) -> bool {
+        self.mode == PlayMode::Once
+            && elapsed.as_nanos() >= self.frame_duration.as_nanos() * self.sheet.len() as u128
+    }
+}
+
+/// Maps the progress of a Tween (0 - 1) to the fraction of the way from its start value to its end one
+#[derive(Clone, Copy, Debug)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineInOut,
+    /// Overshoots the end value and settles back on it
+    BackOut,
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    /// Returns the eased fraction of the progress `t`, which is clamped to 0 - 1
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Easing::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::CubicInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::SineInOut => -((std::f32::consts::PI * t).cos() - 1.0) / 2.0,
+            Easing::BackOut => {
+                let (c1, c3) = (1.70158, 2.70158);
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            }
+            Easing::Custom(f) => f(t),
+        }
+    }
+}
+
+/// Values that a Tween can go through
+pub trait Tweenable: Copy {
+    /// Returns the value `fraction` of the way from `from` to `to` (beyond them when the fraction is outside of 0 - 1)
+    fn lerp(from: Self, to: Self, fraction: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn lerp(from: f32, to: f32, fraction: f32) -> f32 {
+        from + (to - from) * fraction
+    }
+}
+
+/// Opacity, clamped to 0 - 255
+impl Tweenable for u8 {
+    fn lerp(from: u8, to: u8, fraction: f32) -> u8 {
+        f32::lerp(from as f32, to as f32, fraction)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    }
+}
+
+/// Position
+impl Tweenable for (i32, i32) {
+    fn lerp(from: (i32, i32), to: (i32, i32), fraction: f32) -> (i32, i32) {
+        (
+            f32::lerp(from.0 as f32, to.0 as f32, fraction).round() as i32,
+            f32::lerp(from.1 as f32, to.1 as f32, fraction).round() as i32,
+        )
+    }
+}
+
+/// Change of a value from `from` to `to` in a given time, starting after a delay
+#[derive(Clone, Copy, Debug)]
+pub struct Tween<V: Tweenable> {
+    pub from: V,
+    pub to: V,
+    pub duration: Duration,
+    pub delay: Duration,
+    pub easing: Easing,
+}
+
+impl<V: Tweenable> Tween<V> {
+    /// Creates a tween starting at once
+    pub fn new(from: V, to: V, duration: Duration, easing: Easing) -> Tween<V> {
+        Tween {
+            from,
+            to,
+            duration,
+            delay: Duration::ZERO,
+            easing,
+        }
+    }
+
+    /// Returns the value once `elapsed` has passed from the tween's creation (`from` during the delay, `to` once it's finished)
+    pub fn value_at(&self, elapsed: Duration) -> V {
+        let elapsed = elapsed.saturating_sub(self.delay);
+        let progress = if self.duration.is_zero() {
+            1.0
+        } else {
+            (elapsed.as_secs_f64() / self.duration.as_secs_f64()) as f32
+        };
+        V::lerp(self.from, self.to, self.easing.apply(progress))
+    }
+
+    pub fn is_finished_at(&self, elapsed: Duration) -> bool {
+        elapsed >= self.delay + self.duration
+    }
+}
+
+/// An Animation and the tweens of its position, opacity and scale, timed by a Clock from their creation (or restart).
+/// `update` applies their current state to a Sprite (e.g.: one of a Scene, through Scene::sprite_mut)
+pub struct AnimatedSprite<C: Clock = SystemClock> {
+    pub animation: Animation,
+    /// Upper left corner of the unscaled frame
+    pub position: Tween<(i32, i32)>,
+    pub opacity: Option<Tween<u8>>,
+    /// Scale of the frames around their center (1 : their size)
+    pub scale: Option<Tween<f32>>,
+    /// Filter used to scale the frames
+    pub scale_filter: ResizeFilter,
+    clock: C,
+    start: Duration,
+}
+
+impl AnimatedSprite {
+    /// Plays the animation at x, y, in the real time
+    pub fn new(animation: Animation, x: i32, y: i32) -> AnimatedSprite {
+        Self::with_clock(animation, x, y, SystemClock::new())
+    }
+}
+
+impl<C: Clock> AnimatedSprite<C> {
+    /// Plays the animation at x, y, timed by the given Clock (e.g.: a ManualClock to run it deterministically)
+    pub fn with_clock(animation: Animation, x: i32, y: i32, clock: C) -> AnimatedSprite<C> {
+        AnimatedSprite {
+            animation,
+            position: Tween::new((x, y), (x, y), Duration::ZERO, Easing::Linear),
+            opacity: None,
+            scale: None,
+            scale_filter: ResizeFilter::Bilinear,
+            start: clock.now(),
+            clock,
+        }
+    }
+
+    /// Time passed from the start
+    pub fn elapsed(&self) -> Duration {
+        self.clock.now().saturating_sub(self.start)
+    }
+
+    /// Starts the animation and the tweens again
+    pub fn restart(&mut self) {
+        self.start = self.clock.now();
+    }
+
+    /// Returns whether the animation and all the tweens are finished (never, unless the animation is PlayMode::Once)
+    pub fn is_finished(&self) -> bool {
+        let elapsed = self.elapsed();
+        self.animation.is_finished_at(elapsed)
+            && self.position.is_finished_at(elapsed)
+            && self
+                .opacity
+                .is_none_or(|tween| tween.is_finished_at(elapsed))
+            && self.scale.is_none_or(|tween| tween.is_finished_at(elapsed))
+    }
+
+    /// Returns a Sprite showing the current state
+    pub fn sprite(&self) -> Sprite {
+        let mut sprite = Sprite::new(self.animation.sheet.frames[0].clone(), 0, 0);
+        self.update(&mut sprite);
+        sprite
+    }
+
+    /// Sets the sprite's pixels, position and opacity to the current state (its z_index and visibility are left as they are)
+    pub fn update(&self, sprite: &mut Sprite) {
+        let elapsed = self.elapsed();
+        let frame = self.animation.frame_at(elapsed);
+        let (x, y) = self.position.value_at(elapsed);
+        let scale = self
+            .scale
+            .map_or(1.0, |tween| tween.value_at(elapsed).max(0.0));
+        if scale == 1.0 {
+            sprite.pixels = frame.clone();
+        } else {
+            let (width, height) = (
+                (frame.width as f32 * scale).round() as usize,
+                (frame.height as f32 * scale).round() as usize,
+            );
+            sprite.pixels = frame.create_new_resized_with_filter(
+                ResizeSize::WidthAndHeight(width, height),
+                self.scale_filter,
+            );
+        }
+        sprite.x = x + (frame.width as i32 - sprite.pixels.width as i32) / 2;
+        sprite.y = y + (frame.height as i32 - sprite.pixels.height as i32) / 2;
+        sprite.opacity = self.opacity.map_or(255, |tween| tween.value_at(elapsed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    /// 4 frames of 2x1, in a 4x2 sheet, each filled with its index as Blue
+    fn sheet() -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(
+            4,
+            2,
+            [0u8, 0, 1, 1, 2, 2, 3, 3]
+                .iter()
+                .flat_map(|i| [*i, 0, 0, 255])
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sheets_and_play_modes() {
+        let grid = SpriteSheet::from_grid(&sheet(), 2, 1).unwrap();
+        assert_eq!(grid.len(), 4);
+        assert!(grid
+            .frames()
+            .iter()
+            .enumerate()
+            .all(|(i, frame)| frame.width == 2
+                && frame.bytes.chunks_exact(4).all(|bgra| bgra[0] == i as u8)));
+        assert!(SpriteSheet::from_grid(&sheet(), 5, 1).is_err());
+        assert!(matches!(
+            SpriteSheet::from_rects(&sheet(), &[]),
+            Err(PixelCasterError::NoFrames)
+        ));
+
+        let frames = |mode| {
+            let animation = Animation::new(grid.clone(), 10.0, mode);
+            (0..9)
+                .map(|i| animation.frame_index_at(Duration::from_millis(i * 100 + 50)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(frames(PlayMode::Loop), [0, 1, 2, 3, 0, 1, 2, 3, 0]);
+        assert_eq!(frames(PlayMode::PingPong), [0, 1, 2, 3, 2, 1, 0, 1, 2]);
+        assert_eq!(frames(PlayMode::Once), [0, 1, 2, 3, 3, 3, 3, 3, 3]);
+        let once = Animation::new(grid, 10.0, PlayMode::Once);
+        assert!(!once.is_finished_at(Duration::from_millis(399)));
+        assert!(once.is_finished_at(Duration::from_millis(400)));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_sheets() {
+        let json = SpriteSheet::from_json(
+            &sheet(),
+            r#"{"frames": {"b.png": {"frame": {"x": 0, "y": 1, "w": 2, "h": 1}}, "a.png": {"frame": {"x": 2, "y": 0, "w": 2, "h": 1}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(json.frame(0).unwrap().bytes[0], 2);
+        assert_eq!(json.frame(1).unwrap().bytes[0], 1);
+        // in the JSON's order, not the names' one ("walk10" would come before "walk2")
+        let strip =
+            PixelsCollection::<u8>::create(12, 1, (0..12u8).flat_map(|i| [i, 0, 0, 255]).collect())
+                .unwrap();
+        let named: Vec<String> = (0..12)
+            .map(|i| {
+                format!(
+                    r#""walk{}": {{"frame": {{"x": {}, "y": 0, "w": 1, "h": 1}}}}"#,
+                    i + 1,
+                    i
+                )
+            })
+            .collect();
+        let walk = SpriteSheet::from_json(
+            &strip,
+            &format!(r#"{{"frames": {{{}}}}}"#, named.join(", ")),
+        )
+        .unwrap();
+        assert_eq!(walk.len(), 12);
+        assert!((0..12).all(|i| walk.frame(i).unwrap().bytes[0] == i as u8));
+        let json =
+            SpriteSheet::from_json(&sheet(), r#"[{"x": 3, "y": 1, "w": 1, "h": 1}]"#).unwrap();
+        assert_eq!(json.frame(0).unwrap().bytes[0], 3);
+        assert!(SpriteSheet::from_json(&sheet(), r#"[{"x": 3, "y": 1, "w": 2, "h": 1}]"#).is_err());
+        assert!(matches!(
+            SpriteSheet::from_json(&sheet(), "[{"),
+            Err(PixelCasterError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn tweens_with_injected_clock() {
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+        assert_eq!(Easing::CubicInOut.apply(2.0), 1.0);
+        assert!(Easing::BackOut.apply(0.7) > 1.0);
+        let mut opacity = Tween::new(0u8, 200, Duration::from_secs(1), Easing::Linear);
+        opacity.delay = Duration::from_millis(500);
+        assert_eq!(opacity.value_at(Duration::from_millis(250)), 0);
+        assert_eq!(opacity.value_at(Duration::from_millis(1000)), 100);
+        assert_eq!(opacity.value_at(Duration::from_secs(9)), 200);
+
+        let clock = ManualClock::new();
+        let animation = Animation::new(
+            SpriteSheet::from_grid(&sheet(), 2, 1).unwrap(),
+            4.0,
+            PlayMode::Once,
+        );
+        let mut animated = AnimatedSprite::with_clock(animation, 10, 20, clock.clone());
+        animated.position = Tween::new((10, 20), (30, 0), Duration::from_secs(2), Easing::Linear);
+        animated.opacity = Some(Tween::new(255, 55, Duration::from_secs(1), Easing::Linear));
+        animated.scale = Some(Tween::new(1.0, 2.0, Duration::from_secs(1), Easing::Linear));
+
+        let mut sprite = animated.sprite();
+        assert_eq!((sprite.x, sprite.y, sprite.opacity), (10, 20, 255));
+        assert_eq!(sprite.pixels.bytes[0], 0);
+
+        clock.advance(Duration::from_secs(1));
+        animated.update(&mut sprite);
+        // frame 4 of the sheet, twice its size around the position moved halfway
+        assert_eq!(sprite.pixels.bytes[0], 3);
+        assert_eq!((sprite.pixels.width, sprite.pixels.height), (4, 2));
+        assert_eq!((sprite.x, sprite.y, sprite.opacity), (19, 10, 55));
+        assert!(!animated.is_finished());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(animated.is_finished());
+        animated.restart();
+        assert_eq!(animated.sprite().opacity, 255);
+    }
+}
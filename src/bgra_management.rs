@@ -1,4 +1,15 @@
-use crate::{pixels_string::BGRA, PixelValues};
+use serde::{Deserialize, Serialize};
+
+use crate::{PixelCasterError, PixelValues};
+
+/// Blue Green Red Alpha values of a color
+#[derive(Clone, Copy, Debug)]
+pub struct BGRA<T: Copy + Clone>(pub T, pub T, pub T, pub T);
+impl<T: Copy + Clone> BGRA<T> {
+    pub fn to_vec(&self) -> Vec<T> {
+        vec![self.0, self.1, self.2, self.3]
+    }
+}
 
 pub trait ColorAlteration<T: PixelValues<T>> {
     /// Set BGR to the provided values
@@ -178,6 +189,7 @@ impl ColorAlteration<u8> for Vec<u8> {
     /// # Examples
     ///
     /// ```no_run
+    /// use pixel_caster::bgra_management::ColorAlteration;
     /// let mut vec = vec![255,255,255,255, 100,0,200,255, 0,255,0,100];
     /// // In the vec there are values for the colors of 3 pixels (each has its BGRA combinations of 4 values) those which have an Alpha of 255
     /// // will have their Blue and Alpha values set to 255, the Green and Red values set to 0, in order to change their color to fully opaque Blue (255,0,0,255).
@@ -211,6 +223,7 @@ impl ColorAlteration<u8> for Vec<u8> {
     /// # Examples
     ///
     /// ```no_run
+    /// use pixel_caster::bgra_management::ColorAlteration;
     /// let mut vec = vec![255,255,255,255, 100,0,200,255, 0,255,0,100];
     /// // In the vec there are values for the colors of 3 pixels (each has its BGRA combinations of 4 values) those which have a BGRA combination that corresponds to fully opaque white (B,G,R,A = 255)
     /// // will have their Blue and Green values set to 0, in order to change their color to fully opaque Red (0,0,255,255). In this case the one that will be changed is the first BGRA combination of the vec
@@ -251,6 +264,102 @@ impl ColorAlteration<u8> for Vec<u8> {
     }
 }
 
+/// Outermost pixels (and their indexes in the bytes) of an area among those that passed a matcher
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct CardinalPoints {
+    pub top_y: usize,
+    pub top_y_index: usize,
+    pub right_x: usize,
+    pub right_x_index: usize,
+    pub left_x: usize,
+    pub left_x_index: usize,
+    pub bottom_y: usize,
+    pub bottom_y_index: usize,
+}
+
+impl CardinalPoints {
+    /// If the provided point is lesser than the leftest cardinal point, it's value and index will be set to the leftest point's coordinates.
+    /// If the provided point is greater than the rightest cardinal point, it's value and index will be set to the rightest point's coordinates.
+    pub fn update_if_expands_horizontal(&mut self, point: usize, index: usize) {
+        if point < self.left_x {
+            self.left_x = point;
+            self.left_x_index = index;
+        }
+        if point > self.right_x {
+            self.right_x = point;
+            self.right_x_index = index;
+        }
+    }
+
+    /// If the provided point is lesser than the top cardinal point, it's value and index will be set to the top point's coordinates.
+    /// If the provided point is greater than the bottom cardinal point, it's value and index will be set to the bottom point's coordinates.
+    pub fn update_if_expands_vertical(&mut self, point: usize, index: usize) {
+        if point < self.top_y {
+            self.top_y = point;
+            self.top_y_index = index;
+        }
+        if point > self.bottom_y {
+            self.bottom_y = point;
+            self.bottom_y_index = index;
+        }
+    }
+
+    /// If the provided CardinalPoints' have some outermost values than the self's, these will be set as the new values.
+    pub fn update_if_expands_from_cardinal_points(&mut self, c_p: &CardinalPoints) {
+        self.update_if_expands_horizontal(c_p.left_x, c_p.left_x_index);
+        self.update_if_expands_horizontal(c_p.right_x, c_p.right_x_index);
+        self.update_if_expands_vertical(c_p.top_y, c_p.top_y_index);
+        self.update_if_expands_vertical(c_p.bottom_y, c_p.bottom_y_index);
+    }
+
+    pub fn width(&self) -> Result<usize, PixelCasterError> {
+        if self.right_x < self.left_x {
+            return Err(PixelCasterError::NoMatchingPixels);
+        }
+        Ok(self.right_x - self.left_x + 1)
+    }
+
+    pub fn height(&self) -> Result<usize, PixelCasterError> {
+        if self.bottom_y < self.top_y {
+            return Err(PixelCasterError::NoMatchingPixels);
+        }
+        Ok(self.bottom_y - self.top_y + 1)
+    }
+}
+
+impl Default for CardinalPoints {
+    fn default() -> Self {
+        Self {
+            top_y: usize::MAX,
+            top_y_index: 0,
+            right_x: 0,
+            right_x_index: 0,
+            left_x: usize::MAX,
+            left_x_index: 0,
+            bottom_y: 0,
+            bottom_y_index: 0,
+        }
+    }
+}
+
+impl std::fmt::Debug for CardinalPoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CardinalPoints")
+            .field("top_y", &self.top_y)
+            .field("top_y_index", &self.top_y_index)
+            .field("right_x", &self.right_x)
+            .field("right_x_index", &self.right_x_index)
+            .field("left_x", &self.left_x)
+            .field("left_x_index", &self.left_x_index)
+            .field("bottom_y", &self.bottom_y)
+            .field("bottom_y_index", &self.bottom_y_index)
+            .finish()
+    }
+}
+
+/// Tells whether a pixel's Blue Green Red Alpha combination is one to look for (see bytes_matchers)
+pub type BgraMatcher = fn(u8, u8, u8, u8) -> bool;
+
 pub mod bytes_matchers {
     pub fn visible(_: u8, _: u8, _: u8, a: u8) -> bool {
         a > 0
@@ -334,7 +443,7 @@ pub trait SwitchBytes<T: crate::PixelValues<T>, U: crate::PixelValues<U>> {
 impl SwitchBytes<u8, u32> for u8 {
     /// Switches values of 2 provided indexes of every 4 8-bytes chunks ((u8 \[B,G,R,A\]) B: u8, G: u8, R:u8, A :u8 values chunks) in the vector, i1 and i2 must be between 0 and 3 (B:0, G:1, R:2, A:3).
     fn switch_bytes(vec: &mut Vec<u8>, i1: usize, i2: usize) {
-        if i1 > 3 || i2 > 3 || i1 == i2 || vec.len() % 4 != 0 {
+        if i1 > 3 || i2 > 3 || i1 == i2 || !vec.len().is_multiple_of(4) {
             return;
         }
         let mut i = 0;
@@ -505,7 +614,7 @@ pub fn u32_bytes_oredered_indexes_and_fullvalues() -> ([usize; 4], [u32; 4]) {
 
 #[cfg(test)]
 mod tests {
-    use crate::{bgra_management::*, PixelsCollection, PixelsSendMode, Screen};
+    use crate::{bgra_management::*, PixelsCollection, PixelsSendMode, VirtualScreen};
 
     #[test]
     fn test_u8_u32_convertion() {
@@ -518,14 +627,15 @@ mod tests {
         //                                      red
         bytes_u8_bgra.extend_from_slice(&[0, 0, 255, 125]);
 
-        Screen::update_area_custom(
+        VirtualScreen::update_area_custom(
             &bytes_u8_bgra,
             0,
             0,
             bytes_u8_bgra.len() as u32 / 4,
             1,
             PixelsSendMode::AlphaEnabled,
-        );
+        )
+        .unwrap();
         // when exporting into .ong we need to go from BGRA to RGBA, so swap the B and R values
         let mut bytes_u8_rgba_from_u8_bgra = bytes_u8_bgra.clone();
         <u8>::switch_bytes(&mut bytes_u8_rgba_from_u8_bgra, 0, 2);
@@ -544,14 +654,15 @@ mod tests {
         // A = 125, R = 0, G = 0, B = 255
         assert_eq!(bytes_u32_bgra[0], 0x7D00_00FF);
         // on big endian CPU we would have compared it to 0xFF00_007D, because the order would be [0xBGRA]
-        Screen::update_area_custom(
+        VirtualScreen::update_area_custom(
             &bytes_u32_bgra,
             0,
             0,
             bytes_u32_bgra.len() as u32,
             1,
             PixelsSendMode::AlphaEnabled,
-        );
+        )
+        .unwrap();
 
         let mut bytes_u32_rgba_from_u32_bgra = bytes_u32_bgra.clone();
         <u32>::switch_bytes(&mut bytes_u32_rgba_from_u32_bgra, 0, 2);
@@ -580,14 +691,15 @@ mod tests {
         .unwrap();
 
         let image_u8_bgra_from_image_rgba = image_u8_bgra.clone();
-        Screen::update_area_custom(
+        VirtualScreen::update_area_custom(
             &image_u8_bgra_from_image_rgba.bytes,
             -200,
             0,
             image_u8_bgra.width as u32,
             image_u8_bgra.height as u32,
             PixelsSendMode::AlphaEnabled,
-        );
+        )
+        .unwrap();
         // when exporting into .png we need to go from BGRA to RGBA, so swap the B and R values
         let mut image_u8_rgba_from_image_u8_bgra = image_u8_bgra_from_image_rgba.clone();
         image_u8_rgba_from_image_u8_bgra.switch_bytes(0, 2);
@@ -605,25 +717,27 @@ mod tests {
         let image_u32_rgba =
             PixelsCollection::<u32>::create(image_u8_bgra.width, image_u8_bgra.height, rgba_u32)
                 .unwrap();
-        Screen::update_area_custom(
+        VirtualScreen::update_area_custom(
             &image_u32_rgba.bytes,
             -200,
             0,
             image_u8_bgra.width as u32,
             image_u8_bgra.height as u32,
             PixelsSendMode::AlphaEnabled,
-        );
+        )
+        .unwrap();
 
         // image_u32_rgba has BGRA ordered bytes
         let mut bytes_u8_bgra_from_u32_bgra = <u32>::u8_u32_casting(&image_u32_rgba.bytes);
-        Screen::update_area_custom(
+        VirtualScreen::update_area_custom(
             &bytes_u8_bgra_from_u32_bgra,
             -200,
             0,
             image_u8_bgra.width as u32,
             image_u8_bgra.height as u32,
             PixelsSendMode::AlphaEnabled,
-        );
+        )
+        .unwrap();
         // when exporting into .png we need to go from BGRA to RGBA, so swap the B and R values
         <u8>::switch_bytes(&mut bytes_u8_bgra_from_u32_bgra, 0, 2);
         image::save_buffer_with_format(
@@ -3,6 +3,8 @@ pub use windows::Win32::{
     Graphics::Gdi::{BitBlt, HDC, SRCCOPY},
 };
 
+use crate::PixelCasterError;
+
 // CreatedHDC and HDC can be either destination(dst) and source(src) or vice versa
 /// Bit-block transfer of the color data corresponding to an area of pixels of the RGBA sequence.
 /// It doesn't print the 4th value (A : alpha,opacity) so only RGB, the A won't be used.
@@ -15,10 +17,10 @@ pub fn bit_block_transfer(
     src: HDC,
     src_ulc_x: i32,
     src_ulc_y: i32,
-) {
+) -> Result<(), PixelCasterError> {
     unsafe {
         // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-bitblt
-        match BitBlt(
+        BitBlt(
             // A handle to the destination device context
             dst,
             // The x-coordinate, in logical units, of the upper-left corner of the destination rectangle
@@ -37,9 +39,7 @@ pub fn bit_block_transfer(
             src_ulc_y,
             // A raster-operation code. These codes define how the color data for the source rectangle is to be combined with the color data for the destination rectangle to achieve the final color
             SRCCOPY,
-        ) {
-            Ok(_) => (),
-            Err(e) => println!("bit_block_transfer error: {}", e),
-        };
+        )
+        .map_err(PixelCasterError::from)
     }
 }
@@ -0,0 +1,343 @@
+use crate::{
+    bgra_management::{BgraMatcher, CardinalPoints},
+    PixelsCollection, PixelsView, BGRA_INVISIBLE_PIXEL,
+};
+
+/// Which neighbours of a pixel belong to its same blob when they match too
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Those sharing a side with it (left, right, above, below)
+    Four,
+    /// Those sharing a side or a corner with it
+    Eight,
+}
+
+/// Options of find_blobs
+#[derive(Clone, Copy, Debug)]
+pub struct BlobOptions {
+    pub connectivity: Connectivity,
+    /// Blobs stacked one above the other (overlapping horizontally) with at most this many rows between them are merged
+    /// (e.g.: the dot and the stem of 'i' and 'j', the parts of '%' and ':'), None : never
+    pub merge_vertical_distance: Option<usize>,
+}
+
+impl Default for BlobOptions {
+    /// 8-connectivity, no merging
+    fn default() -> BlobOptions {
+        BlobOptions {
+            connectivity: Connectivity::Eight,
+            merge_vertical_distance: None,
+        }
+    }
+}
+
+/// Group of connected pixels passing the bgra_matcher
+#[derive(Clone)]
+pub struct Blob {
+    /// Outermost pixels, their coordinates and indexes are those of the PixelsCollection the blob was found in
+    pub cardinal_points: CardinalPoints,
+    /// Number of pixels
+    pub area: usize,
+    /// Average x, y of the pixels
+    pub centroid: (f32, f32),
+    /// The blob's bounding box, where the pixels that are not part of the blob are set to BGRA_INVISIBLE_PIXEL
+    pub pixels: PixelsCollection<u8>,
+}
+
+/// Blob being built, with the coordinates (relative to the view) of its pixels
+struct PartialBlob {
+    cardinal_points: CardinalPoints,
+    coordinates: Vec<(usize, usize)>,
+}
+
+impl PixelsCollection<u8> {
+    /// Returns the groups of connected pixels that pass the bgra_matcher (e.g.: bgra_management::bytes_matchers::visible),
+    /// in the order their top leftmost pixel is met scanning the rows from the top
+    pub fn find_blobs(&self, bgra_matcher: BgraMatcher, opts: BlobOptions) -> Vec<Blob> {
+        self.view().find_blobs(bgra_matcher, opts)
+    }
+}
+
+impl PixelsView<'_, u8> {
+    /// As PixelsCollection::find_blobs, only inside this view (a blob touching its edges is cut there)
+    pub fn find_blobs(&self, bgra_matcher: BgraMatcher, opts: BlobOptions) -> Vec<Blob> {
+        let (width, height) = (self.width(), self.height());
+        let matching: Vec<bool> = self
+            .rows()
+            .flat_map(|row| {
+                row.chunks_exact(4)
+                    .map(|bgra| bgra_matcher(bgra[0], bgra[1], bgra[2], bgra[3]))
+            })
+            .collect();
+        let neighbours: &[(isize, isize)] = match opts.connectivity {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        };
+
+        let mut visited = vec![false; width * height];
+        let mut blobs = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..width * height {
+            if !matching[start] || visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            stack.push(start);
+            let mut blob = PartialBlob {
+                cardinal_points: CardinalPoints::default(),
+                coordinates: Vec::new(),
+            };
+            while let Some(i) = stack.pop() {
+                let (x, y) = (i % width, i / width);
+                blob.add(self, x, y);
+                for (dx, dy) in neighbours {
+                    let (nx, ny) = (x as isize + dx, y as isize + dy);
+                    if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+                        continue;
+                    }
+                    let n = ny as usize * width + nx as usize;
+                    if matching[n] && !visited[n] {
+                        visited[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+            blobs.push(blob);
+        }
+
+        if let Some(distance) = opts.merge_vertical_distance {
+            merge_vertically_stacked(&mut blobs, distance);
+        }
+        blobs.into_iter().map(|blob| blob.into_blob(self)).collect()
+    }
+}
+
+impl PartialBlob {
+    fn add(&mut self, view: &PixelsView<u8>, x: usize, y: usize) {
+        let (abs_x, abs_y) = (view.x() + x, view.y() + y);
+        let index = abs_y * view.stride() + abs_x * 4;
+        self.cardinal_points
+            .update_if_expands_horizontal(abs_x, index);
+        self.cardinal_points
+            .update_if_expands_vertical(abs_y, index);
+        self.coordinates.push((x, y));
+    }
+
+    /// Top leftmost pixel (y, x), to keep the blobs in scanning order
+    fn first(&self) -> (usize, usize) {
+        self.coordinates
+            .iter()
+            .map(|(x, y)| (*y, *x))
+            .min()
+            .unwrap()
+    }
+
+    fn into_blob(self, view: &PixelsView<u8>) -> Blob {
+        let cp = self.cardinal_points;
+        // both at least 1, every blob has at least a pixel
+        let (width, height) = (cp.width().unwrap(), cp.height().unwrap());
+        let invisible = [
+            BGRA_INVISIBLE_PIXEL.0,
+            BGRA_INVISIBLE_PIXEL.1,
+            BGRA_INVISIBLE_PIXEL.2,
+            BGRA_INVISIBLE_PIXEL.3,
+        ];
+        let mut bytes = invisible.repeat(width * height);
+        let (mut sum_x, mut sum_y) = (0.0, 0.0);
+        for (x, y) in &self.coordinates {
+            let (abs_x, abs_y) = (view.x() + x, view.y() + y);
+            sum_x += abs_x as f32;
+            sum_y += abs_y as f32;
+            let i = ((abs_y - cp.top_y) * width + abs_x - cp.left_x) * 4;
+            bytes[i..i + 4].copy_from_slice(view.get(*x, *y).unwrap());
+        }
+        let area = self.coordinates.len();
+        Blob {
+            cardinal_points: cp,
+            area,
+            centroid: (sum_x / area as f32, sum_y / area as f32),
+            pixels: PixelsCollection::create(width, height, bytes).unwrap(),
+        }
+    }
+}
+
+/// Merges the blobs overlapping horizontally with at most `distance` rows between them, until none are left to merge
+fn merge_vertically_stacked(blobs: &mut Vec<PartialBlob>, distance: usize) {
+    let stacked = |a: &CardinalPoints, b: &CardinalPoints| {
+        let (upper, lower) = if a.top_y <= b.top_y { (a, b) } else { (b, a) };
+        a.left_x <= b.right_x
+            && b.left_x <= a.right_x
+            && lower.top_y.saturating_sub(upper.bottom_y + 1) <= distance
+    };
+    let mut merged_any = true;
+    while merged_any {
+        merged_any = false;
+        // swept from left to right, each blob is compared only with the merged ones reaching its left column,
+        // another pass is needed only for the blobs that grew enough to reach those already passed
+        blobs.sort_by_key(|blob| blob.cardinal_points.left_x);
+        let mut merged: Vec<PartialBlob> = Vec::with_capacity(blobs.len());
+        let mut reaching: Vec<usize> = Vec::new();
+        for blob in blobs.drain(..) {
+            let left_x = blob.cardinal_points.left_x;
+            reaching.retain(|i| merged[*i].cardinal_points.right_x >= left_x);
+            let target = reaching
+                .iter()
+                .find(|i| stacked(&merged[**i].cardinal_points, &blob.cardinal_points));
+            match target {
+                Some(&i) => {
+                    merged[i]
+                        .cardinal_points
+                        .update_if_expands_from_cardinal_points(&blob.cardinal_points);
+                    merged[i].coordinates.extend(blob.coordinates);
+                    merged_any = true;
+                }
+                None => {
+                    reaching.push(merged.len());
+                    merged.push(blob);
+                }
+            }
+        }
+        *blobs = merged;
+    }
+    blobs.sort_by_key(|blob| blob.first());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bgra_management::bytes_matchers;
+
+    /// Pixels from a drawing, '#' : opaque black, '.' : transparent
+    fn drawing(rows: &[&str]) -> PixelsCollection<u8> {
+        let bytes = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .flat_map(|c| match c {
+                '#' => [0, 0, 0, 255],
+                _ => [0, 0, 0, 0],
+            })
+            .collect();
+        PixelsCollection::<u8>::create(rows[0].len(), rows.len(), bytes).unwrap()
+    }
+
+    #[test]
+    fn connectivity() {
+        let pixels = drawing(&[
+            "#...", //
+            ".#..", //
+            "...#", //
+            "..##", //
+        ]);
+        let eight = pixels.find_blobs(bytes_matchers::visible, BlobOptions::default());
+        assert_eq!(eight.len(), 2);
+        assert_eq!(eight[0].area, 2);
+        assert_eq!(eight[0].centroid, (0.5, 0.5));
+        let cp = eight[1].cardinal_points;
+        assert_eq!((cp.left_x, cp.top_y, cp.right_x, cp.bottom_y), (2, 2, 3, 3));
+        assert_eq!(cp.left_x_index, (3 * 4 + 2) * 4);
+        assert_eq!(
+            eight[1].pixels.bytes,
+            [[0, 0, 0, 0], [0, 0, 0, 255], [0, 0, 0, 255], [0, 0, 0, 255]].concat()
+        );
+
+        let four = pixels.find_blobs(
+            bytes_matchers::visible,
+            BlobOptions {
+                connectivity: Connectivity::Four,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            four.iter().map(|blob| blob.area).collect::<Vec<_>>(),
+            [1, 1, 3]
+        );
+
+        // inside a view the coordinates are still those of the whole pixels
+        let view_blobs = pixels
+            .sub_view(1, 1, 3, 3)
+            .unwrap()
+            .find_blobs(bytes_matchers::visible, BlobOptions::default());
+        assert_eq!(view_blobs.len(), 2);
+        assert_eq!(view_blobs[0].centroid, (1.0, 1.0));
+    }
+
+    #[test]
+    fn vertically_stacked_merging() {
+        let pixels = drawing(&[
+            ".#....#", //
+            "......#", //
+            ".#.....", //
+            ".#....#", //
+            "##....#", //
+        ]);
+        let separate = pixels.find_blobs(bytes_matchers::visible, BlobOptions::default());
+        assert_eq!(separate.len(), 4);
+
+        let merged = pixels.find_blobs(
+            bytes_matchers::visible,
+            BlobOptions {
+                merge_vertical_distance: Some(1),
+                ..Default::default()
+            },
+        );
+        assert_eq!(merged.len(), 2);
+        // the 'j' : its dot, a row of space and its stem
+        let cp = merged[0].cardinal_points;
+        assert_eq!((cp.left_x, cp.top_y, cp.right_x, cp.bottom_y), (0, 0, 1, 4));
+        assert_eq!(merged[0].area, 5);
+        assert_eq!(merged[0].pixels.bytes[4..8], [0, 0, 0, 255]);
+        assert_eq!(merged[1].area, 4);
+
+        let too_far = pixels.find_blobs(
+            bytes_matchers::visible,
+            BlobOptions {
+                merge_vertical_distance: Some(0),
+                ..Default::default()
+            },
+        );
+        assert_eq!(too_far.len(), 4);
+    }
+
+    #[test]
+    fn merging_many_stacked() {
+        // 20 rows of 21 blobs (2 pixels wide, 1 pixel wide for the last one) with a row between them
+        let rows: Vec<String> = (0..40)
+            .map(|y| {
+                if y % 2 == 1 {
+                    ".".repeat(61)
+                } else {
+                    (0..61)
+                        .map(|x| if x % 3 == 2 { '.' } else { '#' })
+                        .collect()
+                }
+            })
+            .collect();
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        let pixels = drawing(&rows);
+        let options = |distance| BlobOptions {
+            merge_vertical_distance: Some(distance),
+            ..Default::default()
+        };
+        assert_eq!(
+            pixels.find_blobs(bytes_matchers::visible, options(0)).len(),
+            20 * 21
+        );
+        let merged = pixels.find_blobs(bytes_matchers::visible, options(1));
+        assert_eq!(merged.len(), 21);
+        assert!(merged
+            .windows(2)
+            .all(|b| b[0].cardinal_points.left_x < b[1].cardinal_points.left_x));
+        assert!(merged
+            .iter()
+            .all(|b| b.pixels.height == 39 && b.area == 20 * b.pixels.width));
+    }
+}
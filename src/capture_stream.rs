@@ -0,0 +1,407 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    clock::{Clock, SystemClock},
+    PixelCasterError, PixelValues, PixelsCollection, Screen, ScreenBackend,
+};
+
+/// How many buffers a CaptureStream keeps for reuse, one for the frame being used and one for the next capture
+const REUSED_BUFFERS: usize = 2;
+
+/// Buffers of the frames that were dropped, ready for the next captures
+type BufferPool<T> = Arc<Mutex<Vec<Vec<T>>>>;
+
+/// Frames captured and skipped so far by a CaptureStream
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CaptureStats {
+    pub captured: u64,
+    /// Frames not captured because their time had already passed when the next one was asked for
+    pub dropped: u64,
+}
+
+/// Frame captured by a CaptureStream, its buffer goes back to the stream to be reused when it's dropped
+pub struct CapturedFrame<T: PixelValues<T> + Copy> {
+    /// Number of the frame since the stream started (0 : the first), the skipped ones leave a gap
+    pub index: u64,
+    /// When the capture started, from the stream's start
+    pub timestamp: Duration,
+    // always Some, until into_pixels takes it
+    pixels: Option<PixelsCollection<T>>,
+    pool: BufferPool<T>,
+}
+
+impl<T: PixelValues<T> + Copy> CapturedFrame<T> {
+    /// Returns the captured pixels of the Screen's set pixels area
+    pub fn pixels(&self) -> &PixelsCollection<T> {
+        self.pixels.as_ref().unwrap()
+    }
+
+    /// Returns the captured pixels, keeping their buffer out of the stream's reuse
+    pub fn into_pixels(mut self) -> PixelsCollection<T> {
+        self.pixels.take().unwrap()
+    }
+}
+
+impl<T: PixelValues<T> + Copy> Drop for CapturedFrame<T> {
+    fn drop(&mut self) {
+        if let Some(pixels) = self.pixels.take() {
+            let mut pool = self.pool.lock().unwrap();
+            if pool.len() < REUSED_BUFFERS {
+                pool.push(pixels.bytes);
+            }
+        }
+    }
+}
+
+/// Captures the Screen's set pixels area at a fixed rate, see Screen::capture_stream.
+/// As an Iterator it waits (on its Clock) for each frame's time and never ends.
+/// With the `stream` feature it's also a futures_core::Stream, which waits (on a clone of its Clock) in a helper thread, one per stream
+pub struct CaptureStream<'a, T: PixelValues<T> + Copy, B: ScreenBackend, C: Clock = SystemClock> {
+    screen: &'a Screen<T, B>,
+    clock: C,
+    period: Duration,
+    start: Duration,
+    next_index: u64,
+    stats: CaptureStats,
+    pool: BufferPool<T>,
+    // started by the first poll_next that has to wait
+    #[cfg(feature = "stream")]
+    timer: Option<Timer>,
+}
+
+impl<T: PixelValues<T> + Copy, B: ScreenBackend> Screen<T, B> {
+    /// Returns an endless Iterator of timestamped captures of the Screen's set pixels area, `fps` per second.
+    /// When the consumer lags, the frames whose time has passed are skipped (and counted in the stream's stats) instead of being captured late.
+    /// Only two buffers are allocated as long as at most one frame is kept at a time.
+    ///
+    /// # Panics
+    ///
+    /// If fps is not positive and finite
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pixel_caster::Screen;
+    ///
+    /// let screen = Screen::<u8>::new(0, 0, 200, 100);
+    /// for frame in screen.capture_stream(30.0).take(90) {
+    ///     let frame = frame.unwrap();
+    ///     println!("{} at {:?}", frame.index, frame.timestamp);
+    /// }
+    /// ```
+    pub fn capture_stream(&self, fps: f64) -> CaptureStream<'_, T, B> {
+        self.capture_stream_with_clock(fps, SystemClock::new())
+    }
+
+    /// As capture_stream, timed by the given Clock (e.g.: a ManualClock to run it deterministically)
+    pub fn capture_stream_with_clock<C: Clock>(
+        &self,
+        fps: f64,
+        clock: C,
+    ) -> CaptureStream<'_, T, B, C> {
+        assert!(
+            fps > 0.0 && fps.is_finite(),
+            "fps must be positive and finite"
+        );
+        CaptureStream {
+            screen: self,
+            period: Duration::from_secs_f64(1.0 / fps),
+            start: clock.now(),
+            clock,
+            next_index: 0,
+            stats: CaptureStats::default(),
+            pool: Arc::new(Mutex::new(Vec::with_capacity(REUSED_BUFFERS))),
+            #[cfg(feature = "stream")]
+            timer: None,
+        }
+    }
+}
+
+impl<T: PixelValues<T> + Copy, B: ScreenBackend, C: Clock> CaptureStream<'_, T, B, C> {
+    /// Returns how many frames were captured and dropped so far
+    pub fn stats(&self) -> CaptureStats {
+        self.stats
+    }
+
+    /// Time of the next frame from the stream's start
+    fn frame_time(&self) -> u128 {
+        self.period.as_nanos().max(1) * self.next_index as u128
+    }
+
+    /// Returns how long to wait for the next frame's time, None if it has come,
+    /// skipping the frames whose time has passed before
+    fn wait_or_skip(&mut self) -> Option<Duration> {
+        let period = self.period.as_nanos().max(1);
+        let elapsed = self.clock.now().saturating_sub(self.start).as_nanos();
+        let frame_time = self.frame_time();
+        if elapsed < frame_time {
+            return Some(Duration::from_nanos((frame_time - elapsed) as u64));
+        }
+        let skipped = ((elapsed - frame_time) / period) as u64;
+        self.next_index += skipped;
+        self.stats.dropped += skipped;
+        None
+    }
+
+    fn capture(&mut self) -> Result<CapturedFrame<T>, PixelCasterError> {
+        let area = self.screen.get_pixels_collection();
+        let (width, height) = (area.width, area.height);
+        let mut bytes = self
+            .pool
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_else(|| <T as PixelValues<T>>::initialize_vec(width, height));
+        let index = self.next_index;
+        self.next_index += 1;
+        let timestamp = self.clock.now().saturating_sub(self.start);
+        if let Err(error) = self.screen.scan_area_onto_vec(&mut bytes) {
+            self.pool.lock().unwrap().push(bytes);
+            return Err(error);
+        }
+        self.stats.captured += 1;
+        Ok(CapturedFrame {
+            index,
+            timestamp,
+            pixels: Some(PixelsCollection::<T>::create(width, height, bytes)?),
+            pool: Arc::clone(&self.pool),
+        })
+    }
+}
+
+impl<T: PixelValues<T> + Copy, B: ScreenBackend, C: Clock> Iterator for CaptureStream<'_, T, B, C> {
+    type Item = Result<CapturedFrame<T>, PixelCasterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(wait) = self.wait_or_skip() {
+            self.clock.sleep(wait);
+            self.wait_or_skip();
+        }
+        Some(self.capture())
+    }
+}
+
+/// What a stream's helper thread waits for: the time (on the stream's Clock) when to wake the task that polled it last
+#[cfg(feature = "stream")]
+#[derive(Default)]
+struct TimerState {
+    deadline: Option<Duration>,
+    waker: Option<std::task::Waker>,
+    stopped: bool,
+}
+
+/// Helper thread of an async CaptureStream, stopped when the stream is dropped
+#[cfg(feature = "stream")]
+struct Timer {
+    state: Arc<(Mutex<TimerState>, std::sync::Condvar)>,
+}
+
+#[cfg(feature = "stream")]
+impl Timer {
+    fn spawn<C: Clock + Send + 'static>(clock: C) -> Timer {
+        let state = Arc::new((Mutex::new(TimerState::default()), std::sync::Condvar::new()));
+        let thread_state = Arc::clone(&state);
+        std::thread::spawn(move || {
+            let (lock, condvar) = &*thread_state;
+            let mut timer = lock.lock().unwrap();
+            while !timer.stopped {
+                let Some(deadline) = timer.deadline else {
+                    timer = condvar.wait(timer).unwrap();
+                    continue;
+                };
+                let wait = deadline.saturating_sub(clock.now());
+                if wait.is_zero() {
+                    timer.deadline = None;
+                    let waker = timer.waker.take();
+                    drop(timer);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                } else {
+                    drop(timer);
+                    clock.sleep(wait);
+                }
+                timer = lock.lock().unwrap();
+            }
+        });
+        Timer { state }
+    }
+
+    /// Makes the thread wake the waker at the deadline. Polling again for the same deadline only replaces the waker
+    fn schedule(&self, deadline: Duration, waker: &std::task::Waker) {
+        let (lock, condvar) = &*self.state;
+        let mut timer = lock.lock().unwrap();
+        if !timer.waker.as_ref().is_some_and(|w| w.will_wake(waker)) {
+            timer.waker = Some(waker.clone());
+        }
+        if timer.deadline != Some(deadline) {
+            timer.deadline = Some(deadline);
+            condvar.notify_one();
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let (lock, condvar) = &*self.state;
+        lock.lock().unwrap().stopped = true;
+        condvar.notify_one();
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<T, B, C> futures_core::Stream for CaptureStream<'_, T, B, C>
+where
+    T: PixelValues<T> + Copy,
+    B: ScreenBackend,
+    C: Clock + Clone + Send + Unpin + 'static,
+{
+    type Item = Result<CapturedFrame<T>, PixelCasterError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        if self.wait_or_skip().is_none() {
+            return std::task::Poll::Ready(Some(self.capture()));
+        }
+        let deadline = self.start + Duration::from_nanos(self.frame_time() as u64);
+        if self.timer.is_none() {
+            self.timer = Some(Timer::spawn(self.clock.clone()));
+        }
+        self.timer.as_ref().unwrap().schedule(deadline, cx.waker());
+        std::task::Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::ManualClock, VirtualScreen};
+
+    fn screen() -> (VirtualScreen, Screen<u8, VirtualScreen>) {
+        let virtual_screen = VirtualScreen::new(4, 4);
+        let screen = Screen::<u8, VirtualScreen>::with_backend(virtual_screen.clone(), 1, 1, 2, 2);
+        (virtual_screen, screen)
+    }
+
+    #[test]
+    fn pacing_and_dropped_frames() {
+        let (virtual_screen, screen) = screen();
+        let clock = ManualClock::new();
+        let mut stream = screen.capture_stream_with_clock(10.0, clock.clone());
+
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!((first.index, first.timestamp), (0, Duration::ZERO));
+        drop(first);
+        virtual_screen.framebuffer().bytes[(5 * 4)..(5 * 4 + 4)].copy_from_slice(&[1, 2, 3, 255]);
+        let second = stream.next().unwrap().unwrap();
+        assert_eq!(
+            (second.index, second.timestamp),
+            (1, Duration::from_millis(100))
+        );
+        assert_eq!(second.pixels().bytes[..4], [1, 2, 3, 255]);
+        assert_eq!(clock.now(), Duration::from_millis(100));
+
+        // the consumer lags for 3.5 frames, the frames of 200 and 300 ms are skipped
+        clock.advance(Duration::from_millis(350));
+        let late = stream.next().unwrap().unwrap();
+        assert_eq!(
+            (late.index, late.timestamp),
+            (4, Duration::from_millis(450))
+        );
+        assert_eq!(
+            stream.stats(),
+            CaptureStats {
+                captured: 3,
+                dropped: 2
+            }
+        );
+        let next = stream.next().unwrap().unwrap();
+        assert_eq!(
+            (next.index, next.timestamp),
+            (5, Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn reused_buffers() {
+        let (_, screen) = screen();
+        let mut stream = screen.capture_stream_with_clock(60.0, ManualClock::new());
+        let first = stream.next().unwrap().unwrap();
+        let second = stream.next().unwrap().unwrap();
+        let buffers = [
+            first.pixels().bytes.as_ptr(),
+            second.pixels().bytes.as_ptr(),
+        ];
+        assert_ne!(buffers[0], buffers[1]);
+        drop(first);
+        drop(second);
+        for frame in stream.by_ref().take(5) {
+            assert!(buffers.contains(&frame.unwrap().pixels().bytes.as_ptr()));
+        }
+
+        let kept = stream.next().unwrap().unwrap().into_pixels();
+        assert_eq!((kept.width, kept.height), (2, 2));
+        assert!(buffers.contains(&kept.bytes.as_ptr()));
+        let frame = stream.next().unwrap().unwrap();
+        assert!(buffers.contains(&frame.pixels().bytes.as_ptr()));
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn async_stream() {
+        use futures_core::Stream;
+        use std::{
+            pin::Pin,
+            sync::Condvar,
+            task::{Context, Poll, Wake, Waker},
+        };
+
+        #[derive(Default)]
+        struct Woken(Mutex<bool>, Condvar);
+
+        impl Wake for Woken {
+            fn wake(self: Arc<Self>) {
+                *self.0.lock().unwrap() = true;
+                self.1.notify_all();
+            }
+        }
+
+        let (_, screen) = screen();
+        let clock = ManualClock::new();
+        let mut stream = screen.capture_stream_with_clock(10.0, clock.clone());
+        let woken = Arc::new(Woken::default());
+        let waker = Waker::from(Arc::clone(&woken));
+        let mut cx = Context::from_waker(&waker);
+        assert!(matches!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(Some(Ok(frame))) if frame.index == 0
+        ));
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+
+        // polled again while waiting: the same deadline, slept on once by the helper thread
+        let frame = loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(frame) => break frame.unwrap().unwrap(),
+                Poll::Pending => {
+                    let mut is_woken = woken.0.lock().unwrap();
+                    while !*is_woken {
+                        is_woken = woken.1.wait(is_woken).unwrap();
+                    }
+                    *is_woken = false;
+                }
+            }
+        };
+        assert_eq!(
+            (frame.index, frame.timestamp),
+            (1, Duration::from_millis(100))
+        );
+        assert_eq!(clock.now(), Duration::from_millis(100));
+        assert_eq!(stream.stats().dropped, 0);
+    }
+}
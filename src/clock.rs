@@ -0,0 +1,71 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Source of the time for what runs at a pace (e.g.: Screen::capture_stream), replaceable to run it deterministically
+pub trait Clock {
+    /// Time elapsed from the clock's origin
+    fn now(&self) -> Duration;
+    /// Waits for the given time to elapse
+    fn sleep(&self, duration: Duration);
+}
+
+/// Clock following the real time, its origin is its creation
+#[derive(Clone, Copy, Debug)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// Clock whose time only moves when it's advanced or slept on (sleeping advances it at once), for tests.
+/// Its clones share the same time
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    /// Creates a clock at its origin
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    /// Moves the time forward
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
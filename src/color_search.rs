@@ -0,0 +1,276 @@
+use crate::{PixelValues, PixelsCollection, PixelsView};
+
+/// Color space in which the distance between two colors is measured
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// Euclidean distance of the Red Green Blue values (0 - ~441.7)
+    Rgb,
+    /// Euclidean distance in CIE L*a*b* (CIE76 ΔE, ~2.3 is the smallest noticeable difference),
+    /// closer to how different the colors look than Rgb
+    Lab,
+}
+
+/// Matches the pixels whose color is within a distance (the tolerance) from a given one.
+/// The fully transparent pixels (Alpha = 0) never match
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorMatcher {
+    bgr: [u8; 3],
+    tolerance: f32,
+    color_space: ColorSpace,
+    // the color in the color space, computed once
+    target: [f32; 3],
+}
+
+impl ColorMatcher {
+    /// Creates a matcher for the given Blue Green Red, matching the colors at most `tolerance` away from it in the ColorSpace
+    pub fn new(b: u8, g: u8, r: u8, tolerance: f32, color_space: ColorSpace) -> ColorMatcher {
+        ColorMatcher {
+            bgr: [b, g, r],
+            tolerance,
+            color_space,
+            target: Self::coordinates(color_space, b, g, r),
+        }
+    }
+
+    /// Creates a matcher for exactly the given Blue Green Red
+    pub fn exact(b: u8, g: u8, r: u8) -> ColorMatcher {
+        Self::new(b, g, r, 0.0, ColorSpace::Rgb)
+    }
+
+    /// Blue Green Red of the color to match
+    pub fn bgr(&self) -> [u8; 3] {
+        self.bgr
+    }
+
+    /// Returns the distance of the given color from the one to match, in the matcher's ColorSpace
+    pub fn distance(&self, b: u8, g: u8, r: u8) -> f32 {
+        let [x, y, z] = Self::coordinates(self.color_space, b, g, r);
+        ((x - self.target[0]).powi(2) + (y - self.target[1]).powi(2) + (z - self.target[2]).powi(2))
+            .sqrt()
+    }
+
+    /// Returns whether the BGRA is visible and within the tolerance from the color to match
+    pub fn matches(&self, b: u8, g: u8, r: u8, a: u8) -> bool {
+        a > 0 && ([b, g, r] == self.bgr || self.distance(b, g, r) <= self.tolerance)
+    }
+
+    fn coordinates(color_space: ColorSpace, b: u8, g: u8, r: u8) -> [f32; 3] {
+        match color_space {
+            ColorSpace::Rgb => [r as f32, g as f32, b as f32],
+            ColorSpace::Lab => srgb_to_lab(r, g, b),
+        }
+    }
+}
+
+/// Converts an sRGB color to CIE L*a*b* (D65 white point)
+fn srgb_to_lab(r: u8, g: u8, b: u8) -> [f32; 3] {
+    let linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+    // XYZ relative to the D65 white point
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    let f = |t: f32| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+impl<T: PixelValues<T>> PixelsView<'_, T> {
+    /// Returns the coordinates (relative to the whole PixelsCollection) of every pixel of the view matching the color, from the top left
+    pub fn find_color(&self, matcher: &ColorMatcher) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        self.for_each_matching(matcher, |x, y| {
+            found.push((x, y));
+            true
+        });
+        found
+    }
+
+    /// Returns the coordinates (relative to the whole PixelsCollection) of the first pixel of the view, from the top left, matching the color
+    pub fn find_first_color(&self, matcher: &ColorMatcher) -> Option<(usize, usize)> {
+        let mut first = None;
+        self.for_each_matching(matcher, |x, y| {
+            first = Some((x, y));
+            false
+        });
+        first
+    }
+
+    /// Returns the coordinates (relative to the whole PixelsCollection) of the pixel of the view matching the color that is the closest to x, y
+    /// (the first from the top left among those as close)
+    pub fn find_nearest_color(
+        &self,
+        matcher: &ColorMatcher,
+        x: usize,
+        y: usize,
+    ) -> Option<(usize, usize)> {
+        let mut nearest: Option<((usize, usize), u64)> = None;
+        self.for_each_matching(matcher, |px, py| {
+            let distance = (px.abs_diff(x) as u64).pow(2) + (py.abs_diff(y) as u64).pow(2);
+            if nearest.is_none_or(|(_, d)| distance < d) {
+                nearest = Some(((px, py), distance));
+            }
+            true
+        });
+        nearest.map(|(coordinates, _)| coordinates)
+    }
+
+    /// Returns how many pixels of the view match the color
+    pub fn count_color(&self, matcher: &ColorMatcher) -> usize {
+        let mut count = 0;
+        self.for_each_matching(matcher, |_, _| {
+            count += 1;
+            true
+        });
+        count
+    }
+
+    /// Returns the fraction (0 - 1) of the view's pixels matching the color, 0 if the view is empty
+    pub fn color_fraction(&self, matcher: &ColorMatcher) -> f32 {
+        let pixels = self.width() * self.height();
+        if pixels == 0 {
+            return 0.0;
+        }
+        self.count_color(matcher) as f32 / pixels as f32
+    }
+
+    /// Calls `f` with the coordinates of each matching pixel, from the top left, until it returns false
+    fn for_each_matching(&self, matcher: &ColorMatcher, mut f: impl FnMut(usize, usize) -> bool) {
+        for (y, row) in self.rows().enumerate() {
+            for (x, bgra) in <T>::as_bgra_bytes(row).chunks_exact(4).enumerate() {
+                if matcher.matches(bgra[0], bgra[1], bgra[2], bgra[3])
+                    && !f(self.x() + x, self.y() + y)
+                {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl<T: PixelValues<T>> PixelsCollection<T> {
+    /// Returns the coordinates of every pixel matching the color, from the top left
+    pub fn find_color(&self, matcher: &ColorMatcher) -> Vec<(usize, usize)> {
+        self.view().find_color(matcher)
+    }
+
+    /// Returns the coordinates of the first pixel, from the top left, matching the color
+    pub fn find_first_color(&self, matcher: &ColorMatcher) -> Option<(usize, usize)> {
+        self.view().find_first_color(matcher)
+    }
+
+    /// Returns the coordinates of the pixel matching the color that is the closest to x, y (the first from the top left among those as close)
+    pub fn find_nearest_color(
+        &self,
+        matcher: &ColorMatcher,
+        x: usize,
+        y: usize,
+    ) -> Option<(usize, usize)> {
+        self.view().find_nearest_color(matcher, x, y)
+    }
+
+    /// Returns how many pixels match the color, use sub_view(..).count_color(..) to count those of a region
+    pub fn count_color(&self, matcher: &ColorMatcher) -> usize {
+        self.view().count_color(matcher)
+    }
+
+    /// Returns the fraction (0 - 1) of the pixels matching the color, use sub_view(..).color_fraction(..) for that of a region
+    pub fn color_fraction(&self, matcher: &ColorMatcher) -> f32 {
+        self.view().color_fraction(matcher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3x2 pixels: greens (the third one invisible) in the first row, greys around a green in the second
+    fn pixels() -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(
+            3,
+            2,
+            [
+                [0, 200, 0, 255],
+                [10, 190, 10, 255],
+                [0, 200, 0, 0],
+                [128, 128, 128, 255],
+                [0, 200, 0, 255],
+                [120, 120, 120, 255],
+            ]
+            .concat(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn matchers() {
+        let rgb = ColorMatcher::new(0, 200, 0, 20.0, ColorSpace::Rgb);
+        assert!(rgb.matches(10, 190, 10, 255));
+        assert!(!rgb.matches(0, 200, 0, 0));
+        assert!(!rgb.matches(128, 128, 128, 255));
+        assert!((rgb.distance(10, 190, 10) - 300f32.sqrt()).abs() < 1e-4);
+
+        let lab = ColorMatcher::new(128, 128, 128, 0.0, ColorSpace::Lab);
+        assert!(lab.distance(128, 128, 128) < 1e-4);
+        // white is L 100, black is L 0
+        assert!(
+            (ColorMatcher::new(0, 0, 0, 0.0, ColorSpace::Lab).distance(255, 255, 255) - 100.0)
+                .abs()
+                < 0.1
+        );
+        // the same Rgb distance looks bigger between dark blues than between light greens
+        let dark_blues = ColorMatcher::new(40, 0, 0, 0.0, ColorSpace::Lab).distance(60, 0, 0);
+        let light_greens =
+            ColorMatcher::new(200, 255, 200, 0.0, ColorSpace::Lab).distance(200, 235, 200);
+        assert!(dark_blues > light_greens);
+    }
+
+    #[test]
+    fn searches_and_regions() {
+        let pixels = pixels();
+        let green = ColorMatcher::new(0, 200, 0, 20.0, ColorSpace::Rgb);
+        assert_eq!(pixels.find_color(&green), [(0, 0), (1, 0), (1, 1)]);
+        assert_eq!(pixels.find_first_color(&green), Some((0, 0)));
+        assert_eq!(pixels.find_nearest_color(&green, 2, 1), Some((1, 1)));
+        assert_eq!(pixels.count_color(&green), 3);
+        assert_eq!(pixels.color_fraction(&green), 0.5);
+
+        let region = pixels.sub_view(1, 0, 2, 2).unwrap();
+        assert_eq!(region.find_color(&green), [(1, 0), (1, 1)]);
+        assert_eq!(region.color_fraction(&green), 0.5);
+        assert_eq!(
+            region.find_first_color(&ColorMatcher::exact(120, 120, 120)),
+            Some((2, 1))
+        );
+        assert_eq!(
+            pixels.find_first_color(&ColorMatcher::exact(0, 0, 255)),
+            None
+        );
+
+        let values = PixelsCollection::<u32>::create(
+            3,
+            2,
+            pixels
+                .bytes
+                .chunks_exact(4)
+                .map(|bgra| u32::from_le_bytes(bgra.try_into().unwrap()))
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(values.find_color(&green), pixels.find_color(&green));
+        assert_eq!(values.sub_view(0, 1, 3, 1).unwrap().count_color(&green), 1);
+    }
+}
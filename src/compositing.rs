@@ -0,0 +1,223 @@
+use crate::{PixelsCollection, PixelsView};
+
+/// How the source pixels are combined with the destination ones by PixelsCollection::composite.
+/// Apart from SourceOverPremultiplied, both the source and the destination are in straight (not premultiplied) BGRA
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    /// The source is drawn over the destination, blending them by the source's Alpha
+    SourceOver,
+    /// As SourceOver, but both the source and the destination have their Blue Green Red premultiplied by their Alpha (as GDI's AlphaBlend wants them)
+    SourceOverPremultiplied,
+    /// Multiplies the colors, the result is always at least as dark as either of them
+    Multiply,
+    /// Inverse of Multiply, the result is always at least as light as either of them
+    Screen,
+    /// Multiplies the destination's dark colors and screens its light ones, keeping its highlights and shadows
+    Overlay,
+    /// Sums the colors, capping them at 255
+    Add,
+    /// Subtracts the darker color from the lighter one
+    Difference,
+    /// Keeps the destination only where the source is visible, scaling its Alpha by the source's one
+    DestinationIn,
+    /// Keeps the destination only where the source is not visible, scaling its Alpha by the source's inverse one
+    DestinationOut,
+}
+
+impl PixelsCollection<u8> {
+    /// Draws the `src` pixels onto these ones, with the `src` upper left corner placed at x, y, combining them as defined by the BlendMode.
+    /// `opacity` scales the source's Alpha (255 : as it is, 0 : the destination is left untouched).
+    /// The parts of `src` that end up outside of these pixels (e.g.: with negative x, y) are clipped away
+    pub fn composite(&mut self, src: PixelsView<u8>, x: i32, y: i32, mode: BlendMode, opacity: u8) {
+        let x0 = (x as i64).max(0);
+        let y0 = (y as i64).max(0);
+        let x1 = (x as i64 + src.width() as i64).min(self.width as i64);
+        let y1 = (y as i64 + src.height() as i64).min(self.height as i64);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+        let (width, height) = ((x1 - x0) as usize, (y1 - y0) as usize);
+
+        // both areas are inside their pixels, as they have just been clipped
+        let src = src
+            .sub_view(
+                (x0 - x as i64) as usize,
+                (y0 - y as i64) as usize,
+                width,
+                height,
+            )
+            .unwrap();
+        let mut dst = self
+            .sub_view_mut(x0 as usize, y0 as usize, width, height)
+            .unwrap();
+        for (src_row, dst_row) in src.rows().zip(dst.rows_mut()) {
+            for (s, d) in src_row.chunks_exact(4).zip(dst_row.chunks_exact_mut(4)) {
+                blend_pixel(mode, s, d, opacity);
+            }
+        }
+    }
+}
+
+/// Blends the premultiplied `src` pixel over `dst` as AlphaBlend does with AC_SRC_OVER:
+/// dst = src + (1 - src_alpha * source_constant_alpha) * dst, where src is first scaled by source_constant_alpha
+pub(crate) fn blend_premultiplied(dst: &mut [u8], src: &[u8], source_constant_alpha: u8) {
+    let sca = source_constant_alpha as u32;
+    let src_a = (src[3] as u32 * sca + 127) / 255;
+    for c in 0..4 {
+        let s = (src[c] as u32 * sca + 127) / 255;
+        dst[c] = (s + (dst[c] as u32 * (255 - src_a) + 127) / 255).min(255) as u8;
+    }
+}
+
+pub(crate) fn blend_pixel(mode: BlendMode, src: &[u8], dst: &mut [u8], opacity: u8) {
+    let src_a = src[3] as f32 / 255.0 * (opacity as f32 / 255.0);
+    let dst_a = dst[3] as f32 / 255.0;
+    let to_u8 = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    let blend_function: fn(f32, f32) -> f32 = match mode {
+        BlendMode::SourceOverPremultiplied => return blend_premultiplied(dst, src, opacity),
+        BlendMode::DestinationIn => {
+            dst[3] = to_u8(dst_a * src_a);
+            return;
+        }
+        BlendMode::DestinationOut => {
+            dst[3] = to_u8(dst_a * (1.0 - src_a));
+            return;
+        }
+        BlendMode::SourceOver => |_, s| s,
+        BlendMode::Multiply => |d, s| d * s,
+        BlendMode::Screen => |d, s| d + s - d * s,
+        BlendMode::Overlay => |d, s| {
+            if d <= 0.5 {
+                2.0 * d * s
+            } else {
+                1.0 - 2.0 * (1.0 - d) * (1.0 - s)
+            }
+        },
+        BlendMode::Add => |d, s| (d + s).min(1.0),
+        BlendMode::Difference => |d, s| (d - s).abs(),
+    };
+
+    // https://www.w3.org/TR/compositing-1/#generalformula
+    // the source, mixed with the blended color where the destination is visible, is composited over the destination
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        dst.fill(0);
+        return;
+    }
+    for c in 0..3 {
+        let s = src[c] as f32 / 255.0;
+        let d = dst[c] as f32 / 255.0;
+        let mixed = (1.0 - dst_a) * s + dst_a * blend_function(d, s);
+        dst[c] = to_u8((src_a * mixed + dst_a * d * (1.0 - src_a)) / out_a);
+    }
+    dst[3] = to_u8(out_a);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_pixel(bgra: [u8; 4]) -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(1, 1, bgra.to_vec()).unwrap()
+    }
+
+    fn composited(dst: [u8; 4], src: [u8; 4], mode: BlendMode, opacity: u8) -> Vec<u8> {
+        let mut dst = single_pixel(dst);
+        dst.composite(single_pixel(src).view(), 0, 0, mode, opacity);
+        dst.bytes
+    }
+
+    #[test]
+    fn blend_modes() {
+        let dst = [200, 100, 50, 255];
+        let src = [100, 200, 0, 255];
+        assert_eq!(composited(dst, src, BlendMode::SourceOver, 255), src);
+        assert_eq!(
+            composited(dst, [100, 200, 0, 128], BlendMode::SourceOver, 255),
+            [150, 150, 25, 255]
+        );
+        assert_eq!(
+            composited(dst, src, BlendMode::SourceOver, 0),
+            [200, 100, 50, 255]
+        );
+        // over a fully transparent destination the straight colors are kept
+        assert_eq!(
+            composited([0, 0, 0, 0], [100, 200, 0, 128], BlendMode::SourceOver, 255),
+            [100, 200, 0, 128]
+        );
+        assert_eq!(
+            composited(
+                [0, 0, 100, 255],
+                [50, 50, 0, 128],
+                BlendMode::SourceOverPremultiplied,
+                255
+            ),
+            [50, 50, 50, 255]
+        );
+        assert_eq!(
+            composited(dst, src, BlendMode::Multiply, 255),
+            [78, 78, 0, 255]
+        );
+        assert_eq!(
+            composited(dst, src, BlendMode::Screen, 255),
+            [222, 222, 50, 255]
+        );
+        assert_eq!(
+            composited(dst, src, BlendMode::Overlay, 255),
+            [188, 157, 0, 255]
+        );
+        assert_eq!(
+            composited(dst, src, BlendMode::Add, 255),
+            [255, 255, 50, 255]
+        );
+        assert_eq!(
+            composited(dst, src, BlendMode::Difference, 255),
+            [100, 100, 50, 255]
+        );
+        assert_eq!(
+            composited(dst, [0, 0, 0, 51], BlendMode::DestinationIn, 255),
+            [200, 100, 50, 51]
+        );
+        assert_eq!(
+            composited(dst, [0, 0, 0, 51], BlendMode::DestinationOut, 255),
+            [200, 100, 50, 204]
+        );
+        // the blend modes only apply where the destination is visible
+        assert_eq!(
+            composited([200, 100, 50, 0], src, BlendMode::Multiply, 255),
+            src
+        );
+    }
+
+    #[test]
+    fn clipping() {
+        let mut dst = PixelsCollection::<u8>::create(3, 2, vec![0; 3 * 2 * 4]).unwrap();
+        let src_bytes: Vec<u8> = (1..=4).flat_map(|v| [v, v, v, 255]).collect();
+        let src = PixelsCollection::<u8>::create(2, 2, src_bytes).unwrap();
+
+        dst.composite(src.view(), -1, -1, BlendMode::SourceOver, 255);
+        assert_eq!(dst.bytes[0..4], [4, 4, 4, 255]);
+        assert!(dst.bytes[4..].iter().all(|&b| b == 0));
+
+        dst.composite(src.view(), 2, 1, BlendMode::SourceOver, 255);
+        assert_eq!(dst.bytes[20..24], [1, 1, 1, 255]);
+        assert!(dst.bytes[4..20].iter().all(|&b| b == 0));
+
+        // entirely outside
+        let before = dst.bytes.clone();
+        dst.composite(src.view(), 3, 0, BlendMode::SourceOver, 255);
+        dst.composite(src.view(), i32::MIN, i32::MAX, BlendMode::SourceOver, 255);
+        assert_eq!(dst.bytes, before);
+
+        dst.composite(
+            src.sub_view(1, 0, 1, 2).unwrap(),
+            1,
+            0,
+            BlendMode::SourceOver,
+            255,
+        );
+        assert_eq!(dst.bytes[4..8], [2, 2, 2, 255]);
+        assert_eq!(dst.bytes[16..20], [4, 4, 4, 255]);
+    }
+}
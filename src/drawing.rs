@@ -0,0 +1,683 @@
+use crate::{bgra_management::BGRA, compositing, BlendMode, PixelsCollection};
+
+/// Point as x, y. The pixel at column i and row j covers from i, j to i + 1, j + 1, its center is i + 0.5, j + 0.5
+pub type Point = (f32, f32);
+
+/// Which points are inside a polygon whose edges cross each other
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside if a ray from the point crosses the edges an odd number of times (the overlapping parts of a star are holes)
+    EvenOdd,
+    /// Inside if the edges wind around the point (the overlapping parts of a star are filled)
+    NonZero,
+}
+
+/// Width and anti-aliasing of the drawn lines and outlines
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    /// Blends the edges by how much of each pixel they cover, instead of drawing only the pixels whose center they cover
+    pub anti_aliased: bool,
+}
+
+impl Stroke {
+    pub fn new(width: f32, anti_aliased: bool) -> Stroke {
+        Stroke {
+            width,
+            anti_aliased,
+        }
+    }
+}
+
+impl Default for Stroke {
+    /// 1 pixel wide, anti-aliased
+    fn default() -> Stroke {
+        Stroke::new(1.0, true)
+    }
+}
+
+/// Samples per pixel side when anti-aliasing a shape
+const SUBSAMPLES: usize = 4;
+
+/// The drawing functions blend the color over the pixels (by its Alpha, and by the covered fraction of the pixels when anti-aliasing),
+/// the parts of the shapes outside of the pixels are clipped away
+impl PixelsCollection<u8> {
+    /// Draws a line. Up to 1 pixel wide, it's drawn with Xiaolin Wu's algorithm when anti-aliased, with Bresenham's otherwise.
+    /// Wider lines have round ends
+    pub fn draw_line(&mut self, from: Point, to: Point, bgra: BGRA<u8>, stroke: Stroke) {
+        self.draw_polyline(&[from, to], false, bgra, stroke)
+    }
+
+    /// Draws the lines joining the points, and the last one to the first one if `closed` (e.g.: a polygon's outline).
+    /// Lines wider than 1 pixel have round ends and joints, and the overlapping parts are blended once,
+    /// the thinner ones blend the pixels of their joints once too
+    pub fn draw_polyline(
+        &mut self,
+        points: &[Point],
+        closed: bool,
+        bgra: BGRA<u8>,
+        stroke: Stroke,
+    ) {
+        if stroke.width <= 0.0 || points.is_empty() {
+            return;
+        }
+        let closed = closed && points.len() > 2;
+        let mut segments: Vec<(Point, Point)> = points.windows(2).map(|p| (p[0], p[1])).collect();
+        if closed {
+            segments.push((points[points.len() - 1], points[0]));
+        }
+        if points.len() == 1 {
+            segments.push((points[0], points[0]));
+        }
+        if stroke.width <= 1.0 {
+            for (i, (from, to)) in segments.into_iter().enumerate() {
+                // the joint with the previous segment was drawn as its end
+                let skip_from = i > 0 || closed;
+                if stroke.anti_aliased {
+                    self.wu_line(from, to, bgra, skip_from);
+                } else {
+                    self.bresenham_line(from, to, bgra, skip_from);
+                }
+            }
+            return;
+        }
+
+        let half_width = stroke.width / 2.0;
+        let bounds = points.iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(x0, y0, x1, y1), (x, y)| (x0.min(*x), y0.min(*y), x1.max(*x), y1.max(*y)),
+        );
+        self.fill_shape(
+            (
+                bounds.0 - half_width,
+                bounds.1 - half_width,
+                bounds.2 + half_width,
+                bounds.3 + half_width,
+            ),
+            bgra,
+            stroke.anti_aliased,
+            |x, y| {
+                segments
+                    .iter()
+                    .any(|(a, b)| distance_to_segment((x, y), *a, *b) <= half_width)
+            },
+        );
+    }
+
+    /// Draws the outline of the rectangle (with rounded corners if corner_radius > 0), inside of it
+    pub fn draw_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        corner_radius: f32,
+        bgra: BGRA<u8>,
+        stroke: Stroke,
+    ) {
+        let w = stroke.width;
+        self.fill_shape(
+            (x, y, x + width, y + height),
+            bgra,
+            stroke.anti_aliased,
+            |px, py| {
+                in_rounded_rect(px, py, x, y, width, height, corner_radius)
+                    && !in_rounded_rect(
+                        px,
+                        py,
+                        x + w,
+                        y + w,
+                        width - 2.0 * w,
+                        height - 2.0 * w,
+                        corner_radius - w,
+                    )
+            },
+        );
+    }
+
+    /// Fills the rectangle (with rounded corners if corner_radius > 0)
+    pub fn fill_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        corner_radius: f32,
+        bgra: BGRA<u8>,
+        anti_aliased: bool,
+    ) {
+        self.fill_shape(
+            (x, y, x + width, y + height),
+            bgra,
+            anti_aliased,
+            |px, py| in_rounded_rect(px, py, x, y, width, height, corner_radius),
+        );
+    }
+
+    /// Draws the outline of the ellipse (a circle if the radiuses are the same), inside of it
+    pub fn draw_ellipse(
+        &mut self,
+        center: Point,
+        radius_x: f32,
+        radius_y: f32,
+        bgra: BGRA<u8>,
+        stroke: Stroke,
+    ) {
+        let w = stroke.width;
+        self.fill_shape(
+            (
+                center.0 - radius_x,
+                center.1 - radius_y,
+                center.0 + radius_x,
+                center.1 + radius_y,
+            ),
+            bgra,
+            stroke.anti_aliased,
+            |x, y| {
+                in_ellipse(x, y, center, radius_x, radius_y)
+                    && !in_ellipse(x, y, center, radius_x - w, radius_y - w)
+            },
+        );
+    }
+
+    /// Fills the ellipse (a circle if the radiuses are the same)
+    pub fn fill_ellipse(
+        &mut self,
+        center: Point,
+        radius_x: f32,
+        radius_y: f32,
+        bgra: BGRA<u8>,
+        anti_aliased: bool,
+    ) {
+        self.fill_shape(
+            (
+                center.0 - radius_x,
+                center.1 - radius_y,
+                center.0 + radius_x,
+                center.1 + radius_y,
+            ),
+            bgra,
+            anti_aliased,
+            |x, y| in_ellipse(x, y, center, radius_x, radius_y),
+        );
+    }
+
+    /// Fills the polygon with the given vertexes, deciding by the FillRule where its edges cross each other.
+    /// Use draw_polyline with `closed` to draw its outline
+    pub fn fill_polygon(
+        &mut self,
+        points: &[Point],
+        fill_rule: FillRule,
+        bgra: BGRA<u8>,
+        anti_aliased: bool,
+    ) {
+        if points.len() < 3 {
+            return;
+        }
+        let bounds = points.iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(x0, y0, x1, y1), (x, y)| (x0.min(*x), y0.min(*y), x1.max(*x), y1.max(*y)),
+        );
+        self.fill_shape(bounds, bgra, anti_aliased, |x, y| {
+            let (mut winding, mut crossings) = (0, 0);
+            for (i, a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                if (a.1 <= y) != (b.1 <= y) {
+                    let crossing_x = a.0 + (y - a.1) / (b.1 - a.1) * (b.0 - a.0);
+                    if crossing_x > x {
+                        crossings += 1;
+                        winding += if b.1 > a.1 { 1 } else { -1 };
+                    }
+                }
+            }
+            match fill_rule {
+                FillRule::EvenOdd => crossings % 2 == 1,
+                FillRule::NonZero => winding != 0,
+            }
+        });
+    }
+
+    /// Draws the quadratic Bézier curve from p0 to p2, pulled towards the control point p1
+    pub fn draw_quadratic_bezier(
+        &mut self,
+        p0: Point,
+        p1: Point,
+        p2: Point,
+        bgra: BGRA<u8>,
+        stroke: Stroke,
+    ) {
+        let points = flatten(&[p0, p1, p2], |t| {
+            let u = 1.0 - t;
+            let (a, b, c) = (u * u, 2.0 * u * t, t * t);
+            (
+                a * p0.0 + b * p1.0 + c * p2.0,
+                a * p0.1 + b * p1.1 + c * p2.1,
+            )
+        });
+        self.draw_polyline(&points, false, bgra, stroke);
+    }
+
+    /// Draws the cubic Bézier curve from p0 to p3, pulled towards the control points p1 and p2
+    pub fn draw_cubic_bezier(
+        &mut self,
+        p0: Point,
+        p1: Point,
+        p2: Point,
+        p3: Point,
+        bgra: BGRA<u8>,
+        stroke: Stroke,
+    ) {
+        let points = flatten(&[p0, p1, p2, p3], |t| {
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            (
+                a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+                a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+            )
+        });
+        self.draw_polyline(&points, false, bgra, stroke);
+    }
+
+    /// Blends the color over the pixel at x, y, by the fraction (0 - 1) of it that is covered, if it's inside the pixels
+    fn blend_coverage(&mut self, x: i64, y: i64, bgra: BGRA<u8>, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 || coverage <= 0.0 {
+            return;
+        }
+        let i = (y as usize * self.width + x as usize) * 4;
+        compositing::blend_pixel(
+            BlendMode::SourceOver,
+            &[bgra.0, bgra.1, bgra.2, bgra.3],
+            &mut self.bytes[i..i + 4],
+            (coverage.min(1.0) * 255.0).round() as u8,
+        );
+    }
+
+    /// Blends the color over the pixels inside the bounds (x0, y0, x1, y1) by how many of their samples are inside the shape,
+    /// a single one (their center) if not anti-aliased
+    fn fill_shape(
+        &mut self,
+        bounds: (f32, f32, f32, f32),
+        bgra: BGRA<u8>,
+        anti_aliased: bool,
+        inside: impl Fn(f32, f32) -> bool,
+    ) {
+        let samples = if anti_aliased { SUBSAMPLES } else { 1 };
+        let clip = |v: f32, max: usize| (v.max(0.0) as usize).min(max);
+        let (x0, x1) = (
+            clip(bounds.0.floor(), self.width),
+            clip(bounds.2.ceil(), self.width),
+        );
+        let (y0, y1) = (
+            clip(bounds.1.floor(), self.height),
+            clip(bounds.3.ceil(), self.height),
+        );
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let mut covered = 0;
+                for sy in 0..samples {
+                    for sx in 0..samples {
+                        let px = x as f32 + (sx as f32 + 0.5) / samples as f32;
+                        let py = y as f32 + (sy as f32 + 0.5) / samples as f32;
+                        if inside(px, py) {
+                            covered += 1;
+                        }
+                    }
+                }
+                self.blend_coverage(
+                    x as i64,
+                    y as i64,
+                    bgra,
+                    covered as f32 / (samples * samples) as f32,
+                );
+            }
+        }
+    }
+
+    /// Bounds the thin lines are clipped to before being walked, a little larger than the pixels
+    /// so that their visible part is drawn as if they weren't clipped
+    fn line_clip_bounds(&self) -> (f64, f64, f64, f64) {
+        (
+            -2.0,
+            -2.0,
+            self.width as f64 + 2.0,
+            self.height as f64 + 2.0,
+        )
+    }
+
+    fn bresenham_line(&mut self, from: Point, to: Point, bgra: BGRA<u8>, skip_from: bool) {
+        let Some((from, to)) = clip_segment(from, to, self.line_clip_bounds()) else {
+            return;
+        };
+        let (mut x, mut y) = (from.0.floor() as i64, from.1.floor() as i64);
+        let (x1, y1) = (to.0.floor() as i64, to.1.floor() as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (step_x, step_y) = (if x < x1 { 1 } else { -1 }, if y < y1 { 1 } else { -1 });
+        let mut error = dx + dy;
+        let mut skip = skip_from;
+        loop {
+            if !skip {
+                self.blend_coverage(x, y, bgra, 1.0);
+            }
+            skip = false;
+            if x == x1 && y == y1 {
+                return;
+            }
+            let e2 = 2 * error;
+            if e2 >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if e2 <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    fn wu_line(&mut self, from: Point, to: Point, bgra: BGRA<u8>, skip_from: bool) {
+        let Some((from, to)) = clip_segment(from, to, self.line_clip_bounds()) else {
+            return;
+        };
+        // the algorithm places the pixels' centers on the integer coordinates
+        let (mut x0, mut y0, mut x1, mut y1) = (from.0 - 0.5, from.1 - 0.5, to.0 - 0.5, to.1 - 0.5);
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        if steep {
+            (x0, y0, x1, y1) = (y0, x0, y1, x1);
+        }
+        let (mut skip_start, mut skip_end) = (skip_from, false);
+        if x0 > x1 {
+            (x0, y0, x1, y1) = (x1, y1, x0, y0);
+            (skip_start, skip_end) = (skip_end, skip_start);
+        }
+        let gradient = if x1 - x0 == 0.0 {
+            1.0
+        } else {
+            (y1 - y0) / (x1 - x0)
+        };
+        let fract = |v: f32| v - v.floor();
+        let mut plot = |x: f32, y: f32, coverage: f32| {
+            let (x, y) = (x as i64, y as i64);
+            if steep {
+                self.blend_coverage(y, x, bgra, coverage);
+            } else {
+                self.blend_coverage(x, y, bgra, coverage);
+            }
+        };
+
+        let x_start = x0.round();
+        let y_end = y0 + gradient * (x_start - x0);
+        let gap = 1.0 - fract(x0 + 0.5);
+        if !skip_start {
+            plot(x_start, y_end.floor(), (1.0 - fract(y_end)) * gap);
+            plot(x_start, y_end.floor() + 1.0, fract(y_end) * gap);
+        }
+        let mut y = y_end + gradient;
+
+        let x_end = x1.round();
+        let y_end = y1 + gradient * (x_end - x1);
+        let gap = fract(x1 + 0.5);
+        if !skip_end {
+            plot(x_end, y_end.floor(), (1.0 - fract(y_end)) * gap);
+            plot(x_end, y_end.floor() + 1.0, fract(y_end) * gap);
+        }
+
+        let mut x = x_start + 1.0;
+        while x < x_end {
+            plot(x, y.floor(), 1.0 - fract(y));
+            plot(x, y.floor() + 1.0, fract(y));
+            y += gradient;
+            x += 1.0;
+        }
+    }
+}
+
+fn in_rounded_rect(px: f32, py: f32, x: f32, y: f32, width: f32, height: f32, radius: f32) -> bool {
+    if width <= 0.0 || height <= 0.0 || px < x || py < y || px >= x + width || py >= y + height {
+        return false;
+    }
+    let radius = radius.clamp(0.0, width.min(height) / 2.0);
+    let cx = px.clamp(x + radius, x + width - radius);
+    let cy = py.clamp(y + radius, y + height - radius);
+    (px - cx).powi(2) + (py - cy).powi(2) <= radius * radius
+}
+
+fn in_ellipse(x: f32, y: f32, center: Point, radius_x: f32, radius_y: f32) -> bool {
+    radius_x > 0.0
+        && radius_y > 0.0
+        && ((x - center.0) / radius_x).powi(2) + ((y - center.1) / radius_y).powi(2) <= 1.0
+}
+
+/// Returns the part of the segment inside the bounds (x0, y0, x1, y1) with Liang–Barsky's algorithm,
+/// None if there's none or if a coordinate is not finite
+fn clip_segment(from: Point, to: Point, bounds: (f64, f64, f64, f64)) -> Option<(Point, Point)> {
+    if ![from.0, from.1, to.0, to.1].iter().all(|v| v.is_finite()) {
+        return None;
+    }
+    // in f64, as the distance between two finite f32 can overflow them
+    let (x, y) = (from.0 as f64, from.1 as f64);
+    let (dx, dy) = (to.0 as f64 - x, to.1 as f64 - y);
+    // where the segment enters and leaves the bounds, with the edge (left, right, top, bottom) crossed there
+    let (mut enter, mut leave) = ((0.0, None), (1.0, None));
+    for (edge, (p, q)) in [
+        (-dx, x - bounds.0),
+        (dx, bounds.2 - x),
+        (-dy, y - bounds.1),
+        (dy, bounds.3 - y),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if p == 0.0 {
+            // parallel to this edge, and outside of it
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 && q / p > enter.0 {
+            enter = (q / p, Some(edge));
+        } else if p > 0.0 && q / p < leave.0 {
+            leave = (q / p, Some(edge));
+        }
+        if enter.0 > leave.0 {
+            return None;
+        }
+    }
+    // the crossed edge's coordinate is set exactly, far endpoints would make x + t * dx imprecise
+    let point_at = |edge: Option<usize>, point: Point| match edge {
+        Some(edge @ 0..=1) => {
+            let bound = [bounds.0, bounds.2][edge];
+            (bound as f32, (y + (bound - x) * dy / dx) as f32)
+        }
+        Some(edge) => {
+            let bound = [bounds.1, bounds.3][edge - 2];
+            ((x + (bound - y) * dx / dy) as f32, bound as f32)
+        }
+        None => point,
+    };
+    Some((point_at(enter.1, from), point_at(leave.1, to)))
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    ((p.0 - a.0 - t * dx).powi(2) + (p.1 - a.1 - t * dy).powi(2)).sqrt()
+}
+
+/// Returns the points of a curve, one about every 2 pixels of its control points' polygon
+fn flatten(control_points: &[Point], point_at: impl Fn(f32) -> Point) -> Vec<Point> {
+    let length: f32 = control_points
+        .windows(2)
+        .map(|p| ((p[1].0 - p[0].0).powi(2) + (p[1].1 - p[0].1).powi(2)).sqrt())
+        .sum();
+    let segments = ((length / 2.0).ceil() as usize).clamp(1, 1024);
+    (0..=segments)
+        .map(|i| point_at(i as f32 / segments as f32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: BGRA<u8> = BGRA(0, 0, 255, 255);
+
+    fn canvas(width: usize, height: usize) -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(width, height, vec![0; width * height * 4]).unwrap()
+    }
+
+    /// Alpha of each pixel, row by row
+    fn alphas(pixels: &PixelsCollection<u8>) -> Vec<Vec<u8>> {
+        pixels
+            .bytes
+            .chunks_exact(pixels.width * 4)
+            .map(|row| row.chunks_exact(4).map(|bgra| bgra[3]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn lines() {
+        let mut pixels = canvas(5, 5);
+        pixels.draw_line((0.5, 0.5), (4.5, 4.5), RED, Stroke::new(1.0, false));
+        assert!((0..5).all(|i| pixels.view().get(i, i).unwrap() == [0, 0, 255, 255]));
+        assert_eq!(
+            alphas(&pixels).concat().iter().filter(|a| **a > 0).count(),
+            5
+        );
+
+        // anti-aliased, on the pixels' centers it covers them entirely, between two rows it covers half of both
+        let mut pixels = canvas(5, 5);
+        pixels.draw_line((0.0, 2.5), (5.0, 2.5), RED, Stroke::default());
+        assert_eq!(alphas(&pixels)[2], [255; 5]);
+        let mut pixels = canvas(5, 5);
+        pixels.draw_line((0.0, 2.0), (5.0, 2.0), RED, Stroke::default());
+        assert_eq!(alphas(&pixels)[1][1..4], [128; 3]);
+        assert_eq!(alphas(&pixels)[2][1..4], [128; 3]);
+        assert_eq!(pixels.view().get(2, 1).unwrap()[2], 255);
+
+        // wide, with round ends, clipped
+        let mut pixels = canvas(7, 5);
+        pixels.draw_line((2.0, 2.5), (9.0, 2.5), RED, Stroke::new(3.0, false));
+        assert_eq!(
+            alphas(&pixels),
+            [
+                vec![0, 0, 0, 0, 0, 0, 0],
+                vec![0, 255, 255, 255, 255, 255, 255],
+                vec![255, 255, 255, 255, 255, 255, 255],
+                vec![0, 255, 255, 255, 255, 255, 255],
+                vec![0, 0, 0, 0, 0, 0, 0],
+            ]
+        );
+
+        let mut pixels = canvas(9, 9);
+        pixels.draw_cubic_bezier(
+            (0.5, 8.5),
+            (0.5, 0.5),
+            (8.5, 0.5),
+            (8.5, 8.5),
+            RED,
+            Stroke::default(),
+        );
+        assert!(pixels.view().get(0, 8).unwrap()[3] > 0 && pixels.view().get(8, 8).unwrap()[3] > 0);
+        assert_eq!(pixels.view().get(4, 4).unwrap()[3], 0);
+    }
+
+    #[test]
+    fn shapes() {
+        let mut pixels = canvas(5, 4);
+        pixels.draw_rect(0.0, 0.0, 5.0, 4.0, 0.0, RED, Stroke::new(1.0, false));
+        assert_eq!(
+            alphas(&pixels),
+            [
+                vec![255, 255, 255, 255, 255],
+                vec![255, 0, 0, 0, 255],
+                vec![255, 0, 0, 0, 255],
+                vec![255, 255, 255, 255, 255],
+            ]
+        );
+        // translucent, blended over what's there
+        pixels.fill_rect(1.0, 1.0, 3.0, 2.0, 0.0, BGRA(255, 0, 0, 128), false);
+        assert_eq!(pixels.view().get(1, 1).unwrap(), [255, 0, 0, 128]);
+        pixels.fill_rect(0.0, 0.0, 5.0, 4.0, 2.0, BGRA(0, 255, 0, 255), false);
+        assert_eq!(pixels.view().get(0, 0).unwrap(), [0, 0, 255, 255]);
+        assert_eq!(pixels.view().get(2, 0).unwrap(), [0, 255, 0, 255]);
+
+        let mut pixels = canvas(7, 7);
+        pixels.fill_ellipse((3.5, 3.5), 3.5, 3.5, RED, true);
+        assert_eq!(pixels.view().get(3, 3).unwrap()[3], 255);
+        let edge = pixels.view().get(0, 1).unwrap()[3];
+        assert!(edge > 0 && edge < 255);
+        assert_eq!(pixels.view().get(0, 0).unwrap()[3], 0);
+        let mut ring = canvas(7, 7);
+        ring.draw_ellipse((3.5, 3.5), 3.5, 3.5, RED, Stroke::new(1.0, false));
+        assert_eq!(ring.view().get(3, 0).unwrap()[3], 255);
+        assert_eq!(ring.view().get(3, 3).unwrap()[3], 0);
+
+        // a five-pointed star: its center is a hole with the even-odd rule only
+        let star: Vec<Point> = (0..5)
+            .map(|k| {
+                let angle = (-90.0 + k as f32 * 144.0).to_radians();
+                (10.5 + 10.0 * angle.cos(), 10.5 + 10.0 * angle.sin())
+            })
+            .collect();
+        let mut even_odd = canvas(21, 21);
+        even_odd.fill_polygon(&star, FillRule::EvenOdd, RED, false);
+        let mut non_zero = canvas(21, 21);
+        non_zero.fill_polygon(&star, FillRule::NonZero, RED, false);
+        assert_eq!(even_odd.view().get(10, 10).unwrap()[3], 0);
+        assert_eq!(non_zero.view().get(10, 10).unwrap()[3], 255);
+        assert_eq!(even_odd.view().get(10, 2).unwrap()[3], 255);
+        assert_eq!(even_odd.view().get(0, 20).unwrap()[3], 0);
+    }
+
+    #[test]
+    fn clipped_lines_and_joints() {
+        // far outside, or not finite: only the visible part is walked, or nothing
+        for stroke in [Stroke::new(1.0, false), Stroke::default()] {
+            let mut pixels = canvas(5, 5);
+            pixels.draw_line((-1e30, 2.5), (1e30, 2.5), RED, stroke);
+            assert_eq!(alphas(&pixels)[2], [255; 5]);
+            let mut far = canvas(5, 5);
+            far.draw_line((-1e9, -1e9), (1e9, 1e9), RED, stroke);
+            assert!((0..5).all(|i| far.view().get(i, i).unwrap()[3] > 0));
+            let mut pixels = canvas(5, 5);
+            pixels.draw_line((f32::NAN, 2.5), (4.5, 2.5), RED, stroke);
+            pixels.draw_line((0.5, f32::INFINITY), (4.5, 2.5), RED, stroke);
+            pixels.draw_line((-3e38, 0.5), (3e38, -10.0), RED, stroke);
+            assert!(pixels.bytes.iter().all(|b| *b == 0));
+        }
+
+        // the joints of thin translucent polylines are blended once, as the ends of single lines
+        let translucent = BGRA(0, 0, 255, 128);
+        for stroke in [Stroke::new(1.0, false), Stroke::default()] {
+            let mut line = canvas(9, 9);
+            line.draw_line((0.5, 4.5), (4.5, 4.5), translucent, stroke);
+            let mut polyline = canvas(9, 9);
+            polyline.draw_polyline(
+                &[(0.5, 4.5), (4.5, 4.5), (4.5, 0.5), (8.5, 8.5)],
+                false,
+                translucent,
+                stroke,
+            );
+            assert_eq!(polyline.view().get(4, 4), line.view().get(4, 4));
+            assert_eq!(
+                polyline.view().get(4, 0).unwrap()[3],
+                line.view().get(4, 4).unwrap()[3]
+            );
+            let mut closed = canvas(9, 9);
+            closed.draw_polyline(
+                &[(0.5, 0.5), (8.5, 0.5), (8.5, 8.5)],
+                true,
+                translucent,
+                stroke,
+            );
+            assert_eq!(
+                closed.view().get(0, 0).unwrap()[3],
+                line.view().get(4, 4).unwrap()[3]
+            );
+        }
+    }
+}
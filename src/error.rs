@@ -0,0 +1,190 @@
+use std::{error::Error, fmt};
+
+/// Errors returned by the crate's fallible operations
+#[derive(Debug)]
+pub enum PixelCasterError {
+    /// The provided values don't match the area they should represent (e.g.: a Vec's length against width * height)
+    DimensionMismatch { expected: usize, actual: usize },
+    /// Two collections of pixels that should have the same width and height don't (e.g.: the frames of a diff)
+    SizeMismatch {
+        expected_width: usize,
+        expected_height: usize,
+        actual_width: usize,
+        actual_height: usize,
+    },
+    /// An image could not be read or decoded
+    Decode(Box<dyn Error + Send + Sync>),
+    /// An image could not be encoded or written
+    Encode(Box<dyn Error + Send + Sync>),
+    /// The screen backend (GDI, X11, fbdev, ...) could not complete the operation
+    Backend(Box<dyn Error + Send + Sync>),
+    /// The char is not available (e.g.: not in CHARS, or it could not be found in the chars sample)
+    GlyphNotFound(char),
+    /// No pixel passed the matcher, so there's no area to work with
+    NoMatchingPixels,
+    /// A SpriteSheet was given no frames to slice
+    NoFrames,
+    /// The requested area is not (entirely) inside the available one
+    OutOfBounds {
+        x: i64,
+        y: i64,
+        width: usize,
+        height: usize,
+        bounds_width: usize,
+        bounds_height: usize,
+    },
+    /// Reading or writing a file/folder failed
+    Io(std::io::Error),
+}
+
+impl PixelCasterError {
+    pub fn decode<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> PixelCasterError {
+        PixelCasterError::Decode(error.into())
+    }
+
+    pub fn encode<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> PixelCasterError {
+        PixelCasterError::Encode(error.into())
+    }
+
+    pub fn backend<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> PixelCasterError {
+        PixelCasterError::Backend(error.into())
+    }
+
+    /// Returns an OutOfBounds error if the given area is not entirely inside a bounds_width x bounds_height one (starting at 0, 0)
+    pub fn check_bounds(
+        x: i64,
+        y: i64,
+        width: usize,
+        height: usize,
+        bounds_width: usize,
+        bounds_height: usize,
+    ) -> Result<(), PixelCasterError> {
+        if x < 0
+            || y < 0
+            || x as u64 + width as u64 > bounds_width as u64
+            || y as u64 + height as u64 > bounds_height as u64
+        {
+            return Err(PixelCasterError::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+                bounds_width,
+                bounds_height,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PixelCasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PixelCasterError::DimensionMismatch { expected, actual } => write!(
+                f,
+                "dimension mismatch: expected {} values, got {}",
+                expected, actual
+            ),
+            PixelCasterError::SizeMismatch {
+                expected_width,
+                expected_height,
+                actual_width,
+                actual_height,
+            } => write!(
+                f,
+                "size mismatch: expected {}x{} pixels, got {}x{}",
+                expected_width, expected_height, actual_width, actual_height
+            ),
+            PixelCasterError::Decode(e) => write!(f, "could not decode the image: {}", e),
+            PixelCasterError::Encode(e) => write!(f, "could not encode the image: {}", e),
+            PixelCasterError::Backend(e) => write!(f, "screen backend failure: {}", e),
+            PixelCasterError::GlyphNotFound(c) => write!(f, "glyph not found: {:?}", c),
+            PixelCasterError::NoMatchingPixels => write!(f, "no pixel passed the matcher"),
+            PixelCasterError::NoFrames => write!(f, "no frames to slice"),
+            PixelCasterError::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+                bounds_width,
+                bounds_height,
+            } => write!(
+                f,
+                "out of bounds: the {}x{} area at ({}, {}) is not inside the {}x{} one",
+                width, height, x, y, bounds_width, bounds_height
+            ),
+            PixelCasterError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl Error for PixelCasterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PixelCasterError::Decode(e)
+            | PixelCasterError::Encode(e)
+            | PixelCasterError::Backend(e) => Some(e.as_ref()),
+            PixelCasterError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PixelCasterError {
+    fn from(error: std::io::Error) -> Self {
+        PixelCasterError::Io(error)
+    }
+}
+
+#[cfg(windows)]
+impl From<windows::core::Error> for PixelCasterError {
+    fn from(error: windows::core::Error) -> Self {
+        PixelCasterError::Backend(Box::new(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PixelsCollection, Screen, VirtualScreen};
+
+    #[test]
+    fn bounds_check() {
+        assert!(PixelCasterError::check_bounds(0, 0, 4, 3, 4, 3).is_ok());
+        assert!(PixelCasterError::check_bounds(2, 1, 2, 2, 4, 3).is_ok());
+        assert!(matches!(
+            PixelCasterError::check_bounds(3, 0, 2, 1, 4, 3),
+            Err(PixelCasterError::OutOfBounds { x: 3, width: 2, .. })
+        ));
+        assert!(PixelCasterError::check_bounds(-1, 0, 1, 1, 4, 3).is_err());
+    }
+
+    #[test]
+    fn dimension_mismatch() {
+        assert!(matches!(
+            PixelsCollection::<u8>::create(2, 2, vec![0; 15]),
+            Err(PixelCasterError::DimensionMismatch {
+                expected: 16,
+                actual: 15
+            })
+        ));
+
+        let mut screen =
+            Screen::<u32, VirtualScreen>::with_backend(VirtualScreen::new(4, 4), 0, 0, 2, 2);
+        assert!(matches!(
+            screen.update_area_from_vec(&[0; 3]),
+            Err(PixelCasterError::DimensionMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        assert!(screen.scan_area_onto_vec(&mut [0; 5]).is_err());
+        assert_eq!(
+            screen
+                .update_area_from_vec(&[0; 2])
+                .unwrap_err()
+                .to_string(),
+            "dimension mismatch: expected 4 values, got 2"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{bgra_management::BGRA, drawing::Point, PixelsCollection};
+
+/// Colors to fill pixels with, varying with their position
+pub trait Paint {
+    /// Returns the BGRA of the pixel at column x and row y
+    fn bgra_at(&self, x: usize, y: usize) -> [u8; 4];
+}
+
+/// Geometry of a Gradient, which maps each point to a position (0 - 1) between its first and last stops.
+/// The points before the start or beyond the end get the first or last stop's color
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GradientShape {
+    /// Along the line from `from` (0) to `to` (1), the same on each perpendicular line
+    Linear { from: Point, to: Point },
+    /// From the center (0) to the circle of the given radius (1)
+    Radial { center: Point, radius: f32 },
+    /// Around the center, clockwise from the given angle (in degrees, 0 : towards the right, 90 : downwards)
+    Conic { center: Point, start_angle: f32 },
+}
+
+/// How the colors between two stops are computed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Mixing the Blue Green Red values as they are (as most image editors and CSS do)
+    Srgb,
+    /// Mixing the amounts of light the values stand for, which keeps the middle colors as bright as the stops
+    LinearLight,
+}
+
+/// Colors changing smoothly through the given stops, over the pixels' coordinates
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    shape: GradientShape,
+    // sorted by position
+    stops: Vec<(f32, BGRA<u8>)>,
+    interpolation: Interpolation,
+}
+
+impl Gradient {
+    /// Creates a gradient through the stops, each a position (0 - 1) and its color, in any order.
+    /// The stops at a NaN position are left out, the infinite positions are clamped to 0 - 1.
+    /// Without stops every pixel is fully transparent
+    pub fn new(
+        shape: GradientShape,
+        mut stops: Vec<(f32, BGRA<u8>)>,
+        interpolation: Interpolation,
+    ) -> Gradient {
+        stops.retain(|(position, _)| !position.is_nan());
+        for (position, _) in stops.iter_mut().filter(|(p, _)| p.is_infinite()) {
+            *position = position.clamp(0.0, 1.0);
+        }
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Gradient {
+            shape,
+            stops,
+            interpolation,
+        }
+    }
+
+    /// Returns the position (clamped to 0 - 1) of the point in the gradient
+    pub fn position_at(&self, point: Point) -> f32 {
+        let t = match self.shape {
+            GradientShape::Linear { from, to } => {
+                let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+                let length_squared = dx * dx + dy * dy;
+                if length_squared == 0.0 {
+                    0.0
+                } else {
+                    ((point.0 - from.0) * dx + (point.1 - from.1) * dy) / length_squared
+                }
+            }
+            GradientShape::Radial { center, radius } => {
+                let distance = ((point.0 - center.0).powi(2) + (point.1 - center.1).powi(2)).sqrt();
+                if radius <= 0.0 {
+                    1.0
+                } else {
+                    distance / radius
+                }
+            }
+            GradientShape::Conic {
+                center,
+                start_angle,
+            } => {
+                let angle = (point.1 - center.1).atan2(point.0 - center.0).to_degrees();
+                (angle - start_angle).rem_euclid(360.0) / 360.0
+            }
+        };
+        t.clamp(0.0, 1.0)
+    }
+
+    /// Returns the color at the given position (0 - 1) of the gradient, the first stop's one if it's NaN
+    pub fn bgra_at_position(&self, t: f32) -> [u8; 4] {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return [0, 0, 0, 0];
+        };
+        if t.is_nan() || t <= first.0 {
+            return bgra_array(first.1);
+        }
+        if t >= last.0 {
+            return bgra_array(last.1);
+        }
+        // the first stop after t, there's one before it as t is after the first one
+        let Some(i) = self.stops.iter().position(|(position, _)| *position > t) else {
+            return bgra_array(last.1);
+        };
+        let ((p0, c0), (p1, c1)) = (self.stops[i - 1], self.stops[i]);
+        let fraction = (t - p0) / (p1 - p0);
+        let (c0, c1) = (bgra_array(c0), bgra_array(c1));
+        let mut bgra = [0; 4];
+        for c in 0..4 {
+            let (v0, v1) = (c0[c] as f32 / 255.0, c1[c] as f32 / 255.0);
+            let mixed = if c < 3 && self.interpolation == Interpolation::LinearLight {
+                to_srgb(to_linear(v0) + (to_linear(v1) - to_linear(v0)) * fraction)
+            } else {
+                v0 + (v1 - v0) * fraction
+            };
+            bgra[c] = (mixed * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+        bgra
+    }
+}
+
+impl Paint for Gradient {
+    /// The color at the pixel's center
+    fn bgra_at(&self, x: usize, y: usize) -> [u8; 4] {
+        self.bgra_at_position(self.position_at((x as f32 + 0.5, y as f32 + 0.5)))
+    }
+}
+
+/// Pixels repeated side by side and one row under the other, as tiles
+#[derive(Clone)]
+pub struct Pattern {
+    pub tile: PixelsCollection<u8>,
+    /// Where the upper left corner of a tile is placed
+    pub offset_x: i64,
+    pub offset_y: i64,
+}
+
+impl Pattern {
+    /// Tiles the pixels starting from the upper left corner
+    pub fn new(tile: PixelsCollection<u8>) -> Pattern {
+        Pattern {
+            tile,
+            offset_x: 0,
+            offset_y: 0,
+        }
+    }
+}
+
+impl Paint for Pattern {
+    /// Fully transparent if the tile is empty
+    fn bgra_at(&self, x: usize, y: usize) -> [u8; 4] {
+        if self.tile.width == 0 || self.tile.height == 0 {
+            return [0, 0, 0, 0];
+        }
+        let tile_x = (x as i64 - self.offset_x).rem_euclid(self.tile.width as i64) as usize;
+        let tile_y = (y as i64 - self.offset_y).rem_euclid(self.tile.height as i64) as usize;
+        let i = (tile_y * self.tile.width + tile_x) * 4;
+        self.tile.bytes[i..i + 4].try_into().unwrap()
+    }
+}
+
+/// Which pixels fill_with fills
+#[derive(Clone, Copy, Debug)]
+pub enum FillArea<'a> {
+    /// All of them, replaced by the paint's colors
+    All,
+    /// Those whose value is true, one per pixel, row by row (e.g.: a FrameDiff's mask), replaced by the paint's colors
+    Mask(&'a [bool]),
+    /// Those that are visible (Alpha > 0), whose Blue Green Red are set to the paint's ones and whose Alpha is scaled by the paint's one,
+    /// keeping their shape (e.g.: to color a PixelsString's text)
+    Visible,
+}
+
+impl PixelsCollection<u8> {
+    /// Fills the pixels in the area with the Paint's colors (e.g.: a Gradient or a Pattern). A Mask shorter than the pixels leaves the rest as it is
+    pub fn fill_with(&mut self, paint: &impl Paint, area: FillArea) {
+        let width = self.width.max(1);
+        for (i, bgra) in self.bytes.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % width, i / width);
+            match area {
+                FillArea::All => bgra.copy_from_slice(&paint.bgra_at(x, y)),
+                FillArea::Mask(mask) => {
+                    if mask.get(i) == Some(&true) {
+                        bgra.copy_from_slice(&paint.bgra_at(x, y));
+                    }
+                }
+                FillArea::Visible => {
+                    if bgra[3] > 0 {
+                        let color = paint.bgra_at(x, y);
+                        bgra[..3].copy_from_slice(&color[..3]);
+                        bgra[3] = ((bgra[3] as u32 * color[3] as u32 + 127) / 255) as u8;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn bgra_array(bgra: BGRA<u8>) -> [u8; 4] {
+    [bgra.0, bgra.1, bgra.2, bgra.3]
+}
+
+/// sRGB value (0 - 1) to the linear light amount (0 - 1)
+fn to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: BGRA<u8> = BGRA(0, 0, 0, 255);
+    const WHITE: BGRA<u8> = BGRA(255, 255, 255, 255);
+
+    fn canvas(width: usize, height: usize, bgra: [u8; 4]) -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(width, height, bgra.repeat(width * height)).unwrap()
+    }
+
+    #[test]
+    fn gradients() {
+        let linear = |interpolation| {
+            Gradient::new(
+                GradientShape::Linear {
+                    from: (0.5, 0.0),
+                    to: (4.5, 0.0),
+                },
+                vec![(1.0, WHITE), (0.0, BLACK)],
+                interpolation,
+            )
+        };
+        let mut pixels = canvas(5, 1, [0; 4]);
+        pixels.fill_with(&linear(Interpolation::Srgb), FillArea::All);
+        let blues: Vec<u8> = pixels.bytes.chunks_exact(4).map(|bgra| bgra[0]).collect();
+        assert_eq!(blues, [0, 64, 128, 191, 255]);
+        // half of the light is brighter than the half of the values
+        assert_eq!(
+            linear(Interpolation::LinearLight).bgra_at(2, 0),
+            [188, 188, 188, 255]
+        );
+
+        let stops = vec![
+            (0.0, BGRA(0, 0, 255, 255)),
+            (0.5, BGRA(0, 255, 0, 255)),
+            (1.0, BGRA(255, 0, 0, 0)),
+        ];
+        let radial = Gradient::new(
+            GradientShape::Radial {
+                center: (0.0, 0.0),
+                radius: 10.0,
+            },
+            stops.clone(),
+            Interpolation::Srgb,
+        );
+        assert_eq!(
+            radial.bgra_at_position(radial.position_at((0.0, 5.0))),
+            [0, 255, 0, 255]
+        );
+        assert_eq!(
+            radial.bgra_at_position(radial.position_at((30.0, 0.0))),
+            [255, 0, 0, 0]
+        );
+
+        let conic = Gradient::new(
+            GradientShape::Conic {
+                center: (0.0, 0.0),
+                start_angle: 0.0,
+            },
+            stops,
+            Interpolation::Srgb,
+        );
+        assert_eq!(conic.position_at((1.0, 0.0)), 0.0);
+        assert_eq!(conic.position_at((0.0, 1.0)), 0.25);
+        assert_eq!(conic.position_at((-1.0, 0.0)), 0.5);
+
+        // NaN positions don't panic, the NaN stops are left out
+        let odd_stops = Gradient::new(
+            GradientShape::Radial {
+                center: (0.0, 0.0),
+                radius: 10.0,
+            },
+            vec![
+                (f32::NAN, BGRA(9, 9, 9, 9)),
+                (f32::INFINITY, WHITE),
+                (f32::NEG_INFINITY, BLACK),
+            ],
+            Interpolation::Srgb,
+        );
+        assert_eq!(odd_stops.bgra_at_position(f32::NAN), [0, 0, 0, 255]);
+        assert_eq!(odd_stops.bgra_at_position(0.5), [128, 128, 128, 255]);
+        assert_eq!(
+            odd_stops.bgra_at_position(odd_stops.position_at((f32::NAN, 0.0))),
+            [0, 0, 0, 255]
+        );
+        assert_eq!(radial.bgra_at_position(f32::NAN), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn patterns_and_areas() {
+        let tile = PixelsCollection::<u8>::create(2, 1, [[1, 1, 1, 255], [2, 2, 2, 255]].concat())
+            .unwrap();
+        let mut pattern = Pattern::new(tile);
+        pattern.offset_x = 1;
+        let mut pixels = canvas(3, 2, [0; 4]);
+        pixels.fill_with(&pattern, FillArea::All);
+        let blues: Vec<u8> = pixels.bytes.chunks_exact(4).map(|bgra| bgra[0]).collect();
+        assert_eq!(blues, [2, 1, 2, 2, 1, 2]);
+
+        let mut pixels = canvas(3, 2, [9, 9, 9, 255]);
+        pixels.fill_with(&pattern, FillArea::Mask(&[false, true, false, true]));
+        let blues: Vec<u8> = pixels.bytes.chunks_exact(4).map(|bgra| bgra[0]).collect();
+        assert_eq!(blues, [9, 1, 9, 2, 9, 9]);
+
+        // text-like pixels keep their shape and anti-aliasing
+        let mut text = PixelsCollection::<u8>::create(
+            3,
+            1,
+            [[0, 0, 0, 255], [0, 0, 0, 0], [0, 0, 0, 128]].concat(),
+        )
+        .unwrap();
+        let fading = Gradient::new(
+            GradientShape::Linear {
+                from: (0.5, 0.0),
+                to: (2.5, 0.0),
+            },
+            vec![(0.0, BGRA(0, 0, 255, 255)), (1.0, BGRA(255, 0, 0, 128))],
+            Interpolation::Srgb,
+        );
+        text.fill_with(&fading, FillArea::Visible);
+        assert_eq!(
+            text.bytes,
+            [[0, 0, 255, 255], [0, 0, 0, 0], [255, 0, 0, 64]].concat()
+        );
+    }
+}
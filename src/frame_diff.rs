@@ -0,0 +1,268 @@
+use crate::{PixelCasterError, PixelValues, PixelsCollection, ScreenArea};
+
+/// Options of PixelsCollection::diff_with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// A pixel is changed when any of its Blue Green Red Alpha differs by more than this (0 : any difference), to ignore noise
+    pub tolerance: u8,
+    /// Dirty rectangles with at most this many pixels between them (horizontally and vertically) are merged into one,
+    /// fewer bigger rectangles send some unchanged pixels but make fewer calls (0 : only those touching are merged)
+    pub merge_distance: usize,
+}
+
+impl Default for DiffOptions {
+    /// Any difference, merging only the touching rectangles
+    fn default() -> DiffOptions {
+        DiffOptions {
+            tolerance: 0,
+            merge_distance: 0,
+        }
+    }
+}
+
+/// What changed between two frames of the same size
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameDiff {
+    pub width: usize,
+    pub height: usize,
+    /// Number of changed pixels
+    pub changed_pixels: usize,
+    /// For each pixel, row by row, whether it changed
+    pub mask: Vec<bool>,
+    /// Rectangles covering all of the changed pixels, from the top left, relative to the frames' upper left corner
+    pub dirty_rects: Vec<ScreenArea>,
+}
+
+impl FrameDiff {
+    /// Returns whether nothing changed
+    pub fn is_unchanged(&self) -> bool {
+        self.changed_pixels == 0
+    }
+
+    /// Returns whether the x, y pixel changed (false if it's outside of the frames)
+    pub fn is_changed(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.mask[y * self.width + x]
+    }
+}
+
+/// Rectangle as x0, y0, x1, y1 with exclusive ends
+type Rect = (usize, usize, usize, usize);
+
+impl<T: PixelValues<T>> PixelsCollection<T> {
+    /// Returns what changed from these pixels to the `other` ones, as diff_with with the default DiffOptions
+    pub fn diff(&self, other: &PixelsCollection<T>) -> Result<FrameDiff, PixelCasterError> {
+        self.diff_with(other, DiffOptions::default())
+    }
+
+    /// Returns what changed from these pixels to the `other` ones, which must have the same size (SizeMismatch otherwise)
+    pub fn diff_with(
+        &self,
+        other: &PixelsCollection<T>,
+        opts: DiffOptions,
+    ) -> Result<FrameDiff, PixelCasterError> {
+        if self.width != other.width || self.height != other.height {
+            return Err(PixelCasterError::SizeMismatch {
+                expected_width: self.width,
+                expected_height: self.height,
+                actual_width: other.width,
+                actual_height: other.height,
+            });
+        }
+        let mask: Vec<bool> = <T>::as_bgra_bytes(&self.bytes)
+            .chunks_exact(4)
+            .zip(<T>::as_bgra_bytes(&other.bytes).chunks_exact(4))
+            .map(|(a, b)| {
+                a.iter()
+                    .zip(b)
+                    .any(|(a, b)| a.abs_diff(*b) > opts.tolerance)
+            })
+            .collect();
+
+        // swept row by row, only the rectangles ending at most merge_distance rows above can still be merged
+        let mut rects: Vec<Rect> = Vec::new();
+        let mut open: Vec<Rect> = Vec::new();
+        for (y, row) in mask.chunks(self.width.max(1)).enumerate() {
+            let mut i = 0;
+            while i < open.len() {
+                if open[i].3 + opts.merge_distance < y {
+                    rects.push(open.swap_remove(i));
+                } else {
+                    i += 1;
+                }
+            }
+            let mut x = 0;
+            while x < row.len() {
+                if !row[x] {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < row.len() && row[x] {
+                    x += 1;
+                }
+                add_merging(&mut open, (start, y, x, y + 1), opts.merge_distance);
+            }
+        }
+        rects.append(&mut open);
+        rects.sort_by_key(|&(x0, y0, _, _)| (y0, x0));
+
+        Ok(FrameDiff {
+            width: self.width,
+            height: self.height,
+            changed_pixels: mask.iter().filter(|changed| **changed).count(),
+            mask,
+            dirty_rects: rects
+                .into_iter()
+                .map(|(x0, y0, x1, y1)| {
+                    ScreenArea::new(x0 as i32, y0 as i32, (x1 - x0) as u32, (y1 - y0) as u32)
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Adds the rectangle, merging it with the open ones that are within the distance from it (and then from the merged one), so that none of them are
+fn add_merging(rects: &mut Vec<Rect>, mut rect: Rect, distance: usize) {
+    let gap = |a0: usize, a1: usize, b0: usize, b1: usize| a0.max(b0).saturating_sub(a1.min(b1));
+    while let Some(i) = rects.iter().position(|r| {
+        gap(r.0, r.2, rect.0, rect.2) <= distance && gap(r.1, r.3, rect.1, rect.3) <= distance
+    }) {
+        let r = rects.swap_remove(i);
+        rect = (
+            rect.0.min(r.0),
+            rect.1.min(r.1),
+            rect.2.max(r.2),
+            rect.3.max(r.3),
+        );
+    }
+    rects.push(rect);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Screen, VirtualScreen};
+
+    fn frame(width: usize, height: usize, changed: &[(usize, usize, u8)]) -> PixelsCollection<u8> {
+        let mut pixels =
+            PixelsCollection::<u8>::create(width, height, vec![100; width * height * 4]).unwrap();
+        for (x, y, blue) in changed {
+            pixels.bytes[(y * width + x) * 4] = *blue;
+        }
+        pixels
+    }
+
+    fn rects(diff: &FrameDiff) -> Vec<(i32, i32, u32, u32)> {
+        diff.dirty_rects
+            .iter()
+            .map(|r| {
+                (
+                    r.upperleftcorner_x(),
+                    r.upperleftcorner_y(),
+                    r.width(),
+                    r.height(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn changes_and_tolerance() {
+        let before = frame(6, 4, &[]);
+        let after = frame(6, 4, &[(1, 1, 0), (2, 1, 0), (1, 2, 0), (5, 3, 98)]);
+        let diff = before.diff(&after).unwrap();
+        assert_eq!(diff.changed_pixels, 4);
+        assert!(diff.is_changed(2, 1) && !diff.is_changed(3, 1) && !diff.is_changed(6, 0));
+        assert_eq!(rects(&diff), [(1, 1, 2, 2), (5, 3, 1, 1)]);
+
+        let tolerant = before
+            .diff_with(
+                &after,
+                DiffOptions {
+                    tolerance: 2,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(tolerant.changed_pixels, 3);
+        assert_eq!(rects(&tolerant), [(1, 1, 2, 2)]);
+
+        assert!(before.diff(&before).unwrap().is_unchanged());
+        assert_eq!(
+            before.diff(&frame(4, 6, &[])).unwrap_err().to_string(),
+            "size mismatch: expected 6x4 pixels, got 4x6"
+        );
+    }
+
+    #[test]
+    fn merging() {
+        let before = frame(8, 3, &[]);
+        let after = frame(8, 3, &[(0, 0, 0), (2, 0, 0), (7, 2, 0), (1, 2, 0)]);
+        assert_eq!(
+            rects(&before.diff(&after).unwrap()),
+            [(0, 0, 1, 1), (2, 0, 1, 1), (1, 2, 1, 1), (7, 2, 1, 1)]
+        );
+        let merged = before
+            .diff_with(
+                &after,
+                DiffOptions {
+                    merge_distance: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(rects(&merged), [(0, 0, 3, 3), (7, 2, 1, 1)]);
+
+        let values = PixelsCollection::<u32>::create(2, 1, vec![0, 0]).unwrap();
+        let changed = PixelsCollection::<u32>::create(2, 1, vec![0, 0x0100_0000]).unwrap();
+        assert_eq!(rects(&values.diff(&changed).unwrap()), [(1, 0, 1, 1)]);
+
+        // a grid of isolated pixels stays as many rectangles, and merges into one when they are close enough
+        let grid: Vec<(usize, usize, u8)> = (0..200)
+            .step_by(2)
+            .flat_map(|y| (0..200).step_by(2).map(move |x| (x, y, 0)))
+            .collect();
+        let before = frame(200, 200, &[]);
+        let after = frame(200, 200, &grid);
+        assert_eq!(before.diff(&after).unwrap().dirty_rects.len(), 100 * 100);
+        let merged = before
+            .diff_with(
+                &after,
+                DiffOptions {
+                    merge_distance: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(rects(&merged), [(0, 0, 199, 199)]);
+    }
+
+    #[test]
+    fn partial_updates() {
+        let virtual_screen = VirtualScreen::from_pixels(
+            PixelsCollection::<u8>::create(4, 3, vec![0; 4 * 3 * 4]).unwrap(),
+        );
+        let mut screen =
+            Screen::<u8, VirtualScreen>::with_backend(virtual_screen.clone(), 1, 1, 3, 2);
+        screen.get_bytes_mut().fill(255);
+        let before = screen.get_pixels_collection().clone();
+        screen.get_bytes_mut()[(3 + 2) * 4..(3 + 2) * 4 + 4].copy_from_slice(&[0, 0, 255, 255]);
+        let diff = before.diff(screen.get_pixels_collection()).unwrap();
+        screen.update_area_rects(&diff.dirty_rects).unwrap();
+
+        let sent: Vec<usize> = virtual_screen
+            .to_pixels_collection()
+            .bytes
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, bgra)| bgra[3] > 0)
+            .map(|(i, _)| i)
+            .collect();
+        // only the changed pixel, at 2, 1 in the Screen's area
+        assert_eq!(sent, [2 * 4 + 3]);
+        assert_eq!(virtual_screen.get_pixel(3, 2), Some([0, 0, 255, 255]));
+        assert!(screen
+            .update_area_rects(&[ScreenArea::new(-1, 0, 1, 1)])
+            .is_err());
+    }
+}
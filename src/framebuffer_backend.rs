@@ -0,0 +1,679 @@
+use std::{fs::File, os::fd::AsRawFd, os::unix::fs::FileExt, sync::Arc};
+
+use crate::{
+    PixelCasterError, PixelValues, PixelsCollection, PixelsSendMode, ScreenArea, ScreenBackend,
+    VirtualScreen,
+};
+
+/// Device used by ScreenBackend::open when the FRAMEBUFFER environment variable is not set
+pub const DEFAULT_FRAMEBUFFER_DEVICE: &str = "/dev/fb0";
+
+// https://github.com/torvalds/linux/blob/master/include/uapi/linux/fb.h
+const FBIOGET_VSCREENINFO: libc::c_ulong = 0x4600;
+const FBIOGET_FSCREENINFO: libc::c_ulong = 0x4602;
+const FB_TYPE_PACKED_PIXELS: u32 = 0;
+const FB_VISUAL_TRUECOLOR: u32 = 2;
+const FB_VISUAL_DIRECTCOLOR: u32 = 4;
+
+#[repr(C)]
+#[derive(Default)]
+struct FbBitfieldRaw {
+    offset: u32,
+    length: u32,
+    msb_right: u32,
+}
+
+/// struct fb_var_screeninfo
+#[repr(C)]
+#[derive(Default)]
+struct FbVarScreenInfoRaw {
+    xres: u32,
+    yres: u32,
+    xres_virtual: u32,
+    yres_virtual: u32,
+    xoffset: u32,
+    yoffset: u32,
+    bits_per_pixel: u32,
+    grayscale: u32,
+    red: FbBitfieldRaw,
+    green: FbBitfieldRaw,
+    blue: FbBitfieldRaw,
+    transp: FbBitfieldRaw,
+    nonstd: u32,
+    activate: u32,
+    height: u32,
+    width: u32,
+    accel_flags: u32,
+    pixclock: u32,
+    left_margin: u32,
+    right_margin: u32,
+    upper_margin: u32,
+    lower_margin: u32,
+    hsync_len: u32,
+    vsync_len: u32,
+    sync: u32,
+    vmode: u32,
+    rotate: u32,
+    colorspace: u32,
+    reserved: [u32; 4],
+}
+
+/// struct fb_fix_screeninfo
+#[repr(C)]
+#[derive(Default)]
+struct FbFixScreenInfoRaw {
+    id: [u8; 16],
+    smem_start: libc::c_ulong,
+    smem_len: u32,
+    type_: u32,
+    type_aux: u32,
+    visual: u32,
+    xpanstep: u16,
+    ypanstep: u16,
+    ywrapstep: u16,
+    line_length: u32,
+    mmio_start: libc::c_ulong,
+    mmio_len: u32,
+    accel: u32,
+    capabilities: u16,
+    reserved: [u16; 2],
+}
+
+/// Position and size (in bits) of a color channel inside a pixel's value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FbChannel {
+    pub offset: u32,
+    pub length: u32,
+}
+
+impl FbChannel {
+    pub const fn new(offset: u32, length: u32) -> FbChannel {
+        FbChannel { offset, length }
+    }
+
+    /// Scales the channel's value, found in the pixel's value, to a u8
+    fn get(&self, pixel_value: u32) -> u8 {
+        let max = (1u64 << self.length) - 1;
+        let value = (pixel_value as u64 >> self.offset) & max;
+        ((value * 255 + max / 2) / max) as u8
+    }
+
+    /// Scales the u8 to the channel's length and puts it in its position
+    fn set(&self, byte: u8) -> u32 {
+        let max = (1u64 << self.length) - 1;
+        (((byte as u64 * max + 127) / 255) << self.offset) as u32
+    }
+}
+
+/// The values of the framebuffer's fixed and variable screen info needed to read and write its pixels
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FbScreenInfo {
+    /// Visible resolution
+    pub width: u32,
+    pub height: u32,
+    /// Position of the visible area inside the virtual resolution (panning)
+    pub xoffset: u32,
+    pub yoffset: u32,
+    pub bits_per_pixel: u32,
+    /// Bytes of each row of the virtual resolution, padding included
+    pub line_length: u32,
+    pub red: FbChannel,
+    pub green: FbChannel,
+    pub blue: FbChannel,
+    /// Transparency, its length is 0 if the framebuffer has none
+    pub transp: FbChannel,
+}
+
+impl FbScreenInfo {
+    /// Screen info of a width x height framebuffer without padding nor panning, with the provided pixel format
+    pub fn new(
+        width: u32,
+        height: u32,
+        bits_per_pixel: u32,
+        red: FbChannel,
+        green: FbChannel,
+        blue: FbChannel,
+        transp: FbChannel,
+    ) -> FbScreenInfo {
+        FbScreenInfo {
+            width,
+            height,
+            xoffset: 0,
+            yoffset: 0,
+            bits_per_pixel,
+            line_length: width * bits_per_pixel / 8,
+            red,
+            green,
+            blue,
+            transp,
+        }
+    }
+
+    /// Screen info of a width x height framebuffer storing its pixels as BGRA (the crate's layout)
+    pub fn bgra32(width: u32, height: u32) -> FbScreenInfo {
+        Self::new(
+            width,
+            height,
+            32,
+            FbChannel::new(16, 8),
+            FbChannel::new(8, 8),
+            FbChannel::new(0, 8),
+            FbChannel::new(24, 8),
+        )
+    }
+
+    /// Screen info of a width x height framebuffer storing its pixels as RGB565
+    pub fn rgb565(width: u32, height: u32) -> FbScreenInfo {
+        Self::new(
+            width,
+            height,
+            16,
+            FbChannel::new(11, 5),
+            FbChannel::new(5, 6),
+            FbChannel::new(0, 5),
+            FbChannel::new(0, 0),
+        )
+    }
+
+    /// Gets the screen info of a fbdev device through the FBIOGET_VSCREENINFO and FBIOGET_FSCREENINFO ioctls
+    pub fn from_device(device: &File) -> Result<FbScreenInfo, PixelCasterError> {
+        let mut var = FbVarScreenInfoRaw::default();
+        let mut fix = FbFixScreenInfoRaw::default();
+        unsafe {
+            if libc::ioctl(device.as_raw_fd(), FBIOGET_VSCREENINFO as _, &mut var) == -1
+                || libc::ioctl(device.as_raw_fd(), FBIOGET_FSCREENINFO as _, &mut fix) == -1
+            {
+                return Err(PixelCasterError::backend(format!(
+                    "could not get the framebuffer screen info: {}",
+                    std::io::Error::last_os_error()
+                )));
+            }
+        }
+        Self::from_raw(&var, &fix)
+    }
+
+    fn from_raw(
+        var: &FbVarScreenInfoRaw,
+        fix: &FbFixScreenInfoRaw,
+    ) -> Result<FbScreenInfo, PixelCasterError> {
+        if fix.type_ != FB_TYPE_PACKED_PIXELS
+            || (fix.visual != FB_VISUAL_TRUECOLOR && fix.visual != FB_VISUAL_DIRECTCOLOR)
+        {
+            return Err(PixelCasterError::backend(format!(
+                "unsupported framebuffer type {} with visual {}, only packed pixels true/direct color ones are supported",
+                fix.type_, fix.visual
+            )));
+        }
+        let channel = |bitfield: &FbBitfieldRaw| -> Result<FbChannel, PixelCasterError> {
+            if bitfield.msb_right != 0 {
+                return Err(PixelCasterError::backend(
+                    "unsupported framebuffer channel with its most significant bit on the right"
+                        .to_string(),
+                ));
+            }
+            Ok(FbChannel::new(bitfield.offset, bitfield.length))
+        };
+        let screen_info = FbScreenInfo {
+            width: var.xres,
+            height: var.yres,
+            xoffset: var.xoffset,
+            yoffset: var.yoffset,
+            bits_per_pixel: var.bits_per_pixel,
+            line_length: fix.line_length,
+            red: channel(&var.red)?,
+            green: channel(&var.green)?,
+            blue: channel(&var.blue)?,
+            transp: channel(&var.transp)?,
+        };
+        screen_info.validate()?;
+        Ok(screen_info)
+    }
+
+    fn validate(&self) -> Result<(), PixelCasterError> {
+        if ![16, 24, 32].contains(&self.bits_per_pixel) {
+            return Err(PixelCasterError::backend(format!(
+                "unsupported framebuffer bits per pixel: {}",
+                self.bits_per_pixel
+            )));
+        }
+        for channel in [self.red, self.green, self.blue, self.transp] {
+            if channel.length > 8 || channel.offset + channel.length > self.bits_per_pixel {
+                return Err(PixelCasterError::backend(format!(
+                    "unsupported framebuffer channel: {:?}",
+                    channel
+                )));
+            }
+        }
+        if self.red.length == 0 || self.green.length == 0 || self.blue.length == 0 {
+            return Err(PixelCasterError::backend(
+                "the framebuffer has no Red, Green or Blue channel".to_string(),
+            ));
+        }
+        if self.line_length < self.width * self.bits_per_pixel / 8 {
+            return Err(PixelCasterError::backend(format!(
+                "framebuffer line length {} too short for {} pixels of {} bits",
+                self.line_length, self.width, self.bits_per_pixel
+            )));
+        }
+        Ok(())
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.bits_per_pixel as usize / 8
+    }
+
+    /// True if the pixels are stored exactly as the crate's BGRA (or BGRX) layout, so that no conversion is needed
+    fn is_bgra32(&self) -> bool {
+        self.bits_per_pixel == 32
+            && self.red == FbChannel::new(16, 8)
+            && self.green == FbChannel::new(8, 8)
+            && self.blue == FbChannel::new(0, 8)
+    }
+
+    /// Converts the framebuffer's pixels (in its format) into BGRA bytes
+    fn pixels_to_bgra(&self, fb_bytes: &[u8], bgra: &mut [u8]) {
+        if self.is_bgra32() {
+            bgra.copy_from_slice(fb_bytes);
+            if self.transp.length == 0 {
+                bgra.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+            }
+            return;
+        }
+        for (fb_pixel, pixel) in fb_bytes
+            .chunks_exact(self.bytes_per_pixel())
+            .zip(bgra.chunks_exact_mut(4))
+        {
+            let mut value_bytes = [0u8; 4];
+            value_bytes[..fb_pixel.len()].copy_from_slice(fb_pixel);
+            let value = u32::from_le_bytes(value_bytes);
+            pixel[0] = self.blue.get(value);
+            pixel[1] = self.green.get(value);
+            pixel[2] = self.red.get(value);
+            pixel[3] = if self.transp.length == 0 {
+                255
+            } else {
+                self.transp.get(value)
+            };
+        }
+    }
+
+    /// Converts BGRA bytes into the framebuffer's pixels (in its format)
+    fn bgra_to_pixels(&self, bgra: &[u8], fb_bytes: &mut [u8]) {
+        if self.is_bgra32() {
+            fb_bytes.copy_from_slice(bgra);
+            return;
+        }
+        for (pixel, fb_pixel) in bgra
+            .chunks_exact(4)
+            .zip(fb_bytes.chunks_exact_mut(self.bytes_per_pixel()))
+        {
+            let value = self.blue.set(pixel[0])
+                | self.green.set(pixel[1])
+                | self.red.set(pixel[2])
+                | if self.transp.length == 0 {
+                    0
+                } else {
+                    self.transp.set(pixel[3])
+                };
+            let length = fb_pixel.len();
+            fb_pixel.copy_from_slice(&value.to_le_bytes()[..length]);
+        }
+    }
+}
+
+/// Reads and writes the pixels of a Linux framebuffer device (fbdev), or of any file laid out like one.
+///
+/// The device's pixel format is converted from/to the crate's BGRA layout, and as there's no compositing the sent pixels
+/// are blended in software with the framebuffer's current content. Clones share the same file.
+#[derive(Clone)]
+pub struct FramebufferBackend {
+    file: Arc<File>,
+    screen_info: FbScreenInfo,
+}
+
+impl FramebufferBackend {
+    /// Opens a fbdev device (e.g.: "/dev/fb0"), getting its screen info from the device itself
+    pub fn open_device(path: &str) -> Result<FramebufferBackend, PixelCasterError> {
+        let file = Self::open_read_write(path)?;
+        let screen_info = FbScreenInfo::from_device(&file)?;
+        Ok(FramebufferBackend {
+            file: Arc::new(file),
+            screen_info,
+        })
+    }
+
+    /// Opens any file (e.g.: a plain file sized like a framebuffer), laid out as described by the provided screen info
+    pub fn open_file(
+        path: &str,
+        screen_info: FbScreenInfo,
+    ) -> Result<FramebufferBackend, PixelCasterError> {
+        screen_info.validate()?;
+        let file = Self::open_read_write(path)?;
+        Ok(FramebufferBackend {
+            file: Arc::new(file),
+            screen_info,
+        })
+    }
+
+    fn open_read_write(path: &str) -> Result<File, PixelCasterError> {
+        File::options()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| {
+                PixelCasterError::backend(format!("could not open the framebuffer {}: {}", path, e))
+            })
+    }
+
+    /// Returns the screen info of the framebuffer
+    pub fn screen_info(&self) -> &FbScreenInfo {
+        &self.screen_info
+    }
+
+    /// Writes the BGRA bytes as they are (apart from the format conversion) onto the visible part of the given area
+    fn write_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Result<(), PixelCasterError> {
+        let Some((x, y, width, height)) =
+            self.visible_part(dst_ul_x, dst_ul_y, area_width, area_height)
+        else {
+            return Ok(());
+        };
+        let mut fb_row = vec![0u8; width * self.screen_info.bytes_per_pixel()];
+        for row in 0..height {
+            let i = (((y + row) as i64 - dst_ul_y as i64) as usize * area_width as usize
+                + (x as i64 - dst_ul_x as i64) as usize)
+                * 4;
+            let Some(src_row) = src.get(i..i + width * 4) else {
+                break;
+            };
+            self.screen_info.bgra_to_pixels(src_row, &mut fb_row);
+            self.file
+                .write_all_at(&fb_row, self.row_offset(x, y + row))
+                .map_err(|e| {
+                    PixelCasterError::backend(format!("could not write the framebuffer: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Captures the area's current content, lets `blend` draw onto it and writes the result back
+    fn blend_rect(
+        &self,
+        ul_x: i32,
+        ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        blend: impl FnOnce(&VirtualScreen) -> Result<(), PixelCasterError>,
+    ) -> Result<(), PixelCasterError> {
+        let mut current = <u8>::initialize_vec(area_width as usize, area_height as usize);
+        self.capture_rect(&mut current, ul_x, ul_y, area_width, area_height)?;
+        let blended = VirtualScreen::from_pixels(PixelsCollection::<u8>::create(
+            area_width as usize,
+            area_height as usize,
+            current,
+        )?);
+        blend(&blended)?;
+        let blended = blended.framebuffer();
+        self.write_rect(&blended.bytes, ul_x, ul_y, area_width, area_height)
+    }
+
+    /// Offset in the file of the given visible pixel
+    fn row_offset(&self, x: usize, y: usize) -> u64 {
+        (y as u64 + self.screen_info.yoffset as u64) * self.screen_info.line_length as u64
+            + (x as u64 + self.screen_info.xoffset as u64)
+                * self.screen_info.bytes_per_pixel() as u64
+    }
+
+    /// Returns the part (x, y, width, height) of the given area that is inside the visible resolution, None if there's none
+    fn visible_part(
+        &self,
+        ul_x: i32,
+        ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Option<(usize, usize, usize, usize)> {
+        let x0 = (ul_x as i64).max(0);
+        let y0 = (ul_y as i64).max(0);
+        let x1 = (ul_x as i64 + area_width as i64).min(self.screen_info.width as i64);
+        let y1 = (ul_y as i64 + area_height as i64).min(self.screen_info.height as i64);
+        if x0 >= x1 || y0 >= y1 {
+            return None;
+        }
+        Some((
+            x0 as usize,
+            y0 as usize,
+            (x1 - x0) as usize,
+            (y1 - y0) as usize,
+        ))
+    }
+}
+
+impl ScreenBackend for FramebufferBackend {
+    /// Opens the device set by the FRAMEBUFFER environment variable (DEFAULT_FRAMEBUFFER_DEVICE if not set)
+    fn open(
+        _area_width: u32,
+        _area_height: u32,
+        _is_static: bool,
+    ) -> Result<FramebufferBackend, PixelCasterError> {
+        let device =
+            std::env::var("FRAMEBUFFER").unwrap_or_else(|_| DEFAULT_FRAMEBUFFER_DEVICE.to_string());
+        Self::open_device(&device)
+    }
+
+    /// Pixels outside of the visible resolution are captured as 0s
+    fn capture_rect(
+        &self,
+        dst: &mut [u8],
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Result<(), PixelCasterError> {
+        dst.fill(0);
+        let Some((x, y, width, height)) =
+            self.visible_part(src_ul_x, src_ul_y, area_width, area_height)
+        else {
+            return Ok(());
+        };
+        let mut fb_row = vec![0u8; width * self.screen_info.bytes_per_pixel()];
+        for row in 0..height {
+            self.file
+                .read_exact_at(&mut fb_row, self.row_offset(x, y + row))
+                .map_err(|e| {
+                    PixelCasterError::backend(format!("could not read the framebuffer: {}", e))
+                })?;
+            let i = (((y + row) as i64 - src_ul_y as i64) as usize * area_width as usize
+                + (x as i64 - src_ul_x as i64) as usize)
+                * 4;
+            if let Some(dst_row) = dst.get_mut(i..i + width * 4) {
+                self.screen_info.pixels_to_bgra(&fb_row, dst_row);
+            }
+        }
+        Ok(())
+    }
+
+    /// The pixels are blended in software with the framebuffer's content as defined by the PixelsSendMode
+    fn present_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> Result<(), PixelCasterError> {
+        self.blend_rect(dst_ul_x, dst_ul_y, area_width, area_height, |blended| {
+            blended.present_rect(src, 0, 0, area_width, area_height, pixels_send_mode)
+        })
+    }
+
+    fn present_premultiplied_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        source_constant_alpha: u8,
+    ) -> Result<(), PixelCasterError> {
+        self.blend_rect(dst_ul_x, dst_ul_y, area_width, area_height, |blended| {
+            blended.present_premultiplied_rect(
+                src,
+                0,
+                0,
+                area_width,
+                area_height,
+                source_constant_alpha,
+            )
+        })
+    }
+
+    fn copy_rect(
+        &self,
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+    ) -> Result<(), PixelCasterError> {
+        let mut captured = <u8>::initialize_vec(area_width as usize, area_height as usize);
+        self.capture_rect(&mut captured, src_ul_x, src_ul_y, area_width, area_height)?;
+        self.write_rect(&captured, dst_ul_x, dst_ul_y, area_width, area_height)
+    }
+
+    fn screen_bounds(&self) -> ScreenArea {
+        ScreenArea::new(0, 0, self.screen_info.width, self.screen_info.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Screen;
+
+    /// Creates a plain file sized like the framebuffer described by the screen info, filled with `byte`
+    fn framebuffer_file(name: &str, screen_info: &FbScreenInfo, byte: u8) -> String {
+        let path =
+            std::env::temp_dir().join(format!("pixel_caster_{}_{}.fb", name, std::process::id()));
+        let size = (screen_info.yoffset + screen_info.height) * screen_info.line_length;
+        std::fs::write(&path, vec![byte; size as usize]).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn parse_screen_info() {
+        // sizes of the kernel's structs, which the ioctls fill
+        assert_eq!(std::mem::size_of::<FbVarScreenInfoRaw>(), 160);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(std::mem::size_of::<FbFixScreenInfoRaw>(), 80);
+
+        let mut var = FbVarScreenInfoRaw {
+            xres: 800,
+            yres: 600,
+            xres_virtual: 800,
+            yres_virtual: 1200,
+            yoffset: 600,
+            bits_per_pixel: 16,
+            ..Default::default()
+        };
+        (var.red.offset, var.red.length) = (11, 5);
+        (var.green.offset, var.green.length) = (5, 6);
+        (var.blue.offset, var.blue.length) = (0, 5);
+        let mut fix = FbFixScreenInfoRaw {
+            line_length: 1600,
+            visual: FB_VISUAL_TRUECOLOR,
+            ..Default::default()
+        };
+        let mut expected = FbScreenInfo::rgb565(800, 600);
+        expected.yoffset = 600;
+        assert_eq!(FbScreenInfo::from_raw(&var, &fix).unwrap(), expected);
+
+        fix.visual = 3; // FB_VISUAL_PSEUDOCOLOR
+        assert!(FbScreenInfo::from_raw(&var, &fix).is_err());
+        fix.visual = FB_VISUAL_TRUECOLOR;
+        var.bits_per_pixel = 8;
+        assert!(FbScreenInfo::from_raw(&var, &fix).is_err());
+    }
+
+    #[test]
+    fn rgb565_conversion() {
+        let screen_info = FbScreenInfo::rgb565(2, 1);
+        let mut fb_bytes = [0u8; 4];
+        screen_info.bgra_to_pixels(&[255, 0, 0, 255, 0, 255, 255, 255], &mut fb_bytes);
+        assert_eq!(fb_bytes, [0x1F, 0x00, 0xE0, 0xFF]);
+        let mut bgra = [0u8; 8];
+        screen_info.pixels_to_bgra(&fb_bytes, &mut bgra);
+        assert_eq!(bgra, [255, 0, 0, 255, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn padded_and_panned_file() {
+        let mut screen_info = FbScreenInfo::bgra32(4, 3);
+        screen_info.transp = FbChannel::new(0, 0);
+        screen_info.line_length = 4 * 4 + 8;
+        screen_info.yoffset = 3;
+        let path = framebuffer_file("padded_and_panned", &screen_info, 0);
+        let backend = FramebufferBackend::open_file(&path, screen_info.clone()).unwrap();
+
+        let mut screen =
+            Screen::<u8, FramebufferBackend>::with_backend(backend.clone(), 3, 2, 2, 2);
+        screen.get_bytes_mut().copy_from_slice(&[
+            10, 20, 30, 255, 40, 50, 60, 255, 70, 80, 90, 255, 1, 2, 3, 255,
+        ]);
+        screen.update_area().unwrap();
+
+        // only the upper left pixel of the area is visible, it's in the last row of the panned visible area
+        let file_bytes = std::fs::read(&path).unwrap();
+        let offset = (3 + 2) * screen_info.line_length as usize + 3 * 4;
+        assert_eq!(file_bytes[offset..offset + 4], [10, 20, 30, 255]);
+        assert_eq!(file_bytes.iter().filter(|&&b| b != 0).count(), 4);
+
+        screen.scan_area().unwrap();
+        assert_eq!(
+            screen.get_bytes(),
+            &[10, 20, 30, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn blending_and_copy() {
+        let screen_info = FbScreenInfo::rgb565(4, 4);
+        let path = framebuffer_file("blending_and_copy", &screen_info, 0);
+        let backend = FramebufferBackend::open_file(&path, screen_info).unwrap();
+
+        backend
+            .present_rect(
+                &[255, 255, 255, 255],
+                0,
+                0,
+                1,
+                1,
+                PixelsSendMode::AlphaDisabled,
+            )
+            .unwrap();
+        backend
+            .present_rect(
+                &[0, 0, 0, 255],
+                0,
+                0,
+                1,
+                1,
+                PixelsSendMode::CustomAlpha(128),
+            )
+            .unwrap();
+        backend.copy_rect(0, 0, 1, 1, 3, 3).unwrap();
+        let mut captured = vec![0u8; 4];
+        backend.capture_rect(&mut captured, 3, 3, 1, 1).unwrap();
+        // half of white, as close as 5 and 6 bits per channel allow
+        assert_eq!(captured, [123, 125, 123, 255]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
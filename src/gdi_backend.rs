@@ -0,0 +1,493 @@
+use windows::Win32::{
+    // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/
+    Graphics::Gdi::{
+        AlphaBlend, CreateBitmap, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC,
+        DeleteObject, GetBitmapBits, GetDC, ReleaseDC, SelectObject, TransparentBlt, AC_SRC_ALPHA,
+        AC_SRC_OVER, BLENDFUNCTION, HBITMAP, HDC,
+    },
+    UI::WindowsAndMessaging::{
+        GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
+        SM_YVIRTUALSCREEN,
+    },
+};
+
+use crate::{
+    bitblock_transfer, c_void, PixelCasterError, PixelValues, PixelsSendMode, ScreenArea,
+    ScreenBackend,
+};
+
+/// Stuff used to work with the winapi
+#[derive(Clone)]
+pub struct GdiBackend {
+    /// Gets a handle (H) to a device context (DC) for the client area,
+    /// in this case for the entire virtual screen (not just a monitor),
+    /// instead of a window (from hwnd value)
+    /// this is the handle (H) of a memory device context (DC) to which send data (BGRA colors)
+    /// Used either as a handle (H) of a memory device context (DC) to/from which send/capture data (BGRA colors)
+    screen: HDC,
+    /// Create a compatible bitmap of the requested pixel area (area_width x area_height px).
+    /// Used either as a handle (H) of a memory device context (DC) from/to which capture/send data (BGRA colors)
+    dc_screen: HDC,
+    /// requested pixels' area width and height to be captured
+    captured_hbmp: HBITMAP,
+    /// Determines if the values are to keep after use or not
+    is_static: bool,
+}
+unsafe impl Send for GdiBackend {}
+unsafe impl Sync for GdiBackend {}
+
+/// Former name of GdiBackend, kept for backwards compatibility, to remove at version 2.0
+pub type WindowsApiScreen = GdiBackend;
+
+impl ScreenBackend for GdiBackend {
+    /// Prepares the stuff needed to make the Windows API to manage the screen's pixel's data
+    fn open(
+        area_width: u32,
+        area_height: u32,
+        is_static: bool,
+    ) -> Result<GdiBackend, PixelCasterError> {
+        unsafe {
+            let screen = GetDC(None);
+            Ok(GdiBackend {
+                screen,
+                dc_screen: CreateCompatibleDC(screen),
+                captured_hbmp: CreateCompatibleBitmap(
+                    screen,
+                    area_width as i32,
+                    area_height as i32,
+                ),
+                is_static,
+            })
+        }
+    }
+
+    /// Gets the bytes from the pixels of a screen area of the requested size, starting from an absolute position on the screen.
+    /// The bytes are retrieved row by row.
+    fn capture_rect(
+        &self,
+        dst: &mut [u8],
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Result<(), PixelCasterError> {
+        unsafe {
+            let hbmp_replace = SelectObject(self.dc_screen, self.captured_hbmp);
+
+            let result = bitblock_transfer::bit_block_transfer(
+                self.dc_screen,
+                0,
+                0,
+                area_width,
+                area_height,
+                self.screen,
+                src_ul_x,
+                src_ul_y,
+            )
+            .and_then(|()| {
+                // add the captured area's pixels BGRA values to the provided slice
+                // DOESENT WORK WITH VEC created with Vec::with_capacity(), only with already populated, e.g.: with vec![;]
+                // to make it work with Vec<T> created with Vec::with_capacity(area_width*area_height*4) must then call vec.set_len(area_width*area_height*4) , which is unsafe
+                if GetBitmapBits(
+                    self.captured_hbmp,
+                    dst.len() as i32,
+                    dst.as_mut_ptr() as *mut c_void,
+                ) == 0
+                {
+                    return Err(PixelCasterError::backend(
+                        "GetBitmapBits could not copy the captured pixels",
+                    ));
+                }
+                Ok(())
+            });
+
+            // If the backend was made for a single run delete its elements. Must delete the created elements, otherwise after many calls the api will stop working for the whole duration of this .exe process
+            if !self.is_static {
+                ReleaseDC(None, self.dc_screen);
+                ReleaseDC(None, self.screen);
+                // This function returns the previously selected object of the specified type.
+                // An application should always replace a new object with the original,
+                // default object after it has finished drawing with the new object.
+                SelectObject(self.dc_screen, hbmp_replace);
+                let _ = DeleteDC(self.dc_screen);
+                let _ = DeleteObject(self.captured_hbmp);
+            }
+            result
+        }
+    }
+
+    fn present_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> Result<(), PixelCasterError> {
+        match pixels_send_mode {
+            PixelsSendMode::AlphaEnabled => self.send_bytes_to_screen(
+                &<u8>::create_adjusted_vec(src),
+                dst_ul_x,
+                dst_ul_y,
+                area_width,
+                area_height,
+                255,
+            ),
+            PixelsSendMode::AlphaDisabled => self.send_bytes_to_screen_alpha_disabled(
+                src,
+                dst_ul_x,
+                dst_ul_y,
+                area_width,
+                area_height,
+            ),
+            PixelsSendMode::AlphaDisabledHideBGR(b, g, r) => self
+                .send_bytes_to_screen_alpha_disabled_hide_specific_bgr(
+                    src,
+                    dst_ul_x,
+                    dst_ul_y,
+                    area_width,
+                    area_height,
+                    b,
+                    g,
+                    r,
+                ),
+            PixelsSendMode::CustomAlpha(custom_alpha) => {
+                if custom_alpha == 255 {
+                    self.send_bytes_to_screen_alpha_disabled(
+                        src,
+                        dst_ul_x,
+                        dst_ul_y,
+                        area_width,
+                        area_height,
+                    )
+                } else {
+                    self.send_bytes_to_screen(
+                        &<u8>::create_adjusted_vec(src),
+                        dst_ul_x,
+                        dst_ul_y,
+                        area_width,
+                        area_height,
+                        custom_alpha,
+                    )
+                }
+            }
+        }
+    }
+
+    fn present_premultiplied_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        source_constant_alpha: u8,
+    ) -> Result<(), PixelCasterError> {
+        self.send_bytes_to_screen(
+            src,
+            dst_ul_x,
+            dst_ul_y,
+            area_width,
+            area_height,
+            source_constant_alpha,
+        )
+    }
+
+    fn copy_rect(
+        &self,
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+    ) -> Result<(), PixelCasterError> {
+        unsafe {
+            // Create a compatible bitmap of the requested pixel area (area_width x area_height px).
+            // get a handle (H) of a memory device context (DC) from which capture data (pixels)
+            let captured_screen = CreateCompatibleDC(self.screen);
+
+            // requested pixels' area width and height to be captured
+            let captured_hbmp =
+                CreateCompatibleBitmap(self.screen, area_width as i32, area_height as i32);
+
+            let hbmp_replace = SelectObject(captured_screen, captured_hbmp);
+
+            // get the data of a given area from screen ad set it to captured_screen
+            let captured_screen_upperleftcorner_x = 0;
+            let captured_screen_upperleftcorner_y = 0;
+
+            let result = bitblock_transfer::bit_block_transfer(
+                captured_screen,
+                captured_screen_upperleftcorner_x,
+                captured_screen_upperleftcorner_y,
+                area_width,
+                area_height,
+                self.screen,
+                src_ul_x,
+                src_ul_y,
+            )
+            .and_then(|()| {
+                // print to screen
+                // source and destination pixel area set as the same
+                let pixels_upperleftcorner_x = 0;
+                let pixels_upperleftcorner_y = 0;
+
+                bitblock_transfer::bit_block_transfer(
+                    self.screen,
+                    dst_ul_x,
+                    dst_ul_y,
+                    area_width,
+                    area_height,
+                    captured_screen,
+                    pixels_upperleftcorner_x,
+                    pixels_upperleftcorner_y,
+                )
+            });
+
+            ReleaseDC(None, captured_screen);
+            // This function returns the previously selected object of the specified type.
+            // An application should always replace a new object with the original,
+            // default object after it has finished drawing with the new object.
+            SelectObject(captured_screen, hbmp_replace);
+            let _ = DeleteDC(captured_screen);
+            let _ = DeleteObject(captured_hbmp);
+
+            // If the backend was made for a single run delete its elements
+            if !self.is_static {
+                ReleaseDC(None, self.screen);
+                let _ = DeleteDC(self.dc_screen);
+                let _ = DeleteObject(self.captured_hbmp);
+            }
+            result
+        }
+    }
+
+    /// Returns the area of the entire virtual screen (the bounding rectangle of all the monitors)
+    fn screen_bounds(&self) -> ScreenArea {
+        unsafe {
+            ScreenArea::new(
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN) as u32,
+                GetSystemMetrics(SM_CYVIRTUALSCREEN) as u32,
+            )
+        }
+    }
+}
+
+impl GdiBackend {
+    /// send Blue Green Red Alpha values to the pixels of a defined area of the screen
+    /// source_constant_alpha sets the Alpha value of every BGRA (so it sets the whole image's opacity , range : 0-255)
+    /// set source_constant_alpha to 255 in order to use per-pixel alpha values
+    /// The color chunks must be in BGRA, with Blue Green Red already premultiplied by the Alpha
+    fn send_bytes_to_screen(
+        &self,
+        vec: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        source_constant_alpha: u8,
+    ) -> Result<(), PixelCasterError> {
+        unsafe {
+            // create HBITMAP from a BGRA color pattern sequence array
+            let hbmp_from_bytes = CreateBitmap(
+                area_width as i32,
+                area_height as i32,
+                1,
+                // B        G        R        A
+                // 0-255    0-255    0-255    0-255
+                // 2^8 = 256 , so 8 bits are required to represent a color's range of values
+                // so each one of the colors is represented by an unsigned 8 bit integer (u8)
+
+                // a single pixel is formed by BGRA (Blue,Green,Red,Alpha)
+                // the combination of their values gives the resulting color to a pixel
+                // the range of each of their values : 0-255 (so : 0-255,0-255,0-255,0-255)
+                // all 255 results in a black pixel, all 0 in a white pixel, 255,0,0,255 in a blue one
+                // to represent values of range : 0-255 are necessary 8 bits ( 2^8 = 256 ), so the following bitcount must be 32
+                // because 8+8+8+8 = 32, 32 bits are necessary to represent a pixel's combination of B,G,R,A
+                32,
+                // uses BGRA format instead of RGBA
+                // https://stackoverflow.com/questions/31759582/assign-an-array-to-mut-c-void
+                Some(vec.as_ptr() as *mut c_void), //&vec as *const Vec<u8> as *mut c_void
+            );
+            let hbmp_replace = SelectObject(self.dc_screen, hbmp_from_bytes);
+
+            // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/ns-wingdi-blendfunction
+            let bf = BLENDFUNCTION {
+                BlendOp: AC_SRC_OVER as u8,
+                BlendFlags: 1,
+                // Set the SourceConstantAlpha value to 255 (opaque) when you only want to use per-pixel alpha values
+                SourceConstantAlpha: source_constant_alpha,
+                // This flag is set when the bitmap has an Alpha channel (that is, per-pixel alpha).
+                // The Windows API use premultiplied alpha, which means that the Red, Green and Blue channel values must be premultiplied with the Alpha channel value.
+                // For example, if the alpha channel value is x, the Red, Green and Blue channels must be multiplied by x and divided by 0xff (255) prior to the call.
+                AlphaFormat: AC_SRC_ALPHA as u8,
+            };
+
+            // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-alphablend
+            let result = AlphaBlend(
+                // the entire virtual screen (not just a monitor)
+                self.screen,
+                dst_ul_x,
+                dst_ul_y,
+                area_width as i32,
+                area_height as i32,
+                self.dc_screen,
+                0,
+                0,
+                area_width as i32,
+                area_height as i32,
+                bf,
+            )
+            .ok()
+            .map_err(PixelCasterError::from);
+
+            // If the backend was made for a single run delete its elements. Must delete the created elements, otherwise after many calls the api will stop working for the whole duration of this .exe process
+            if !self.is_static {
+                ReleaseDC(None, self.screen);
+                // This function returns the previously selected object of the specified type.
+                // An application should always replace a new object with the original,
+                // default object after it has finished drawing with the new object.
+                SelectObject(self.dc_screen, hbmp_replace);
+                let _ = DeleteDC(self.dc_screen);
+                let _ = DeleteObject(self.captured_hbmp);
+                let _ = DeleteObject(hbmp_from_bytes);
+            }
+            result
+        }
+    }
+
+    /// Sends the bytes to the pixels of a screen area of the requested size, starting from an absolute position on the screen.
+    /// The bytes are sent row by row, the Alpha value in BlueGreenRedAlpha, that is used to define transparency,
+    /// will be ignored, as it will be max by default (255), so every pixel will have full opacity
+    /// The color chunks must be in BGRA
+    fn send_bytes_to_screen_alpha_disabled(
+        &self,
+        vec: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Result<(), PixelCasterError> {
+        unsafe {
+            // create HBITMAP from a BGRA color pattern sequence array
+            let hbmp_from_bytes = CreateBitmap(
+                area_width as i32,
+                area_height as i32,
+                1,
+                // 8+8+8+8 = 32, 32 bits are necessary to represent a pixel's combination of B,G,R,A
+                32,
+                // uses BGR format instead of RGB
+                // https://stackoverflow.com/questions/31759582/assign-an-array-to-mut-c-void
+                Some(vec.as_ptr() as *mut c_void), //&vec as *const Vec<u8> as *mut c_void
+            );
+
+            let hbmp_replace = SelectObject(self.dc_screen, hbmp_from_bytes);
+
+            let pixels_upperleftcorner_x = 0;
+            let pixels_upperleftcorner_y = 0;
+
+            // bit-block transfer of the color data corresponding to an area of pixels
+            // of the RGBA sequence it doesn't print the 4th value (A : alpha,opacity) so only RGB, the A won't be used
+            let result = bitblock_transfer::bit_block_transfer(
+                self.screen,
+                dst_ul_x,
+                dst_ul_y,
+                area_width,
+                area_height,
+                self.dc_screen,
+                pixels_upperleftcorner_x,
+                pixels_upperleftcorner_y,
+            );
+
+            // If the backend was made for a single run delete its elements
+            if !self.is_static {
+                ReleaseDC(None, self.screen);
+                // This function returns the previously selected object of the specified type.
+                // An application should always replace a new object with the original,
+                // default object after it has finished drawing with the new object.
+                SelectObject(self.dc_screen, hbmp_replace);
+                let _ = DeleteDC(self.dc_screen);
+                let _ = DeleteObject(self.captured_hbmp);
+                let _ = DeleteObject(hbmp_from_bytes);
+            }
+            result
+        }
+    }
+
+    /// send Blue Green Red Alpha (ignored) values to the pixels of a defined area of the screen
+    /// and make so that if a BGRA value to be sent to a pixel matches a specific (A is ignored) BGR value
+    /// that color will be sent as completely transparent
+    /// e.g., every time a white (B=255, G=255, R=255, A=any_u8_value) is to be sent to a pixel it must be sent as completely transparent, invisible, hidden
+    /// The color chunks must be in BGRA
+    fn send_bytes_to_screen_alpha_disabled_hide_specific_bgr(
+        &self,
+        vec: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        hide_b: u8,
+        hide_g: u8,
+        hide_r: u8,
+    ) -> Result<(), PixelCasterError> {
+        unsafe {
+            // create HBITMAP from a BGRA color pattern sequence array
+            let hbmp_from_bytes = CreateBitmap(
+                area_width as i32,
+                area_height as i32,
+                1,
+                // 8+8+8+8 = 32, 32 bits are necessary to represent a pixel's combination of B,G,R,A
+                32,
+                // uses BGR format instead of RGB
+                // https://stackoverflow.com/questions/31759582/assign-an-array-to-mut-c-void
+                Some(vec.as_ptr() as *mut c_void), //&vec as *const Vec<u8> as *mut c_void
+            );
+
+            let hbmp_replace = SelectObject(self.dc_screen, hbmp_from_bytes);
+
+            let pixels_upperleftcorner_x = 0;
+            let pixels_upperleftcorner_y = 0;
+
+            // the COLORREF to hide is in 0x00BBGGRR order
+            // e.g.: to hide the White ( obtained by value 255 of B,G,R combined, in B G R A) : 0x00FF FFFF
+            // to hide the Blue ( obtained by value 255 of B value in B G R A) : 0x00FF 0000
+            let bgr_u32_to_hide = u32::from_ne_bytes([hide_r, hide_g, hide_b, 0]);
+
+            // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-transparentblt
+            let result = TransparentBlt(
+                self.screen,
+                dst_ul_x,
+                dst_ul_y,
+                area_width as i32,
+                area_height as i32,
+                self.dc_screen,
+                pixels_upperleftcorner_x,
+                pixels_upperleftcorner_y,
+                area_width as i32,
+                area_height as i32,
+                // the A in BGRA will be ignored when matching the BGRA value between the one seeked and those present in the provided bytes
+                // so the matching is done on the BGR values alone
+                bgr_u32_to_hide,
+            )
+            .ok()
+            .map_err(PixelCasterError::from);
+
+            // If the backend was made for a single run delete its elements
+            if !self.is_static {
+                ReleaseDC(None, self.screen);
+                // This function returns the previously selected object of the specified type.
+                // An application should always replace a new object with the original,
+                // default object after it has finished drawing with the new object.
+                SelectObject(self.dc_screen, hbmp_replace);
+                let _ = DeleteDC(self.dc_screen);
+                let _ = DeleteObject(self.captured_hbmp);
+                let _ = DeleteObject(hbmp_from_bytes);
+            }
+            result
+        }
+    }
+}
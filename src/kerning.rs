@@ -0,0 +1,186 @@
+use crate::{
+    pixels_string::{CharsCollection, PixelsChar},
+    PixelCasterError,
+};
+
+/// A char's visible pixels, per row of the line (from the top of the tallest char):
+/// the empty columns from its origin to the leftmost one, and from the rightmost one to the next char's origin
+type Silhouette = Vec<Option<(i32, i32)>>;
+
+impl CharsCollection<u8> {
+    /// Adds the kerning pairs of BMFont data, in its text (`kerning first=84 second=111 amount=-2`) or XML (`<kerning first="84" second="111" amount="-2"/>`) format,
+    /// replacing those already set for the same pairs. Returns how many pairs were read
+    pub fn load_bmfont_kerning(&mut self, bmfont: &str) -> Result<usize, PixelCasterError> {
+        let mut count = 0;
+        for line in bmfont.lines() {
+            let line = line.trim().trim_start_matches('<');
+            let Some(attributes) = line.strip_prefix("kerning ") else {
+                continue;
+            };
+            let (mut first, mut second, mut amount) = (None, None, None);
+            for attribute in attributes
+                .trim_end_matches('>')
+                .trim_end_matches('/')
+                .split_whitespace()
+            {
+                let Some((key, value)) = attribute.split_once('=') else {
+                    continue;
+                };
+                let value = value.trim_matches('"');
+                match key {
+                    "first" => first = value.parse::<u32>().ok().and_then(char::from_u32),
+                    "second" => second = value.parse::<u32>().ok().and_then(char::from_u32),
+                    "amount" => amount = value.parse::<i32>().ok(),
+                    _ => {}
+                }
+            }
+            match (first, second, amount) {
+                (Some(first), Some(second), Some(amount)) => {
+                    self.kerning.insert((first, second), amount);
+                    count += 1;
+                }
+                _ => {
+                    return Err(PixelCasterError::decode(format!(
+                        "invalid BMFont kerning: {}",
+                        line
+                    )))
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Sets the kerning of every pair of chars from their bitmaps: the closest horizontal distance between the first char's visible pixels
+    /// and the second one's (in the rows where both have some) is brought to the most common (median) distance among all the pairs,
+    /// adjusting it by at most max_adjustment pixels. The pairs without rows in common (e.g.: '-' and '.') are left without kerning.
+    /// Returns how many pairs have a kerning
+    pub fn derive_kerning(&mut self, max_adjustment: u32) -> usize {
+        let ascent = self
+            .chars
+            .iter()
+            .map(|c| c.metrics.baseline)
+            .max()
+            .unwrap_or(0);
+        let silhouettes: Vec<(char, Silhouette)> = self
+            .chars
+            .iter()
+            .map(|c| (c.char, silhouette(c, ascent)))
+            .collect();
+
+        let mut distances = Vec::new();
+        for (first, first_silhouette) in &silhouettes {
+            for (second, second_silhouette) in &silhouettes {
+                let closest = first_silhouette
+                    .iter()
+                    .zip(second_silhouette)
+                    .filter_map(|rows| match rows {
+                        (Some((_, right_gap)), Some((left_gap, _))) => Some(right_gap + left_gap),
+                        _ => None,
+                    })
+                    .min();
+                if let Some(closest) = closest {
+                    distances.push(((*first, *second), closest));
+                }
+            }
+        }
+
+        self.kerning.clear();
+        let mut sorted: Vec<i32> = distances.iter().map(|(_, d)| *d).collect();
+        sorted.sort_unstable();
+        let Some(&median) = sorted.get(sorted.len() / 2) else {
+            return 0;
+        };
+        let max_adjustment = max_adjustment as i32;
+        for (pair, closest) in distances {
+            let adjustment = (median - closest).clamp(-max_adjustment, max_adjustment);
+            if adjustment != 0 {
+                self.kerning.insert(pair, adjustment);
+            }
+        }
+        self.kerning.len()
+    }
+}
+
+fn silhouette(char: &PixelsChar<u8>, ascent: usize) -> Silhouette {
+    let (width, metrics) = (char.pixels.width, char.metrics);
+    let top = ascent.saturating_sub(metrics.baseline);
+    let mut silhouette = vec![None; top + char.pixels.height];
+    for (y, row) in char.pixels.bytes.chunks_exact(4 * width.max(1)).enumerate() {
+        let mut visible = row
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, bgra)| bgra[3] > 0);
+        if let Some((leftmost, _)) = visible.next() {
+            let rightmost = visible.next_back().map_or(leftmost, |(x, _)| x);
+            silhouette[top + y] = Some((
+                metrics.left_bearing + leftmost as i32,
+                metrics.advance - metrics.left_bearing - rightmost as i32 - 1,
+            ));
+        }
+    }
+    silhouette
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::pixels_string::{CharsCollection, PixelsChar, BGRA};
+
+    // 'T' has its bar on the first of 4 rows, 'o' fills the last 2 rows, 'l' all of them
+    fn chars() -> CharsCollection<u8> {
+        let char = |c: char, width: usize, rows: &[&str]| {
+            let bytes = rows
+                .iter()
+                .flat_map(|row| row.chars())
+                .flat_map(|p| if p == '#' { [0, 0, 0, 255] } else { [0; 4] })
+                .collect();
+            PixelsChar::create(c, &c.to_string(), width, rows.len(), bytes).unwrap()
+        };
+        CharsCollection {
+            chars: vec![
+                char('l', 1, &["#", "#", "#", "#"]),
+                char('T', 3, &["###", ".#.", ".#.", ".#."]),
+                char('o', 2, &["..", "..", "##", "##"]),
+            ],
+            path: String::new(),
+            bgra: BGRA(0, 0, 0, 255),
+            kerning: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn derived_kerning() {
+        let mut chars = chars();
+        let unkerned_width = chars.create_pixels_string("To", 0).pixels.width;
+        assert_eq!(chars.derive_kerning(2), 2);
+        assert_eq!(
+            chars.kerning,
+            HashMap::from([(('T', 'o'), -1), (('o', 'T'), -1)])
+        );
+        let kerned = chars.create_pixels_string("To", 0).pixels;
+        assert_eq!((unkerned_width, kerned.width), (5, 4));
+        // the 'o' moved under the 'T' bar
+        assert_eq!(kerned.bytes[(2 * 4 + 2) * 4 + 3], 255);
+        // the global spacing still applies
+        assert_eq!(chars.create_pixels_string("To", 2).pixels.width, 8);
+    }
+
+    #[test]
+    fn bmfont_kerning() {
+        let mut chars = chars();
+        let text = "info face=\"Sample\" size=4\nkernings count=2\nkerning first=84  second=111 amount=-2\nkerning first=108 second=108 amount=1";
+        assert_eq!(chars.load_bmfont_kerning(text).unwrap(), 2);
+        let xml = "<kernings count=\"1\">\n  <kerning first=\"111\" second=\"84\" amount=\"-1\" />\n</kernings>";
+        assert_eq!(chars.load_bmfont_kerning(xml).unwrap(), 1);
+        assert_eq!(
+            chars.kerning,
+            HashMap::from([(('T', 'o'), -2), (('l', 'l'), 1), (('o', 'T'), -1)])
+        );
+        assert_eq!(chars.create_pixels_string("ll", 0).pixels.width, 3);
+
+        assert!(chars
+            .load_bmfont_kerning("kerning first=84 amount=-2")
+            .is_err());
+    }
+}
@@ -1,6 +1,6 @@
 // Down here are the legacy functions
 
-use crate::{bitblock_transfer, PixelValues};
+use crate::{bitblock_transfer, PixelCasterError, PixelValues};
 pub use libc::c_void;
 use windows::Win32::{
     // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/
@@ -12,13 +12,14 @@ use windows::Win32::{
 };
 
 /// Gets the bytes from the pixels of a screen area of the requested size, starting from an absolute position on the screen.
-/// The bytes are retrieved row by row. Returns a Vec of specified type (u8/u32) ( e.g.: let vec_u8 :Vec<u8> = get_bytes(...) ).
+/// The bytes are retrieved row by row. Returns a Vec of specified type (u8/u32) ( e.g.: let vec_u8 :Vec<u8> = get_bytes(...)? ),
+/// or the error of the GDI call that failed
 pub fn get_bytes<T: PixelValues<T>>(
     area_width: u32,
     area_height: u32,
     src_ul_x: i32,
     src_ul_y: i32,
-) -> Vec<T> {
+) -> Result<Vec<T>, PixelCasterError> {
     unsafe {
         // get a handle (H) to a device context (DC) for the client area,
         // in this case for the entire virtual screen (not just a monitor),
@@ -39,7 +40,7 @@ pub fn get_bytes<T: PixelValues<T>>(
         let dst_ul_x = 0;
         let dst_ul_y = 0;
 
-        bitblock_transfer::bit_block_transfer(
+        let result = bitblock_transfer::bit_block_transfer(
             dst_screen,
             dst_ul_x,
             dst_ul_y,
@@ -48,19 +49,26 @@ pub fn get_bytes<T: PixelValues<T>>(
             screen,
             src_ul_x,
             src_ul_y,
-        );
-
-        // create a Vec of u8 values (R,G,B(,A) values range : 0-255), with the size needed to represent the area of the screenshot to take
-        //let mut vec :Vec<u8> = vec![0; area_width as usize * area_height as usize * 4];
-
-        let mut vec = <T>::initialize_vec(area_width as usize, area_height as usize);
-        //get_bitmap_bits(captured_hbmp, &mut vec);
-        // add the captured area's pixels RGB values to the Vec
-        GetBitmapBits(
-            captured_hbmp,
-            (area_width * area_height * 4) as i32,
-            vec.as_mut_ptr() as *mut c_void,
-        );
+        )
+        .and_then(|()| {
+            // create a Vec of u8 values (R,G,B(,A) values range : 0-255), with the size needed to represent the area of the screenshot to take
+            //let mut vec :Vec<u8> = vec![0; area_width as usize * area_height as usize * 4];
+
+            let mut vec = <T>::initialize_vec(area_width as usize, area_height as usize);
+            //get_bitmap_bits(captured_hbmp, &mut vec);
+            // add the captured area's pixels RGB values to the Vec
+            if GetBitmapBits(
+                captured_hbmp,
+                (area_width * area_height * 4) as i32,
+                vec.as_mut_ptr() as *mut c_void,
+            ) == 0
+            {
+                return Err(PixelCasterError::backend(
+                    "GetBitmapBits could not copy the captured pixels",
+                ));
+            }
+            Ok(vec)
+        });
 
         //std::mem::forget(vec);
         ReleaseDC(None, dst_screen);
@@ -69,9 +77,9 @@ pub fn get_bytes<T: PixelValues<T>>(
         // An application should always replace a new object with the original,
         // default object after it has finished drawing with the new object.
         SelectObject(dst_screen, hbmp_replace);
-        DeleteDC(dst_screen).unwrap();
-        DeleteObject(captured_hbmp).unwrap();
-        vec
+        let _ = DeleteDC(dst_screen);
+        let _ = DeleteObject(captured_hbmp);
+        result
     }
 }
 
@@ -86,7 +94,7 @@ pub fn send_bytes<T: PixelValues<T> + Copy>(
     dst_ul_x: i32,
     dst_ul_y: i32,
     source_constant_alpha: u8,
-) {
+) -> Result<(), PixelCasterError> {
     unsafe {
         //let mut vec :Vec<u8> = vec![0,0,255,255,0,0,255,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255];
 
@@ -139,7 +147,7 @@ pub fn send_bytes<T: PixelValues<T> + Copy>(
         };
 
         // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-alphablend
-        AlphaBlend(
+        let result = AlphaBlend(
             screen,
             dst_ul_x,
             dst_ul_y,
@@ -152,7 +160,8 @@ pub fn send_bytes<T: PixelValues<T> + Copy>(
             area_height as i32,
             bf,
         )
-        .unwrap();
+        .ok()
+        .map_err(PixelCasterError::from);
 
         //std::mem::forget(vec);
 
@@ -161,8 +170,9 @@ pub fn send_bytes<T: PixelValues<T> + Copy>(
         // An application should always replace a new object with the original,
         // default object after it has finished drawing with the new object.
         SelectObject(dc_src, hbmp_replace);
-        DeleteDC(dc_src).unwrap();
-        DeleteObject(hbmp_from_bytes).unwrap();
+        let _ = DeleteDC(dc_src);
+        let _ = DeleteObject(hbmp_from_bytes);
+        result
     }
 }
 
@@ -176,7 +186,7 @@ pub fn send_bytes_alpha_disabled<T>(
     area_height: u32,
     dst_ul_x: i32,
     dst_ul_y: i32,
-) {
+) -> Result<(), PixelCasterError> {
     unsafe {
         //let mut vec :Vec<u8> = vec![0,0,255,255,0,0,255,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255];
 
@@ -219,7 +229,7 @@ pub fn send_bytes_alpha_disabled<T>(
 
         // bit-block transfer of the color data corresponding to an area of pixels
         // of the RGBA sequence it doesn't print the 4th value (A : alpha,opacity) so only RGB, the A won't be used
-        bitblock_transfer::bit_block_transfer(
+        let result = bitblock_transfer::bit_block_transfer(
             screen,
             dst_ul_x,
             dst_ul_y,
@@ -237,8 +247,9 @@ pub fn send_bytes_alpha_disabled<T>(
         // An application should always replace a new object with the original,
         // default object after it has finished drawing with the new object.
         SelectObject(dc_src, hbmp_replace);
-        DeleteDC(dc_src).unwrap();
-        DeleteObject(hbmp_from_bytes).unwrap();
+        let _ = DeleteDC(dc_src);
+        let _ = DeleteObject(hbmp_from_bytes);
+        result
     }
 }
 
@@ -257,7 +268,7 @@ pub fn send_bytes_alpha_disabled_hide_specific_bgr<T>(
     hide_b: u8,
     hide_g: u8,
     hide_r: u8,
-) {
+) -> Result<(), PixelCasterError> {
     unsafe {
         //let mut vec :Vec<u8> = vec![0,0,255,255,0,0,255,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255,255,0,0,255];
 
@@ -301,7 +312,7 @@ pub fn send_bytes_alpha_disabled_hide_specific_bgr<T>(
         let bgr_u32_to_hide = u32::from_ne_bytes([hide_r, hide_g, hide_b, 0]);
 
         // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-transparentblt
-        TransparentBlt(
+        let result = TransparentBlt(
             screen,
             dst_ul_x,
             dst_ul_y,
@@ -347,7 +358,8 @@ pub fn send_bytes_alpha_disabled_hide_specific_bgr<T>(
             // use fn bgra_to_abgr_u32(b,g,r,a) to get the u32 value out of u8 bgra values
             bgr_u32_to_hide.to_owned(),
         )
-        .unwrap();
+        .ok()
+        .map_err(PixelCasterError::from);
         //std::mem::forget(vec);
 
         ReleaseDC(None, screen);
@@ -355,7 +367,8 @@ pub fn send_bytes_alpha_disabled_hide_specific_bgr<T>(
         // An application should always replace a new object with the original,
         // default object after it has finished drawing with the new object.
         SelectObject(dc_src, hbmp_replace);
-        DeleteDC(dc_src).unwrap();
-        DeleteObject(hbmp_from_bytes).unwrap();
+        let _ = DeleteDC(dc_src);
+        let _ = DeleteObject(hbmp_from_bytes);
+        result
     }
 }
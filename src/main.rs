@@ -16,7 +16,8 @@ fn main() {
         pixels_height,
         screen_destination_area_upperleftcorner_x,
         screen_destination_area_upperleftcorner_y,
-    );
+    )
+    .unwrap();
 
     screen_area_to_capture_upperleftcorner_x = 420;
     screen_area_to_capture_upperleftcorner_y = 110;
@@ -33,7 +34,7 @@ fn main() {
         pixels_width,
         pixels_height,
     );
-    screen_u8.scan_area();
+    screen_u8.scan_area().unwrap();
     /* Vec for testing : a qube of 4 x 4 (16) pixels, where the first 2 will be red, the other 14 blue
     let mut vec:Vec<u8> = Vec::with_capacity(4 * 4 * 4);
     vec.extend_from_slice(&[0,0,255,255,0,0,255,255]);
@@ -49,7 +50,8 @@ fn main() {
         pixels_width,
         pixels_height,
         PixelsSendMode::AlphaDisabledHideBGR(255, 255, 255),
-    );
+    )
+    .unwrap();
 
     // sets the BGRA sending method to AlphaEnabled in to use per-pixel alpha values
     let pixels_send_mode = PixelsSendMode::AlphaEnabled;
@@ -61,7 +63,8 @@ fn main() {
         pixels_width,
         pixels_height,
         pixels_send_mode,
-    );
+    )
+    .unwrap();
 }
 // based on
 // https://stackoverflow.com/questions/33669344/bitblt-captures-only-partial-screen
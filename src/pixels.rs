@@ -1,9 +1,8 @@
 use image::{DynamicImage, ImageBuffer, Rgb, Rgba};
 use serde::{Deserialize, Serialize};
 
-use crate::bgra_management::{
-    u32_bytes_oredered_indexes_and_fullvalues, ColorAlteration, SwitchBytes,
-};
+use crate::bgra_management::{ColorAlteration, SwitchBytes};
+use crate::PixelCasterError;
 
 /// BGRA for the invisible pixels (those to not display, Alpha = 0). B=G=R=A=0 combination stands for completely transparent black
 pub const BGRA_INVISIBLE_PIXEL: (u8, u8, u8, u8) = (0, 0, 0, 0);
@@ -14,8 +13,33 @@ pub trait PixelValues<T> {
     /// this will make so that each pixel's transparency is adjusted to the value the Windows API needs to display it with the wanted transparency.
     /// The Windows API use premultiplied alpha, which means that the Red, Green and Blue channel values must be premultiplied with the Alpha channel value.
     /// For example, if the alpha channel value is x, the Red, Green and Blue channels must be multiplied by x and divided by 0xff (255) prior to the call.
+    /// Returns a premultiplied copy of the provided values, see premultiply
     fn create_adjusted_vec(vec: &[T]) -> Vec<T>;
 
+    /// Premultiplies each pixel's Blue, Green and Red by its Alpha: c * a / 255, rounded to the nearest integer as GDI does
+    fn premultiply(vec: &mut [T]) {
+        for bgra in Self::as_bgra_bytes_mut(vec).chunks_exact_mut(4) {
+            let alpha = bgra[3] as u32;
+            for c in &mut bgra[..3] {
+                *c = ((*c as u32 * alpha + 127) / 255) as u8;
+            }
+        }
+    }
+
+    /// Reverts premultiply: c * 255 / a, rounded to the nearest integer and capped at 255 (fully transparent pixels become 0, 0, 0, 0).
+    /// The lower the Alpha, the more precision the round trip loses (e.g.: with Alpha 1 each channel can only be 0 or 255)
+    fn unpremultiply(vec: &mut [T]) {
+        for bgra in Self::as_bgra_bytes_mut(vec).chunks_exact_mut(4) {
+            let alpha = bgra[3] as u32;
+            for c in &mut bgra[..3] {
+                *c = match alpha {
+                    0 => 0,
+                    _ => ((*c as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+                };
+            }
+        }
+    }
+
     /// Creates a Vec of the given type and sets its capacity and length needed to contain the pixels' color values of the of the given area (u8 : area_width * area_height * 4, u32 : area_width * area_height)
     fn initialize_vec(area_width: usize, area_height: usize) -> Vec<T>;
 
@@ -24,39 +48,17 @@ pub trait PixelValues<T> {
 
     /// Returns .units_per_pixel() for the type specified at &self
     fn get_units_per_pixel(&self) -> u8;
+
+    /// Returns a view of the provided values as BGRA ordered bytes, without copying them (u8 : as they are, u32 : each value's 4 bytes in memory order)
+    fn as_bgra_bytes(vec: &[T]) -> &[u8];
+
+    /// Returns a mutable view of the provided values as BGRA ordered bytes, without copying them
+    fn as_bgra_bytes_mut(vec: &mut [T]) -> &mut [u8];
 }
 impl PixelValues<u8> for u8 {
     fn create_adjusted_vec(vec: &[u8]) -> Vec<u8> {
-        let mut vec_adjusted: Vec<u8> = Vec::with_capacity(vec.len());
-
-        let mut i = 0;
-        for _ in 0..vec.len() / 4 {
-            // get Alpha Red Green Blue values
-            let blue = vec[i];
-            let green = vec[i + 1];
-            let red = vec[i + 2];
-            let alpha = vec[i + 3];
-            let diff = 255 - alpha;
-            let mut blue_adjusted = blue;
-            let mut green_adjusted = green;
-            let mut red_adjusted = red;
-            // in case the current BGRA value's Alpha is not at it's max (255 (u8))
-            // we need to adjust the values of the BGR bytes
-            if diff > 0 {
-                if blue > 255 - diff {
-                    blue_adjusted = 255 - diff;
-                }
-                if green > 255 - diff {
-                    green_adjusted = 255 - diff;
-                }
-                if red > 255 - diff {
-                    red_adjusted = 255 - diff;
-                }
-            }
-            vec_adjusted.extend_from_slice(&[blue_adjusted, green_adjusted, red_adjusted, alpha]);
-
-            i += 4;
-        }
+        let mut vec_adjusted = vec.to_vec();
+        <u8>::premultiply(&mut vec_adjusted);
         vec_adjusted
     }
 
@@ -70,54 +72,19 @@ impl PixelValues<u8> for u8 {
     fn get_units_per_pixel(&self) -> u8 {
         4
     }
+
+    fn as_bgra_bytes(vec: &[u8]) -> &[u8] {
+        vec
+    }
+
+    fn as_bgra_bytes_mut(vec: &mut [u8]) -> &mut [u8] {
+        vec
+    }
 }
 impl PixelValues<u32> for u32 {
     fn create_adjusted_vec(vec: &[u32]) -> Vec<u32> {
-        let mut vec_adjusted: Vec<u32> = Vec::with_capacity(vec.len());
-
-        let (ordered_indexes, fullvalues) = u32_bytes_oredered_indexes_and_fullvalues();
-        let v1_index = ordered_indexes[0];
-        let v2_index = ordered_indexes[1];
-        let v3_index = ordered_indexes[2];
-        let v4_index = ordered_indexes[3];
-        let v1_full_val = fullvalues[0];
-        let v2_full_val = fullvalues[1];
-        let v3_full_val = fullvalues[2];
-        let v4_full_val = fullvalues[3];
-
-        for p in vec {
-            // get Blue Green Red Alpha values by excluding the others
-            // for each make null the bytes that are not those representing the value we need
-            // e.g., Alpha value : is the 4th in BGRA (in a CPU with little endianness is represented by the 2 most left bytes), so with "0xFF00_0000" we keep them,
-            // shift them enough position to have them at the most right so that they will be represented in a 0-255 range of values,
-            // and assign to the variable "alpha"
-            let blue: u32 = (p & v1_full_val) >> v1_index;
-            let green: u32 = (p & v2_full_val) >> v2_index;
-            let red: u32 = (p & v3_full_val) >> v3_index;
-            let alpha: u32 = (p & v4_full_val) >> v4_index;
-            let diff: u32 = 255 - alpha;
-            let mut red_adjusted: u32 = red;
-            let mut green_adjusted: u32 = green;
-            let mut blue_adjusted: u32 = blue;
-            // in case the current BGRA value's Alpha is not at it's max (255 (u8), FF00 0000 (HEX), 4.278.190.080 (u32))
-            // we need to adjust the values of the BGR bytes
-            if diff > 0 {
-                if red > 255 - diff {
-                    red_adjusted = 255 - diff;
-                }
-                if green > 255 - diff {
-                    green_adjusted = 255 - diff;
-                }
-                if blue > 255 - diff {
-                    blue_adjusted = 255 - diff;
-                }
-            }
-            // set the BGRA values back to their u32 original positions (in a CPU with little endianness BGRA bytes will go from right to left : 0xAARRGGBB)
-            vec_adjusted.extend_from_slice(&[(alpha << v4_index)
-                + (red_adjusted << v3_index)
-                + (green_adjusted << v2_index)
-                + (blue_adjusted << v1_index)]);
-        }
+        let mut vec_adjusted = vec.to_vec();
+        <u32>::premultiply(&mut vec_adjusted);
         vec_adjusted
     }
 
@@ -131,6 +98,17 @@ impl PixelValues<u32> for u32 {
     fn get_units_per_pixel(&self) -> u8 {
         1
     }
+
+    fn as_bgra_bytes(vec: &[u32]) -> &[u8] {
+        // a u32 is made of 4 u8 with no padding, its bytes in memory are the BGRA values it was built from (see u8_u32_casting)
+        unsafe { std::slice::from_raw_parts(vec.as_ptr() as *const u8, std::mem::size_of_val(vec)) }
+    }
+
+    fn as_bgra_bytes_mut(vec: &mut [u32]) -> &mut [u8] {
+        unsafe {
+            std::slice::from_raw_parts_mut(vec.as_mut_ptr() as *mut u8, std::mem::size_of_val(vec))
+        }
+    }
 }
 
 /// Contains pixels' color bytes data, in BGRA format, and info
@@ -154,16 +132,13 @@ impl<T: PixelValues<T>> PixelsCollection<T> {
         width: usize,
         height: usize,
         bytes: Vec<T>,
-    ) -> Result<PixelsCollection<T>, String> {
-        // if bytes.len()%4 != 0
-        if bytes.len() % <T>::units_per_pixel() as usize != 0 {
-            return Err("provided Vec<u8>'s length must be divisible by 4, as it takes 4 values (BGRA, in Vec<u8>) to get the resulting color for each pixel".to_string());
-        }
+    ) -> Result<PixelsCollection<T>, PixelCasterError> {
+        // it takes 4 values (BGRA, in Vec<u8>) to get the resulting color for each pixel, so the length must match width * height * 4 (for Vec<u8>)
         if bytes.len() != width * height * <T>::units_per_pixel() as usize {
-            return Err(
-                "provided Vec's length does not match width * height of the resulting values area"
-                    .to_string(),
-            );
+            return Err(PixelCasterError::DimensionMismatch {
+                expected: width * height * <T>::units_per_pixel() as usize,
+                actual: bytes.len(),
+            });
         }
         Ok(PixelsCollection {
             width,
@@ -175,6 +150,41 @@ impl<T: PixelValues<T>> PixelsCollection<T> {
     }
 }
 
+/// Pixels whose Blue, Green and Red are premultiplied by their Alpha, as the blending of GDI's AlphaBlend (PixelsSendMode::AlphaEnabled) wants them.
+/// They can be sent as they are (e.g.: with Screen::update_area_premultiplied_custom), without being converted at every send
+#[derive(Clone, PartialEq, Eq)]
+pub struct PremultipliedPixels<T: PixelValues<T>> {
+    pixels: PixelsCollection<T>,
+}
+impl<T: PixelValues<T>> PremultipliedPixels<T> {
+    /// Premultiplies the provided (straight Alpha) pixels
+    pub fn from_straight(mut pixels: PixelsCollection<T>) -> PremultipliedPixels<T> {
+        <T>::premultiply(&mut pixels.bytes);
+        PremultipliedPixels { pixels }
+    }
+
+    /// Wraps pixels that are already premultiplied, without converting them
+    pub fn from_premultiplied(pixels: PixelsCollection<T>) -> PremultipliedPixels<T> {
+        PremultipliedPixels { pixels }
+    }
+
+    /// Unpremultiplies the pixels back to straight Alpha
+    pub fn into_straight(mut self) -> PixelsCollection<T> {
+        <T>::unpremultiply(&mut self.pixels.bytes);
+        self.pixels
+    }
+
+    /// Returns the premultiplied pixels, without converting them
+    pub fn into_premultiplied(self) -> PixelsCollection<T> {
+        self.pixels
+    }
+
+    /// Returns the premultiplied pixels
+    pub fn pixels(&self) -> &PixelsCollection<T> {
+        &self.pixels
+    }
+}
+
 impl PixelsCollection<u8> {
     pub fn switch_bytes(&mut self, i1: usize, i2: usize) {
         <u8>::switch_bytes(&mut self.bytes, i1, i2);
@@ -382,40 +392,6 @@ impl PixelsCollection<u8> {
         }
         vec_adjusted
     }
-
-    /// Returns a new PixelsCollection from the provided one, scaling based on the provided ResizeSize (either width or hight)
-    pub fn create_new_resized(&self, resize_size: ResizeSize) -> PixelsCollection<u8> {
-        let new_height;
-        let new_width;
-
-        match resize_size {
-            ResizeSize::Width(w) => {
-                new_width = w;
-                new_height = (new_width * self.height) / self.width;
-            }
-            ResizeSize::Height(h) => {
-                new_height = h;
-                new_width = (new_height * self.width) / self.height;
-            }
-        }
-
-        let x_ratio: f32 = self.width as f32 / new_width as f32;
-        let y_ratio: f32 = self.height as f32 / new_height as f32;
-
-        let mut bytes_resized = Vec::<u8>::with_capacity(new_width * new_height * 4);
-        for fy in 0..new_height {
-            for fx in 0..new_width {
-                let pixel_needed = self.row_length * (fy as f32 * y_ratio) as usize
-                    + 4 * (fx as f32 * x_ratio) as usize;
-                bytes_resized.push(self.bytes[pixel_needed]);
-                bytes_resized.push(self.bytes[pixel_needed + 1]);
-                bytes_resized.push(self.bytes[pixel_needed + 2]);
-                bytes_resized.push(self.bytes[pixel_needed + 3]);
-            }
-        }
-
-        PixelsCollection::create(new_width, new_height, bytes_resized).unwrap()
-    }
 }
 
 
@@ -431,10 +407,11 @@ impl<T: PixelValues<T>> PixelsCollection<T> {
     }
 }
 
-/// Measure of either Width or Height
+/// Measure of either Width or Height (the other one keeps the aspect ratio), or both
 pub enum ResizeSize {
     Width(usize),
     Height(usize),
+    WidthAndHeight(usize, usize),
 }
 
 impl PixelsCollection<u32> {
@@ -445,17 +422,14 @@ impl PixelsCollection<u32> {
 
 /// Note: this function assumes that the provided `image_data` is already in BGR(A) format,
 /// if it's not switching the Red and Blue bytes with `<u8>::switch_bytes(&mut pixels_collection.bytes, 0, 2)`.
-pub fn dynamic_image_data_to_pixels_collection(image_data: Vec<u8>, has_alpha_channel: bool) -> Result<PixelsCollection::<u8>, String> {
+pub fn dynamic_image_data_to_pixels_collection(image_data: Vec<u8>, has_alpha_channel: bool) -> Result<PixelsCollection::<u8>, PixelCasterError> {
     // Load the image from the png data
-    let image = match image::load_from_memory(&image_data) {
-        Ok(img) => img,
-        Err(e) => return Err(format!("Error loading image: {}", e)),
-    };
+    let image = image::load_from_memory(&image_data).map_err(PixelCasterError::decode)?;
     dynamic_image_to_pixels_collection(image, has_alpha_channel)
 }
 /// Note: this function assumes that the provided `image` is already in BGR(A) format,
 /// if it's not switching the Red and Blue bytes with `<u8>::switch_bytes(&mut pixels_collection.bytes, 0, 2)`.
-pub fn dynamic_image_to_pixels_collection(image: DynamicImage, has_alpha_channel: bool) -> Result<PixelsCollection::<u8>, String> {
+pub fn dynamic_image_to_pixels_collection(image: DynamicImage, has_alpha_channel: bool) -> Result<PixelsCollection::<u8>, PixelCasterError> {
     // Convert the image to BGR(A)
     let (bgra_bytes, width, height) = if has_alpha_channel {
         let bgr_image = image.to_rgba8();
@@ -473,9 +447,9 @@ pub fn dynamic_image_to_pixels_collection(image: DynamicImage, has_alpha_channel
         (bgra_bytes, w, h)
     };
 
-    Ok(PixelsCollection::<u8>::create(width as usize, height as usize, bgra_bytes).unwrap())
+    PixelsCollection::<u8>::create(width as usize, height as usize, bgra_bytes)
 }
-pub fn pixels_collection_to_dynamic_image_data(pixels_collection: PixelsCollection::<u8>, keep_alpha_channel: bool) -> Result<Vec::<u8>, String> {
+pub fn pixels_collection_to_dynamic_image_data(pixels_collection: PixelsCollection::<u8>, keep_alpha_channel: bool) -> Result<Vec::<u8>, PixelCasterError> {
     use std::io::Cursor;
 
     // Convert BGRA to RGBA
@@ -491,15 +465,62 @@ pub fn pixels_collection_to_dynamic_image_data(pixels_collection: PixelsCollecti
     // Create an ImageBuffer from the BGR(A) data
     if keep_alpha_channel {
         let img = ImageBuffer::<Rgba<u8>, _>::from_raw(pixels_collection.width as u32, pixels_collection.height as u32, pixels_collection.bytes)
-            .ok_or("Failed to create image buffer")?;
-        img.write_to(&mut Cursor::new(&mut png_data), image::ImageFormat::Png).map_err(|e| e.to_string())?;
+            .ok_or_else(|| PixelCasterError::encode("Failed to create image buffer"))?;
+        img.write_to(&mut Cursor::new(&mut png_data), image::ImageFormat::Png).map_err(PixelCasterError::encode)?;
     } else {
         let mut vec = vec![];
         pixels_collection.bytes.chunks_exact(4).for_each(|c| vec.extend_from_slice(&c[0..3]));
 
         let img = ImageBuffer::<Rgb<u8>, _>::from_raw(pixels_collection.width as u32, pixels_collection.height as u32, vec)
-            .ok_or("Failed to create image buffer")?;
-        img.write_to(&mut Cursor::new(&mut png_data), image::ImageFormat::Png).map_err(|e| e.to_string())?;
+            .ok_or_else(|| PixelCasterError::encode("Failed to create image buffer"))?;
+        img.write_to(&mut Cursor::new(&mut png_data), image::ImageFormat::Png).map_err(PixelCasterError::encode)?;
     };
     Ok(png_data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn premultiply_round_trip() {
+        let mut bytes: Vec<u8> = vec![255, 128, 0, 128, 200, 100, 50, 255, 90, 60, 30, 0];
+        <u8>::premultiply(&mut bytes);
+        assert_eq!(bytes, [128, 64, 0, 128, 200, 100, 50, 255, 0, 0, 0, 0]);
+        <u8>::unpremultiply(&mut bytes);
+        assert_eq!(bytes, [255, 128, 0, 128, 200, 100, 50, 255, 0, 0, 0, 0]);
+
+        // premultiplied values survive being unpremultiplied and premultiplied again
+        for alpha in [1u8, 128, 200, 255] {
+            let premultiplied: Vec<u8> = (0..=alpha).flat_map(|c| [c, c, c, alpha]).collect();
+            let mut round_trip = premultiplied.clone();
+            <u8>::unpremultiply(&mut round_trip);
+            <u8>::premultiply(&mut round_trip);
+            assert_eq!(round_trip, premultiplied);
+        }
+
+        let mut values = vec![u32::from_le_bytes([255, 128, 0, 128])];
+        <u32>::premultiply(&mut values);
+        assert_eq!(values[0].to_le_bytes(), [128, 64, 0, 128]);
+        assert_eq!(
+            <u32>::create_adjusted_vec(&[u32::from_le_bytes([255, 128, 0, 128])]),
+            values
+        );
+    }
+
+    #[test]
+    fn premultiplied_pixels() {
+        let straight =
+            PixelsCollection::<u8>::create(2, 1, vec![255, 128, 0, 128, 10, 20, 30, 255]).unwrap();
+        let premultiplied = PremultipliedPixels::from_straight(straight.clone());
+        assert_eq!(
+            premultiplied.pixels().bytes,
+            [128, 64, 0, 128, 10, 20, 30, 255]
+        );
+        assert!(premultiplied.clone().into_straight() == straight);
+        assert!(
+            PremultipliedPixels::from_premultiplied(premultiplied.clone().into_premultiplied())
+                == premultiplied
+        );
+    }
+}
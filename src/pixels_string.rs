@@ -1,11 +1,15 @@
 use image;
 use lazy_static::lazy_static;
-use serde::{Deserialize, Serialize};
-use std::{ffi::OsStr, fs, io, path::Path};
+use std::{collections::HashMap, ffi::OsStr, fs, path::Path};
 
+/// added because CardinalPoints and BGRA were moved to bgra_management (which doesn't need the pixels_string feature), for backwards compatibility, to remove at version 2.0
+pub use crate::bgra_management::{CardinalPoints, BGRA};
 /// added because PixelsCollection was moved to a new module, "pub" in order to make it callable from this module pixels_string::PixelsCollection for backwards compatibility, to remove at version 2.0
 pub use crate::PixelsCollection;
-use crate::{add_limited, bgra_management::*, PixelValues, BGRA_INVISIBLE_PIXEL};
+use crate::{
+    bgra_management::*, compositing::blend_pixel, pixels::ResizeSize, BlendMode, PixelCasterError,
+    PixelValues, PixelsView, ResizeFilter, BGRA_INVISIBLE_PIXEL,
+};
 
 pub struct CharsCollectionCreator<'a> {
     pixels_collection: &'a PixelsCollection<u8>,
@@ -23,7 +27,7 @@ impl<'a> CharsCollectionCreator<'a> {
     /// Tries to get the same amount of characters provided in chars_string from the whole `PixelsCollection`.
     /// Scans an area of the given range from the provided coordinates and gets the pixels that pass the bgra_matcher,
     /// with those pixels creates the most little rectange that still comprehends them.
-    pub fn create(&self) -> Result<CharsCollection<u8>, String> {
+    pub fn create(&self) -> Result<CharsCollection<u8>, PixelCasterError> {
         let Self {
             pixels_collection,
             mut start_x,
@@ -37,13 +41,8 @@ impl<'a> CharsCollectionCreator<'a> {
         } = *self;
 
         // edges, cardinal points of the range of pixels that pass the bgra_matcher (e.g. : bgra_matchers::visible = which werent transparent, where A > 0)
-        let img_visible_range = get_cardinal_points_until_nonestreak_x(
-            &pixels_collection.bytes,
-            pixels_collection.height,
-            start_x,
-            start_y,
-            range_x,
-            range_y,
+        let img_visible_range = get_cardinal_points_until_nonestreak_x_in_view(
+            &pixels_collection.sub_view(start_x, start_y, range_x, range_y)?,
             range_x,
             bgra_matcher,
         );
@@ -54,43 +53,42 @@ impl<'a> CharsCollectionCreator<'a> {
             chars: Vec::new(),
             path: "".to_string(),
             bgra: BGRA(0, 0, 0, 255),
+            kerning: HashMap::new(),
         };
 
-        for char in chars_string.chars() {
+        for (i, char) in chars_string.chars().enumerate() {
             // this char's cardinal points
-            let values = get_cardinal_points_until_nonestreak_x(
-                &pixels_collection.bytes,
-                pixels_collection.height,
-                start_x,
-                start_y,
-                range_x,
-                range_y,
+            let values = get_cardinal_points_until_nonestreak_x_in_view(
+                &pixels_collection.sub_view(start_x, start_y, range_x, range_y)?,
                 min_px_space_between_chars,
                 bgra_matcher,
             );
+            // no pixel of this char passed the matcher
+            let char_width = values
+                .width()
+                .map_err(|_| PixelCasterError::GlyphNotFound(char))?;
+            let char_height = values.bottom_y - img_visible_range.top_y + 1;
 
             // with this char's cardinal points creates the most little range that still comprehends them and using that
             // creates a Vec<u8> that will be populated only with this character's pixels. those not passing the matcher will have their color set to BGRA_INVISIBLE_PIXEL (B=G=R=A=0)
-            let (pixels_captured, _) = pixel_grabber(
-                &pixels_collection.bytes,
-                pixels_collection.height,
-                values.left_x,
-                img_visible_range.top_y,
-                values.right_x - values.left_x + 1,
-                values.bottom_y - img_visible_range.top_y + 1,
+            let (pixels_captured, _) = pixel_grabber_in_view(
+                &pixels_collection.sub_view(
+                    values.left_x,
+                    img_visible_range.top_y,
+                    char_width,
+                    char_height,
+                )?,
                 bgra_matcher,
             );
 
             // add this character to the collection
             char_u8_vec.chars.push(PixelsChar {
                 char,
-                char_name: CHARS.get_char_name_by_char(char).unwrap(),
-                pixels: PixelsCollection::<u8>::create(
-                    values.right_x - values.left_x + 1,
-                    values.bottom_y - img_visible_range.top_y + 1,
-                    pixels_captured,
-                )
-                .unwrap(),
+                char_name: CHARS
+                    .get_char_name_by_char(char)
+                    .ok_or(PixelCasterError::GlyphNotFound(char))?,
+                pixels: PixelsCollection::<u8>::create(char_width, char_height, pixels_captured)?,
+                metrics: GlyphMetrics::from_size(char_width, char_height),
             });
 
             // println!("printing: char:{}, {}", char, char_u8_vec.chars.last().unwrap().char);
@@ -103,21 +101,31 @@ impl<'a> CharsCollectionCreator<'a> {
             // adjust the range of the image in which search for the next character
             start_x = values.right_x + min_px_space_between_chars;
             if start_x > original_range_x {
-                return Err("Could not retrieve all the characters".to_owned());
+                // there's no room left for the next char
+                return Err(PixelCasterError::GlyphNotFound(
+                    chars_string.chars().nth(i + 1).unwrap_or(char),
+                ));
             }
             range_x = original_range_x - start_x;
         }
 
         char_u8_vec.chars.push(PixelsChar {
             char: ' ',
-            char_name: CHARS.get_char_name_by_char(' ').unwrap(),
+            char_name: CHARS
+                .get_char_name_by_char(' ')
+                .ok_or(PixelCasterError::GlyphNotFound(' '))?,
             pixels: PixelsCollection::<u8>::create(
                 space_char_width as usize,
                 char_u8_vec.chars[0].pixels.height,
                 vec![0; space_char_width as usize * char_u8_vec.chars[0].pixels.height * 4],
-            )
-            .unwrap(),
+            )?,
+            metrics: GlyphMetrics::from_size(
+                space_char_width as usize,
+                char_u8_vec.chars[0].pixels.height,
+            ),
         });
+        // the chars' pixels all start from the sample's top visible row
+        char_u8_vec.infer_baselines();
 
         Ok(char_u8_vec)
     }
@@ -142,6 +150,12 @@ mod tests {
         let buffer = PixelsCollection::white_background_to_transparency_gradient(
             &image_transparent_bkgrnd.bytes,
         );
+        let buffer_collection = PixelsCollection::<u8>::create(
+            image_transparent_bkgrnd.width,
+            image_transparent_bkgrnd.height,
+            buffer.clone(),
+        )
+        .unwrap();
         let min_px_space_btwn_chars = 10;
         let chars_string = r#"abcdefghijklmnopqrstuvwxyz,.?!0123456789-+/*\_@#()[]{};:"£$%&='^"#;
         // a b c d e f g h i j k l m n o p q r s t u v w x y z , . ? ! 0 1 2 3 4 5 6 7 8 9 0 - + / * \ _ @ # ( ) [ ] { } ; : " £ $ % & = ' ^
@@ -154,13 +168,10 @@ mod tests {
         let range_y = image_transparent_bkgrnd.height;
 
         // range the extreme pixels which werent transparent (where A > 0)
-        let img_visible_range = get_cardinal_points_until_nonestreak_x(
-            &buffer,
-            height,
-            start_x,
-            start_y,
-            range_x,
-            range_y,
+        let img_visible_range = get_cardinal_points_until_nonestreak_x_in_view(
+            &buffer_collection
+                .sub_view(start_x, start_y, range_x, range_y)
+                .unwrap(),
             range_x,
             |_: u8, _: u8, _: u8, a: u8| -> bool { a > 0 },
         );
@@ -170,14 +181,15 @@ mod tests {
         if DISPLAY_RESULTS {
             let buffer_alpha_not_zero = buffer.clone();
             //crate::bgra_management::u8_bgra_pos_not_zero_set_pos(&mut buffer_alpha_not_zero, 3, 255,0,0,255);
-            crate::Screen::update_area_custom(
+            crate::VirtualScreen::update_area_custom(
                 &buffer_alpha_not_zero,
                 0,
                 0,
                 original_range_x as u32,
                 range_y as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
             //export Vec<u8> bytes into .png with image formatting
             image::save_buffer_with_format(
                 format!("{}{}", "fonts/exports/", "testing_result_export.png"),
@@ -194,30 +206,30 @@ mod tests {
             chars: Vec::new(),
             path: "".to_string(),
             bgra: BGRA(0, 0, 0, 255),
+            kerning: HashMap::new(),
         };
 
         let mut bytes_chars_poles = buffer.clone();
 
         for char in chars_string.chars() {
-            let values = get_cardinal_points_until_nonestreak_x(
-                &buffer,
-                height,
-                start_x,
-                start_y,
-                range_x,
-                range_y,
+            let values = get_cardinal_points_until_nonestreak_x_in_view(
+                &buffer_collection
+                    .sub_view(start_x, start_y, range_x, range_y)
+                    .unwrap(),
                 min_px_space_btwn_chars,
                 |_: u8, _: u8, _: u8, a: u8| -> bool { a > 0 },
             );
 
             // +1 because start and end values are included in the area, therefore if an area's first pixel is at 0 and it's last at 9 its range is 10, range is 9-0+1. Another e.g.: x starts at 10, ends at 40 : area = 31; 40 - 10 + 1
-            let (pixels_captured, char_values) = pixel_grabber(
-                &buffer,
-                height,
-                values.left_x,
-                img_visible_range.top_y,
-                values.right_x - values.left_x + 1,
-                values.bottom_y - img_visible_range.top_y + 1,
+            let (pixels_captured, char_values) = pixel_grabber_in_view(
+                &buffer_collection
+                    .sub_view(
+                        values.left_x,
+                        img_visible_range.top_y,
+                        values.right_x - values.left_x + 1,
+                        values.bottom_y - img_visible_range.top_y + 1,
+                    )
+                    .unwrap(),
                 |_: u8, _: u8, _: u8, a: u8| -> bool { a > 0 },
             );
 
@@ -250,25 +262,27 @@ mod tests {
                     values.bottom_y_index,
                 ];
                 bytes_chars_poles.set_addresses_bgra(&vec_addresses_char, 0, 0, 255, 255);
-                crate::Screen::update_area_custom(
+                crate::VirtualScreen::update_area_custom(
                     &bytes_chars_poles,
                     0,
                     (range_y + 10) as i32,
                     original_range_x as u32,
                     range_y as u32,
                     PixelsSendMode::AlphaEnabled,
-                );
+                )
+                .unwrap();
 
                 // greys (B == G == R) too close to white (255) greater than a threshold will be set transparent (Alpha = 0), the others will be set to 0 (black), unless whites (where B || G || R == 255)
                 // u8_grey_scale_into_black(&mut pixels_captured, 149);
-                crate::Screen::update_area_custom(
+                crate::VirtualScreen::update_area_custom(
                     &pixels_captured,
                     0,
                     ((range_y + 10) * 2) as i32,
                     (values.right_x - values.left_x) as u32 + 1,
                     (values.bottom_y - img_visible_range.top_y) as u32 + 1,
                     PixelsSendMode::AlphaEnabled,
-                );
+                )
+                .unwrap();
 
                 let mut pixels_captured_clone = pixels_captured.clone();
                 let vec_pos_char_relative = vec![
@@ -301,14 +315,15 @@ mod tests {
                     255,
                     255,
                 );
-                crate::Screen::update_area_custom(
+                crate::VirtualScreen::update_area_custom(
                     &pixels_captured_clone,
                     0,
                     ((range_y + 10) * 3) as i32,
                     (values.right_x - values.left_x) as u32 + 1,
                     (values.bottom_y - img_visible_range.top_y) as u32 + 1,
                     PixelsSendMode::AlphaEnabled,
-                );
+                )
+                .unwrap();
             }
 
             char_u8_vec.chars.push(PixelsChar {
@@ -320,6 +335,10 @@ mod tests {
                     pixels_captured,
                 )
                 .unwrap(),
+                metrics: GlyphMetrics::from_size(
+                    values.right_x - values.left_x + 1,
+                    values.bottom_y - img_visible_range.top_y + 1,
+                ),
             });
 
             if char == chars_string.chars().last().unwrap() {
@@ -348,14 +367,15 @@ mod tests {
                 img_visible_range.bottom_y_index,
             ];
             bytes_chars_poles.set_addresses_bgra(&vec_addresses_string, 255, 0, 255, 255);
-            crate::Screen::update_area_custom(
+            crate::VirtualScreen::update_area_custom(
                 &bytes_chars_poles,
                 0,
                 ((range_y + 10) * 4) as i32,
                 original_range_x as u32,
                 range_y as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
         }
 
         assert_eq!(
@@ -375,20 +395,22 @@ mod tests {
                 vec![0; space_char_width * char_u8_vec.chars[0].pixels.height * 4],
             )
             .unwrap(),
+            metrics: GlyphMetrics::from_size(space_char_width, char_u8_vec.chars[0].pixels.height),
         });
 
         // crate::send_bytes(&char_u8_vec.chars[0].bgra_bytes, &(char_u8_vec.chars[0].width as i32), &(char_u8_vec.chars[0].height as i32), &10, &10, 255);
 
         let strings_from_string_png =
             Vec::from([char_u8_vec.create_pixels_string("testing generated_text!^", 3)]);
-        crate::Screen::update_area_custom(
+        crate::VirtualScreen::update_area_custom(
             &strings_from_string_png[0].pixels.bytes,
             0,
             ((range_y + 10) * 5) as i32,
             strings_from_string_png[0].pixels.width as u32,
             strings_from_string_png[0].pixels.height as u32,
             PixelsSendMode::AlphaEnabled,
-        );
+        )
+        .unwrap();
     }
 
     // TO DO: remove this fn, it was just for temp purpose, prints a gold pixel on the top left and top right side of each char at the height of the heighest char starting from each char's bottom
@@ -404,6 +426,12 @@ mod tests {
         // send_bytes(&image_white_bkgrnd.bytes, &(image_white_bkgrnd.width as i32), &(image_white_bkgrnd.height as i32), &0, &0, 255);
 
         let buffer = image_transparent_bkgrnd.bytes.clone();
+        let buffer_collection = PixelsCollection::<u8>::create(
+            image_transparent_bkgrnd.width,
+            image_transparent_bkgrnd.height,
+            buffer.clone(),
+        )
+        .unwrap();
         let min_px_space_btwn_chars = 8;
         let chars_string = r#"abcdefghijklmnopqrstuvwxyz,.?!0123456789-+/*\_@#()[]{};:"£$%&='^"#;
         // a b c d e f g h i j k l m n o p q r s t u v w x y z , . ? ! 0 1 2 3 4 5 6 7 8 9 0 - + / * \ _ @ # ( ) [ ] { } ; : " £ $ % & = ' ^
@@ -415,13 +443,10 @@ mod tests {
         let range_y = image_transparent_bkgrnd.height;
 
         // range the extreme pixels which werent transparent (where A > 0)
-        let img_visible_range = get_cardinal_points_until_nonestreak_x(
-            &buffer,
-            height,
-            start_x,
-            start_y,
-            range_x,
-            range_y,
+        let img_visible_range = get_cardinal_points_until_nonestreak_x_in_view(
+            &buffer_collection
+                .sub_view(start_x, start_y, range_x, range_y)
+                .unwrap(),
             range_x,
             |_: u8, _: u8, _: u8, a: u8| -> bool { a > 0 },
         );
@@ -433,25 +458,24 @@ mod tests {
         let mut highest_height = 0;
 
         for char in chars_string.chars() {
-            let values = get_cardinal_points_until_nonestreak_x(
-                &buffer,
-                height,
-                start_x,
-                start_y,
-                range_x,
-                range_y,
+            let values = get_cardinal_points_until_nonestreak_x_in_view(
+                &buffer_collection
+                    .sub_view(start_x, start_y, range_x, range_y)
+                    .unwrap(),
                 min_px_space_btwn_chars,
                 |_: u8, _: u8, _: u8, a: u8| -> bool { a > 0 },
             );
 
             // +1 because start and end values are included in the area, therefore if an area's first pixel is at 0 and it's last at 9 its range is 10, range is 9-0+1. Another e.g.: x starts at 10, ends at 40 : area = 31; 40 - 10 + 1
-            let (_, char_values) = pixel_grabber(
-                &buffer,
-                height,
-                values.left_x,
-                img_visible_range.top_y,
-                values.right_x - values.left_x + 1,
-                values.bottom_y - img_visible_range.top_y + 1,
+            let (_, char_values) = pixel_grabber_in_view(
+                &buffer_collection
+                    .sub_view(
+                        values.left_x,
+                        img_visible_range.top_y,
+                        values.right_x - values.left_x + 1,
+                        values.bottom_y - img_visible_range.top_y + 1,
+                    )
+                    .unwrap(),
                 |_: u8, _: u8, _: u8, a: u8| -> bool { a > 0 },
             );
 
@@ -504,13 +528,10 @@ mod tests {
         let mut range_x = image_transparent_bkgrnd.width;
 
         for char in chars_string.chars() {
-            let values = get_cardinal_points_until_nonestreak_x(
-                &buffer,
-                height,
-                start_x,
-                start_y,
-                range_x,
-                range_y,
+            let values = get_cardinal_points_until_nonestreak_x_in_view(
+                &buffer_collection
+                    .sub_view(start_x, start_y, range_x, range_y)
+                    .unwrap(),
                 min_px_space_btwn_chars,
                 |_: u8, _: u8, _: u8, a: u8| -> bool { a > 0 },
             );
@@ -529,14 +550,15 @@ mod tests {
                     255,
                 );
 
-                crate::Screen::update_area_custom(
+                crate::VirtualScreen::update_area_custom(
                     &bytes_chars_poles,
                     0,
                     (range_y + 10) as i32,
                     original_range_x as u32,
                     range_y as u32,
                     PixelsSendMode::AlphaEnabled,
-                );
+                )
+                .unwrap();
             }
             if char == chars_string.chars().last().unwrap() {
                 break;
@@ -560,6 +582,121 @@ mod tests {
         .unwrap();
     }
 
+    fn visible_columns(pixels: &PixelsCollection<u8>, row: usize) -> Vec<usize> {
+        (0..pixels.width)
+            .filter(|x| pixels.bytes[(row * pixels.width + x) * 4 + 3] > 0)
+            .collect()
+    }
+
+    #[test]
+    fn chars_on_inferred_baseline() {
+        // 'H' and 'x' sit on the same row, 'g' goes 2 rows below it
+        let mut sample = PixelsCollection::<u8>::create(11, 7, vec![0; 11 * 7 * 4]).unwrap();
+        for (left, rows) in [(0, 0..5), (4, 2..5), (8, 2..7)] {
+            for y in rows {
+                for x in left..left + 2 {
+                    let i = (y * 11 + x) * 4;
+                    sample.bytes[i..i + 4].copy_from_slice(&[0, 0, 0, 255]);
+                }
+            }
+        }
+        let chars = sample
+            .try_create_char_collection(2, "Hxg", 3, bytes_matchers::visible)
+            .unwrap();
+        assert!(chars.chars.iter().all(|c| c.metrics.baseline == 5));
+
+        let string = chars.create_pixels_string("Hg", 1).pixels;
+        assert_eq!((string.width, string.height), (6, 7));
+        assert_eq!(visible_columns(&string, 0), [0, 1]);
+        assert_eq!(visible_columns(&string, 4), [0, 1, 3, 4]);
+        assert_eq!(visible_columns(&string, 6), [3, 4]);
+
+        // negative spacing removes the chars' right columns
+        let string = chars.create_pixels_string("Hg", -1).pixels;
+        assert_eq!(string.width, 2);
+        assert_eq!(visible_columns(&string, 4), [0, 1]);
+    }
+
+    #[test]
+    fn chars_with_bearings() {
+        let mut a = PixelsChar::create('A', "A", 2, 2, [0, 0, 255, 255].repeat(4)).unwrap();
+        a.metrics = GlyphMetrics::new(2, 4, 1, 2);
+        let mut b = PixelsChar::create('B', "B", 2, 3, [255, 0, 0, 255].repeat(6)).unwrap();
+        b.metrics = GlyphMetrics::new(2, 2, -1, 2);
+        assert_eq!((a.metrics.right_bearing, b.metrics.right_bearing), (1, 1));
+        let chars = CharsCollection {
+            chars: vec![a, b],
+            path: String::new(),
+            bgra: BGRA(0, 255, 0, 255),
+            kerning: HashMap::new(),
+        };
+
+        // 'B' overhangs the line's start by 1 column, 'A' starts 1 column after its origin
+        let string = chars.create_pixels_string("BA", 0).pixels;
+        assert_eq!((string.width, string.height), (7, 3));
+        assert_eq!(visible_columns(&string, 0), [0, 1, 4, 5]);
+        assert_eq!(visible_columns(&string, 2), [0, 1]);
+
+        // missing chars are as wide as the widest one and as tall as the line
+        let string = chars.create_pixels_string("?", 0).pixels;
+        assert_eq!((string.width, string.height), (2, 3));
+        assert_eq!(string.bytes, [0, 255, 0, 255].repeat(6));
+    }
+
+    #[test]
+    fn measure_like_create_pixels_string() {
+        let mut a = PixelsChar::create('a', "a", 3, 2, [0, 0, 0, 255].repeat(6)).unwrap();
+        a.metrics = GlyphMetrics::new(2, 3, 0, 3);
+        let mut g = PixelsChar::create('g', "g", 2, 4, [0, 0, 0, 255].repeat(8)).unwrap();
+        g.metrics = GlyphMetrics::new(2, 3, -1, 2);
+        let chars = CharsCollection {
+            chars: vec![a, g],
+            path: String::new(),
+            bgra: BGRA(0, 0, 0, 255),
+            kerning: HashMap::from([(('a', 'g'), -1)]),
+        };
+        for spacing in [-5, -2, -1, 0, 1, 4] {
+            for string in ["", "a", "ag", "ga?a", "?g", "aag"] {
+                let measured = chars.measure(string, spacing);
+                let pixels = chars.create_pixels_string(string, spacing).pixels;
+                assert_eq!(
+                    (measured.width, measured.height),
+                    (pixels.width, pixels.height)
+                );
+                assert_eq!(measured.x_offsets.len(), string.chars().count());
+            }
+        }
+        // 'g' overhangs the start by 1 column and goes 2 rows below the baseline, the kerning moves it 1 column closer to the 'a'
+        let measured = chars.measure("gag", 1);
+        assert_eq!(measured.x_offsets, [1, 5, 8]);
+        assert_eq!((measured.height, measured.baseline), (4, 2));
+        // negative spacing is clamped to the chars' advance
+        assert_eq!(chars.measure("aa", -5).width, 0);
+    }
+
+    #[test]
+    fn measure_negative_kerning() {
+        let char = |c: char| {
+            let mut char = PixelsChar::create(c, &c.to_string(), 1, 1, vec![0, 0, 0, 255]).unwrap();
+            char.metrics = GlyphMetrics::new(1, 3, 2, 1);
+            char
+        };
+        let chars = CharsCollection {
+            chars: vec![char('A'), char('V')],
+            path: String::new(),
+            bgra: BGRA(0, 0, 0, 255),
+            kerning: HashMap::from([(('A', 'V'), -8)]),
+        };
+        // the kerning moves the 'V' pen 5 columns before the 'A' one, the line starts from it
+        let measured = chars.measure("AV", 0);
+        assert_eq!(measured.x_offsets, [5, 0]);
+        let pixels = chars.create_pixels_string("AV", 0).pixels;
+        assert_eq!((measured.width, pixels.width), (8, 8));
+        // 'V' drawn at 2, 'A' at 7
+        let alphas: Vec<u8> = pixels.bytes.chunks_exact(4).map(|bgra| bgra[3]).collect();
+        assert_eq!(alphas, [0, 0, 255, 0, 0, 0, 0, 255]);
+    }
+
     #[test]
     fn hashmap_test() {
         assert_eq!(
@@ -576,129 +713,81 @@ mod tests {
         assert_eq!(CHARS.get_char_by_char_name_with_default("DIGIT ZERO"), '0');
         assert_eq!(CHARS.get_char_by_char_name_with_default("banana"), '█');
     }
-}
-
-#[derive(Clone, Copy, Deserialize, Serialize)]
-pub struct CardinalPoints {
-    pub top_y: usize,
-    pub top_y_index: usize,
-    pub right_x: usize,
-    pub right_x_index: usize,
-    pub left_x: usize,
-    pub left_x_index: usize,
-    pub bottom_y: usize,
-    pub bottom_y_index: usize,
-}
-
-impl CardinalPoints {
-    /// If the provided point is lesser than the leftest cardinal point, it's value and index will be set to the leftest point's coordinates.
-    /// If the provided point is greater than the rightest cardinal point, it's value and index will be set to the rightest point's coordinates.
-    pub fn update_if_expands_horizontal(&mut self, point: usize, index: usize) {
-        if point < self.left_x {
-            self.left_x = point;
-            self.left_x_index = index;
-        }
-        if point > self.right_x {
-            self.right_x = point;
-            self.right_x_index = index;
-        }
-    }
 
-    /// If the provided point is lesser than the top cardinal point, it's value and index will be set to the top point's coordinates.
-    /// If the provided point is greater than the bottom cardinal point, it's value and index will be set to the bottom point's coordinates.
-    pub fn update_if_expands_vertical(&mut self, point: usize, index: usize) {
-        if point < self.top_y {
-            self.top_y = point;
-            self.top_y_index = index;
-        }
-        if point > self.bottom_y {
-            self.bottom_y = point;
-            self.bottom_y_index = index;
-        }
-    }
-
-    /// If the provided CardinalPoints' have some outermost values than the self's, these will be set as the new values.
-    pub fn update_if_expands_from_cardinal_points(&mut self, c_p: &CardinalPoints) {
-        self.update_if_expands_horizontal(c_p.left_x, c_p.left_x_index);
-        self.update_if_expands_horizontal(c_p.right_x, c_p.right_x_index);
-        self.update_if_expands_vertical(c_p.top_y, c_p.top_y_index);
-        self.update_if_expands_vertical(c_p.bottom_y, c_p.bottom_y_index);
-    }
-
-    pub fn width(&self) -> Result<usize, String> {
-        if self.right_x < self.left_x {
-            return Err("No fully opaque pixels found".to_string());
-        }
-        Ok(self.right_x - self.left_x + 1)
-    }
+    #[test]
+    #[allow(deprecated)]
+    fn tuple_based_area_functions() {
+        // 3x2, only the pixel at 2, 1 is visible
+        let mut buffer = vec![0u8; 3 * 2 * 4];
+        buffer[20..24].copy_from_slice(&[1, 2, 3, 255]);
+        let visible = |_: u8, _: u8, _: u8, a: u8| a > 0;
+
+        let values =
+            get_cardinal_points_until_nonestreak_x(&buffer, 2, 1, 0, 2, 2, 1, visible).unwrap();
+        assert_eq!(
+            (values.left_x, values.top_y, values.left_x_index),
+            (2, 1, 20)
+        );
 
-    pub fn height(&self) -> Result<usize, String> {
-        if self.bottom_y < self.top_y {
-            return Err("No fully opaque pixels found".to_string());
-        }
-        Ok(self.bottom_y - self.top_y + 1)
-    }
-}
+        let (captured, values) = pixel_grabber(&buffer, 2, 1, 1, 2, 1, visible).unwrap();
+        assert_eq!(
+            captured,
+            [
+                BGRA_INVISIBLE_PIXEL.0,
+                BGRA_INVISIBLE_PIXEL.1,
+                BGRA_INVISIBLE_PIXEL.2,
+                BGRA_INVISIBLE_PIXEL.3,
+                1,
+                2,
+                3,
+                255
+            ]
+        );
+        assert_eq!((values.left_x, values.left_x_index), (1, 4));
 
-impl Default for CardinalPoints {
-    fn default() -> Self {
-        Self {
-            top_y: usize::MAX,
-            top_y_index: 0,
-            right_x: 0,
-            right_x_index: 0,
-            left_x: usize::MAX,
-            left_x_index: 0,
-            bottom_y: 0,
-            bottom_y_index: 0,
-        }
-    }
-}
+        assert_eq!(
+            pixels_from_area(&buffer, 2, 2, 0, 1, 2).unwrap(),
+            [0, 0, 0, 0, 1, 2, 3, 255]
+        );
 
-impl std::fmt::Debug for CardinalPoints {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("CardinalPoints")
-            .field("top_y", &self.top_y)
-            .field("top_y_index", &self.top_y_index)
-            .field("right_x", &self.right_x)
-            .field("right_x_index", &self.right_x_index)
-            .field("left_x", &self.left_x)
-            .field("left_x_index", &self.left_x_index)
-            .field("bottom_y", &self.bottom_y)
-            .field("bottom_y_index", &self.bottom_y_index)
-            .finish()
+        assert!(matches!(
+            pixels_from_area(&buffer, 2, 2, 0, 2, 2),
+            Err(PixelCasterError::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            pixel_grabber(&buffer[1..], 2, 0, 0, 1, 1, visible),
+            Err(PixelCasterError::DimensionMismatch { .. })
+        ));
     }
 }
 
-/// returns the cardinal points of given range of which pixels match a condition
-pub fn get_cardinal_points_until_nonestreak_x(
-    buffer: &[u8],
-    height: usize,
-    start_x: usize,
-    start_y: usize,
-    range_x: usize,
-    range_y: usize,
+/// Returns the cardinal points of the area's pixels that match a condition, scanning it column by column until
+/// `none_streak_x` columns in a row (after the rightest matching one) have no matching pixels.
+/// Their coordinates and indexes are those of the PixelsCollection the area is a view of
+pub fn get_cardinal_points_until_nonestreak_x_in_view(
+    area: &PixelsView<u8>,
     none_streak_x: usize,
     bgra_matcher: fn(u8, u8, u8, u8) -> bool,
 ) -> CardinalPoints {
-    // how many buffer units there are in a horizontal, 1 pixel high, line across the screen
-    let stride = buffer.len() / height;
+    let (start_x, start_y) = (area.x(), area.y());
 
     let mut values = CardinalPoints {
-        top_y: start_y + range_y,
+        top_y: start_y + area.height(),
         top_y_index: 0,
         right_x: 0,
         right_x_index: 0,
-        left_x: start_x + range_x,
+        left_x: start_x + area.width(),
         left_x_index: 0,
         bottom_y: 0,
         bottom_y_index: 0,
     };
 
-    for x in start_x..start_x + range_x {
-        for y in start_y..start_y + range_y {
-            let i = stride * y + 4 * x;
-            if bgra_matcher(buffer[i], buffer[i + 1], buffer[i + 2], buffer[i + 3]) {
+    let rows: Vec<&[u8]> = area.rows().collect();
+    for x in start_x..start_x + area.width() {
+        let row_i = 4 * (x - start_x);
+        for (y, row) in (start_y..).zip(&rows) {
+            let i = area.stride() * y + 4 * x;
+            if bgra_matcher(row[row_i], row[row_i + 1], row[row_i + 2], row[row_i + 3]) {
                 if y < values.top_y {
                     values.top_y = y;
                     values.top_y_index = i;
@@ -724,46 +813,30 @@ pub fn get_cardinal_points_until_nonestreak_x(
     values
 }
 
-/// From a starting pixel scans an area of the given range and populates a new Vec<u8> with the given range with pixels that pass the bgra_matcher.
-/// Returns the Vec<u8> and the cardinal points of the most outer pixels that passed the bgra_matcher
-pub fn pixel_grabber(
-    buffer: &[u8],
-    height: usize,
-    start_x: usize,
-    start_y: usize,
-    range_x: usize,
-    range_y: usize,
+/// Populates a new Vec<u8>, as big as the area, with its pixels that pass the bgra_matcher (the others are set to BGRA_INVISIBLE_PIXEL).
+/// Returns the Vec<u8> and the cardinal points of the most outer pixels that passed the bgra_matcher, relative to the area
+pub fn pixel_grabber_in_view(
+    area: &PixelsView<u8>,
     bgra_matcher: fn(u8, u8, u8, u8) -> bool,
 ) -> (Vec<u8>, CardinalPoints) {
-    // how many buffer units there are in a horizontal, 1 pixel high, line across the screen
-    let stride = buffer.len() / height;
-
     // will contain all the pixels which werent transparent (where A > 0)
-    let mut pixels_captured: Vec<u8> = Vec::with_capacity(range_x * range_y * 4);
+    let mut pixels_captured: Vec<u8> = Vec::with_capacity(area.width() * area.height() * 4);
     let mut values = CardinalPoints {
-        top_y: start_y + range_y,
+        top_y: area.height(),
         top_y_index: 0,
         right_x: 0,
         right_x_index: 0,
-        left_x: start_x + range_x,
+        left_x: area.width(),
         left_x_index: 0,
         bottom_y: 0,
         bottom_y_index: 0,
     };
-    let mut rx = 0;
-    let rstride = (range_x * range_y * 4) / range_y;
-    for (ry, y) in (start_y..start_y + range_y).enumerate() {
-        for x in start_x..start_x + range_x {
-            let i = stride * y + 4 * x;
-            let j = rstride * ry + 4 * rx;
-            if bgra_matcher(buffer[i], buffer[i + 1], buffer[i + 2], buffer[i + 3]) {
+    for (ry, row) in area.rows().enumerate() {
+        for (rx, bgra) in row.chunks_exact(4).enumerate() {
+            let j = pixels_captured.len();
+            if bgra_matcher(bgra[0], bgra[1], bgra[2], bgra[3]) {
                 // is in BGRA
-                pixels_captured.extend_from_slice(&[
-                    buffer[i],
-                    buffer[i + 1],
-                    buffer[i + 2],
-                    buffer[i + 3],
-                ]);
+                pixels_captured.extend_from_slice(bgra);
 
                 if ry < values.top_y {
                     values.top_y = ry;
@@ -789,41 +862,78 @@ pub fn pixel_grabber(
                     BGRA_INVISIBLE_PIXEL.3,
                 ]);
             }
-            rx += 1;
         }
-        rx = 0;
     }
     (pixels_captured, values)
 }
 
 
-/// Width and height restricted to the fully opaque area of the provided image.
-pub fn get_fully_opaque_area_range(image: &PixelsCollection<u8>) -> Result<(usize, usize), String> {
-    let values = get_cardinal_points_until_nonestreak_x(&image.bytes, image.height, 0, 0, image.width, image.height, image.width, |_,_,_,a| a == 255);
-    Ok((values.width()?, values.height()?))
+/// Returns the cardinal points of given range of which pixels match a condition, `buffer` holds `height` rows of pixels.
+/// Kept for backwards compatibility, to remove at version 2.0
+#[deprecated(note = "use get_cardinal_points_until_nonestreak_x_in_view with a PixelsView")]
+#[allow(clippy::too_many_arguments)]
+pub fn get_cardinal_points_until_nonestreak_x(
+    buffer: &[u8],
+    height: usize,
+    start_x: usize,
+    start_y: usize,
+    range_x: usize,
+    range_y: usize,
+    none_streak_x: usize,
+    bgra_matcher: fn(u8, u8, u8, u8) -> bool,
+) -> Result<CardinalPoints, PixelCasterError> {
+    let area =
+        PixelsView::from_rows(buffer, height)?.sub_view(start_x, start_y, range_x, range_y)?;
+    Ok(get_cardinal_points_until_nonestreak_x_in_view(
+        &area,
+        none_streak_x,
+        bgra_matcher,
+    ))
 }
 
+/// From a starting pixel scans an area of the given range and populates a new Vec<u8> with the given range with pixels that pass the bgra_matcher,
+/// `buffer` holds `height` rows of pixels. Kept for backwards compatibility, to remove at version 2.0
+#[deprecated(note = "use pixel_grabber_in_view with a PixelsView")]
+pub fn pixel_grabber(
+    buffer: &[u8],
+    height: usize,
+    start_x: usize,
+    start_y: usize,
+    range_x: usize,
+    range_y: usize,
+    bgra_matcher: fn(u8, u8, u8, u8) -> bool,
+) -> Result<(Vec<u8>, CardinalPoints), PixelCasterError> {
+    let area =
+        PixelsView::from_rows(buffer, height)?.sub_view(start_x, start_y, range_x, range_y)?;
+    Ok(pixel_grabber_in_view(&area, bgra_matcher))
+}
 
-/// From a starting pixel scans an area of the given range and populates a new Vec<u8> with the given range with pixels.
-pub fn pixels_from_area(buffer: &[u8], height: usize, start_x: usize, start_y: usize, range_x: usize, range_y: usize) -> Vec<u8> {
-    
-    // how many buffer units there are in a horizontal, 1 pixel high, line across the screen
-    let stride = buffer.len() / height;
-
-    let mut pixels_captured = Vec::with_capacity(range_x * range_y * 4);
-    for y in start_y..start_y+range_y {
-        for x in start_x..start_x+range_x {
-            let i = stride * y + 4 * x;
-            pixels_captured.extend_from_slice(&[ buffer[i], buffer[i+1], buffer[i+2], buffer[i+3] ]);
-        }
-    }
-    pixels_captured
+/// From a starting pixel scans an area of the given range and populates a new Vec<u8> with the given range with pixels,
+/// `buffer` holds `height` rows of pixels. Kept for backwards compatibility, to remove at version 2.0
+#[deprecated(note = "use PixelsCollection::sub_view and PixelsView::to_owned")]
+pub fn pixels_from_area(
+    buffer: &[u8],
+    height: usize,
+    start_x: usize,
+    start_y: usize,
+    range_x: usize,
+    range_y: usize,
+) -> Result<Vec<u8>, PixelCasterError> {
+    let area =
+        PixelsView::from_rows(buffer, height)?.sub_view(start_x, start_y, range_x, range_y)?;
+    Ok(area.to_owned().bytes)
+}
+
+/// Width and height restricted to the fully opaque area of the provided image.
+pub fn get_fully_opaque_area_range(image: &PixelsCollection<u8>) -> Result<(usize, usize), PixelCasterError> {
+    let values = get_cardinal_points_until_nonestreak_x_in_view(&image.view(), image.width, |_,_,_,a| a == 255);
+    Ok((values.width()?, values.height()?))
 }
 
 /// Additional implementations that enables .png importing and CharsCollection creation
 impl PixelsCollection<u8> {
     /// Creates a new instance from a .png (resulting color bytes will be BGRA ordered)
-    pub fn from_png(png_path: &str) -> Result<PixelsCollection<u8>, String> {
+    pub fn from_png(png_path: &str) -> Result<PixelsCollection<u8>, PixelCasterError> {
         // get Vec<u8> from .png and load it to a .png format, png works in RGBA, to make it usable it will be converted into BGRA
         match crate::pixels_string::png_into_pixels_collection(png_path) {
             Ok(mut pixel_coll) => {
@@ -840,7 +950,7 @@ impl PixelsCollection<u8> {
         chars_string: &str,
         space_char_width: u32,
         bgra_matcher: fn(u8, u8, u8, u8) -> bool,
-    ) -> Result<CharsCollection<u8>, String> {
+    ) -> Result<CharsCollection<u8>, PixelCasterError> {
         let start_x = 0;
         let start_y = 0;
         let range_x = self.width;
@@ -868,7 +978,7 @@ impl PixelsCollection<u8> {
 }
 impl PixelsCollection<u32> {
     /// Creates a new instance from a .png (resulting color bytes will be BGRA ordered)
-    pub fn from_png(png_path: &str) -> Result<PixelsCollection<u32>, String> {
+    pub fn from_png(png_path: &str) -> Result<PixelsCollection<u32>, PixelCasterError> {
         // get Vec<u8> from .png and load it to a .png format, png works in RGBA, to make it usable it will be converted into BGRA
         match crate::pixels_string::png_into_pixels_collection(png_path) {
             Ok(mut pixel_coll) => {
@@ -885,14 +995,16 @@ impl PixelsCollection<u32> {
 }
 
 /// Gets a Vec<u8> in RGBA values from a .png
-pub fn png_into_pixels_collection(png_path: &str) -> Result<PixelsCollection<u8>, String> {
+pub fn png_into_pixels_collection(
+    png_path: &str,
+) -> Result<PixelsCollection<u8>, PixelCasterError> {
     match image::open(png_path) {
         Ok(img) => Ok(PixelsCollection::<u8>::create(
             img.width() as usize,
             img.height() as usize,
             img.into_rgba8().to_vec(),
         )?),
-        Err(err) => Err(err.to_string()),
+        Err(err) => Err(PixelCasterError::decode(err)),
     }
     /*match std::fs::read(png_path) {
         Ok(bytes) => {
@@ -921,12 +1033,59 @@ pub fn create_dir_recursive(dir_full_path: &str) -> std::io::Result<()> {
     Ok(())
 }
 
-/// Character name, the char it refers to and its PixelsCollection
+/// Character name, the char it refers to, its PixelsCollection and where to place it in a line of text
 #[derive(Clone)]
 pub struct PixelsChar<T: PixelValues<T>> {
     pub char: char,
     pub char_name: String,
     pub pixels: PixelsCollection<T>,
+    pub metrics: GlyphMetrics,
+}
+
+/// Where a PixelsChar's pixels go in a line of text, in pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphMetrics {
+    /// Rows from the top of the pixels to the baseline, the line the chars sit on (descenders as those of 'g' and 'p' go below it)
+    pub baseline: usize,
+    /// Distance from this char's origin on the baseline to the next char's one
+    pub advance: i32,
+    /// Columns from the origin to the pixels' left edge (negative if they overhang the previous char)
+    pub left_bearing: i32,
+    /// Columns from the pixels' right edge to the next char's origin (negative if they overhang the next char)
+    pub right_bearing: i32,
+}
+
+impl GlyphMetrics {
+    /// Metrics of pixels sitting on their bottom row, advancing by their width, without bearings
+    pub fn from_size(width: usize, height: usize) -> GlyphMetrics {
+        GlyphMetrics {
+            baseline: height,
+            advance: width as i32,
+            left_bearing: 0,
+            right_bearing: 0,
+        }
+    }
+
+    /// Metrics of pixels that are width wide, with their right_bearing being what's left of the advance
+    pub fn new(baseline: usize, advance: i32, left_bearing: i32, width: usize) -> GlyphMetrics {
+        GlyphMetrics {
+            baseline,
+            advance,
+            left_bearing,
+            right_bearing: advance - left_bearing - width as i32,
+        }
+    }
+
+    /// The same metrics for pixels scaled by the given factor
+    pub fn scaled(&self, scale: f32) -> GlyphMetrics {
+        let scale = |v: f32| (v * scale).round();
+        GlyphMetrics {
+            baseline: scale(self.baseline as f32) as usize,
+            advance: scale(self.advance as f32) as i32,
+            left_bearing: scale(self.left_bearing as f32) as i32,
+            right_bearing: scale(self.right_bearing as f32) as i32,
+        }
+    }
 }
 impl PixelsChar<u8> {
     /// Creates a new instance that will represent the given char
@@ -936,16 +1095,21 @@ impl PixelsChar<u8> {
         width: usize,
         height: usize,
         bytes: Vec<u8>,
-    ) -> Result<PixelsChar<u8>, String> {
+    ) -> Result<PixelsChar<u8>, PixelCasterError> {
         Ok(PixelsChar {
             char,
             char_name: char_name.to_string(),
             pixels: PixelsCollection::<u8>::create(width, height, bytes)?,
+            metrics: GlyphMetrics::from_size(width, height),
         })
     }
 
     /// Creates a new instance from a .png (resulting color bytes will be BGRA ordered)
-    pub fn from_png(png_path: &str, char: char, char_name: &str) -> Result<PixelsChar<u8>, String> {
+    pub fn from_png(
+        png_path: &str,
+        char: char,
+        char_name: &str,
+    ) -> Result<PixelsChar<u8>, PixelCasterError> {
         // get Vec<u8> from .png and load it to a .png format, png works in RGBA, to make it usable it will be converted into BGRA
         match crate::pixels_string::png_into_pixels_collection(png_path) {
             Ok(mut bytes) => {
@@ -953,6 +1117,7 @@ impl PixelsChar<u8> {
                 Ok(PixelsChar {
                     char,
                     char_name: char_name.to_string(),
+                    metrics: GlyphMetrics::from_size(bytes.width, bytes.height),
                     pixels: bytes,
                 })
             }
@@ -965,20 +1130,14 @@ impl PixelsChar<u8> {
     }
 }
 
-#[derive(Clone, Copy)]
-pub struct BGRA<T: Copy + Clone>(pub T, pub T, pub T, pub T);
-impl<T: Copy + Clone> BGRA<T> {
-    pub fn to_vec(&self) -> Vec<T> {
-        vec![self.0, self.1, self.2, self.3]
-    }
-}
-
 /// Collection of PixelsChar
 #[derive(Clone)]
 pub struct CharsCollection<T: PixelValues<T> + Copy + Clone> {
     pub chars: Vec<PixelsChar<T>>,
     pub path: String,
     pub bgra: BGRA<T>,
+    /// Pixels added to (or, if negative, removed from) the distance between two chars, when the first one is followed by the second one
+    pub kerning: HashMap<(char, char), i32>,
 }
 
 impl CharsCollection<u8> {
@@ -987,41 +1146,39 @@ impl CharsCollection<u8> {
     }*/
     /// Creates a new collection from a folder containing the chars in .png file format.
     /// The filenames that do not match those inside the CHARS hashmap will still be added, but will represent the default char '█'
-    pub fn from_pngs_folder(dir: &str) -> io::Result<CharsCollection<u8>> {
+    pub fn from_pngs_folder(dir: &str) -> Result<CharsCollection<u8>, PixelCasterError> {
         let mut char_u8_vec = CharsCollection {
             chars: Vec::new(),
             path: dir.to_string(),
             bgra: BGRA(0, 0, 0, 255),
+            kerning: HashMap::new(),
         };
 
         for entry in fs::read_dir(Path::new(dir))? {
             let entry = entry?;
             let path = entry.path();
             // path is directory && get extension from filename == "png"
-            if !path.is_dir() && path.extension().and_then(OsStr::to_str).unwrap() == "png" {
+            if !path.is_dir() && path.extension().and_then(OsStr::to_str) == Some("png") {
                 let fname_without_extension =
                     String::from(path.file_stem().unwrap().to_str().unwrap());
                 let fpath = path.into_os_string().into_string().unwrap();
                 //println!("{:?}, {:?}, {:?}",fname_without_extension, fname, fpath);
 
                 // get Vec<u8> from .png and load it to a .png format, png works in RGBA, to make it usable it will be converted into BGRA
-                match PixelsChar::from_png(
+                let mut pixels_char = PixelsChar::from_png(
                     &fpath,
                     CHARS.get_char_by_char_name_with_default(&fname_without_extension),
                     &fname_without_extension,
-                ) {
-                    Ok(mut pixels_char) => {
-                        pixels_char.switch_bytes(0, 2);
-                        char_u8_vec.chars.push(pixels_char);
-                    }
-                    Err(e) => println!("{}", e),
-                }
+                )?;
+                pixels_char.switch_bytes(0, 2);
+                char_u8_vec.chars.push(pixels_char);
             }
         }
+        char_u8_vec.infer_baselines();
         Ok(char_u8_vec)
     }
     /// Exports the collection's chars' Vec<u8> color bytes into the given folder path in .png file format (BGRA will become RGBA)
-    pub fn export_as_pngs(&self, folder_path: &str) -> std::io::Result<()> {
+    pub fn export_as_pngs(&self, folder_path: &str) -> Result<(), PixelCasterError> {
         Self::export(folder_path, self)
     }
     /// Exports all the Chars into the CharsCollection's path except those whose char value is the one provided
@@ -1030,11 +1187,12 @@ impl CharsCollection<u8> {
         &mut self,
         folder_path: &str,
         c_to_exclude: char,
-    ) -> std::io::Result<()> {
+    ) -> Result<(), PixelCasterError> {
         let mut cc_except = CharsCollection {
             chars: self.chars.clone(),
             path: self.path.to_string(),
             bgra: self.bgra,
+            kerning: self.kerning.clone(),
         };
         cc_except.chars.retain(|x| x.char != c_to_exclude);
         Self::export(
@@ -1047,33 +1205,54 @@ impl CharsCollection<u8> {
         )
     }
     /// Exports the provided collection's chars' Vec<u8> color bytes into the given folder path in .png file format (BGRA will become RGBA), creates the path if the provided one does not exist
-    fn export(png_path: &str, coll: &CharsCollection<u8>) -> std::io::Result<()> {
-        match create_dir_recursive(png_path) {
-            Ok(()) => {}
-            Err(err) => {
-                return Err(err);
-            }
-        };
+    fn export(png_path: &str, coll: &CharsCollection<u8>) -> Result<(), PixelCasterError> {
+        create_dir_recursive(png_path)?;
         for c in &coll.chars {
             image::save_buffer_with_format(
-                format!(
-                    "{}{}.png",
-                    png_path.trim_end_matches('\\').to_owned() + "\\",
-                    c.char_name
-                ),
+                Path::new(png_path.trim_end_matches('\\')).join(format!("{}.png", c.char_name)),
                 &<u8>::swap_blue_with_red(&c.pixels.bytes),
                 c.pixels.width as u32,
                 c.pixels.height as u32,
                 image::ColorType::Rgba8,
                 image::ImageFormat::Png,
             )
-            .unwrap();
+            .map_err(PixelCasterError::encode)?;
         }
         Ok(())
     }
 }
 
 impl CharsCollection<u8> {
+    /// Returns a new collection with every char scaled by the same factor (e.g.: 1.5 turns 12px tall chars into 18px tall ones),
+    /// so that they keep their sizes relative to each other. Each char is at least 1 pixel wide and tall
+    pub fn create_new_scaled(&self, scale: f32, filter: ResizeFilter) -> CharsCollection<u8> {
+        CharsCollection {
+            chars: self
+                .chars
+                .iter()
+                .map(|c| PixelsChar {
+                    char: c.char,
+                    char_name: c.char_name.clone(),
+                    pixels: c.pixels.create_new_resized_with_filter(
+                        ResizeSize::WidthAndHeight(
+                            (c.pixels.width as f32 * scale).round() as usize,
+                            (c.pixels.height as f32 * scale).round() as usize,
+                        ),
+                        filter,
+                    ),
+                    metrics: c.metrics.scaled(scale),
+                })
+                .collect(),
+            path: self.path.clone(),
+            bgra: self.bgra,
+            kerning: self
+                .kerning
+                .iter()
+                .map(|(pair, adjustment)| (*pair, (*adjustment as f32 * scale).round() as i32))
+                .collect(),
+        }
+    }
+
     /// Every grey below a threshold set to black, else to invisible
     pub fn grey_scale_into_black(&mut self, grey_threshold: u8) {
         for v in &mut self.chars {
@@ -1141,87 +1320,244 @@ impl CharsCollection<u8> {
         self.bgra = BGRA(b, g, r, self.bgra.3);
     }
     
-    pub fn create_pixels_string(&self, string: &str, char_spacing: isize) -> PixelsString {
-        let mut vec: Vec<u8> = Vec::new();
-        let mut vec_width = 0;
-        // starting from a base value of 1, gets the tallest char's hight
-        let tallest_char_height = self.chars.iter().fold(1, |a, b| a.max(b.pixels.height));
+    /// Sets the baseline of every char to the most common bottom row among the visible ones,
+    /// for chars sharing their top row (as those taken from the same sample image, or exported from it)
+    pub fn infer_baselines(&mut self) {
+        let mut bottoms = std::collections::BTreeMap::new();
+        for c in &self.chars {
+            let visible_rows = c
+                .pixels
+                .bytes
+                .chunks_exact(4 * c.pixels.width.max(1))
+                .rposition(|row| row.chunks_exact(4).any(|bgra| bgra[3] > 0));
+            if let Some(last_row) = visible_rows {
+                *bottoms.entry(last_row + 1).or_insert(0) += 1;
+            }
+        }
+        // on a tie, the upper one (descenders are fewer than the chars sitting on the baseline)
+        let Some(baseline) = bottoms
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(bottom, _)| *bottom)
+        else {
+            return;
+        };
+        self.chars
+            .iter_mut()
+            .for_each(|c| c.metrics.baseline = baseline);
+    }
+
+    /// Places the chars of the string on a shared baseline, each one advancing the next one's origin by its advance + char_spacing (+ the pair's kerning)
+    /// (a negative char_spacing also removes that many columns from each char, starting from the last one on the right).
+    /// With a tab_width, each '\t' moves the next char's origin to the following multiple of it
+    pub(crate) fn layout_line(
+        &self,
+        string: &str,
+        char_spacing: isize,
+        tab_width: Option<usize>,
+    ) -> LineLayout<'_> {
+        // rows above and below the baseline, from the tallest chars
+        let ascent = self
+            .chars
+            .iter()
+            .map(|c| c.metrics.baseline)
+            .max()
+            .unwrap_or(0);
+        let descent = self
+            .chars
+            .iter()
+            .map(|c| c.pixels.height.saturating_sub(c.metrics.baseline))
+            .max()
+            .unwrap_or(0);
         // starting from a base value of 1, gets the widest char's width
         let widest_char_width = self.chars.iter().fold(1, |a, b| a.max(b.pixels.width));
-        // add pixels to the resulting pixels string vector row by row, left to righ
-        for string_y in 0..tallest_char_height {
-            // char by char, when current char's row bytes end, the next character's row will start to be added
-            for s in string.chars() {
-                let char_found = self.chars.iter().find(|r| r.char == s);
-                if let Some(char) = char_found {
-                    let char_vec = &char.pixels.bytes;
-
-                    // char width + char_spacing (char_spacing can be negative, which will remove columns starting from the last one on the right)
-                    //let char_total_width = add_limited!(char.pixels.width as i32, char_spacing, 1);
-                    let char_total_width = add_limited!(char.pixels.width as i32, char_spacing, 0);
-
-                    // in case this char is not as high as the current row we won't have any more bytes to add
-                    // so add the needed rows at the end to make it high as needed
-                    if char.pixels.height <= string_y {
-                        for _ in 0..char_total_width {
-                            vec.extend_from_slice(&[0, 0, 0, 0]);
-                        }
-                        continue;
-                    }
-                    // start adding bytes to the resulting pixels string's current row
-                    for w in 0..char_total_width {
-                        // in case the char's current row's bytes have ended, add the necessary bytes to make up the remaining width (made by char_spacing)
-                        if w >= char.pixels.width as i32 {
-                            vec.extend_from_slice(&[0, 0, 0, 0]);
-                        }
-                        // add the char's current row's bytes to the resulting pixels string
-                        else {
-                            let i =
-                                (char_vec.len() / char.pixels.height) * string_y + (w * 4) as usize;
-                            vec.extend_from_slice(&[
-                                char_vec[i],
-                                char_vec[i + 1],
-                                char_vec[i + 2],
-                                char_vec[i + 3],
-                            ])
+
+        let mut glyphs = Vec::new();
+        let (mut pen_x, mut left, mut right) = (0i64, 0i64, 0i64);
+        let mut previous = None;
+        for s in string.chars() {
+            if let (Some(tab_width), '\t') = (tab_width.filter(|w| *w > 0), s) {
+                previous = None;
+                let tab_width = tab_width as i64;
+                let advance = (pen_x.div_euclid(tab_width) + 1) * tab_width - pen_x;
+                glyphs.push(PlacedChar {
+                    text_char: s,
+                    char: None,
+                    pen_x,
+                    advance,
+                    x: pen_x,
+                    y: 0,
+                    width: 0,
+                });
+                pen_x += advance;
+                right = right.max(pen_x);
+            } else if let Some(char) = self.chars.iter().find(|r| r.char == s) {
+                if let Some(adjustment) = previous.and_then(|p| self.kerning.get(&(p, s))) {
+                    pen_x += *adjustment as i64;
+                }
+                previous = Some(s);
+                let x = pen_x + char.metrics.left_bearing as i64;
+                let width = add_limited!(char.pixels.width as i32, char_spacing.min(0), 0) as usize;
+                let advance = add_limited!(char.metrics.advance, char_spacing, 0) as i64;
+                glyphs.push(PlacedChar {
+                    text_char: s,
+                    char: Some(char),
+                    pen_x,
+                    advance,
+                    x,
+                    y: ascent - char.metrics.baseline,
+                    width,
+                });
+                // a negative kerning can also move the pen itself before the line's origin
+                left = left.min(pen_x).min(x);
+                right = right.max(x + width as i64).max(pen_x + advance);
+                pen_x += advance;
+            }
+            // in case a char was not found, put widest_char_width wide matching bgra pixels
+            else {
+                previous = None;
+                let width = add_limited!(
+                    widest_char_width as i32,
+                    char_spacing,
+                    widest_char_width as i32
+                ) as usize;
+                glyphs.push(PlacedChar {
+                    text_char: s,
+                    char: None,
+                    pen_x,
+                    advance: width as i64,
+                    x: pen_x,
+                    y: 0,
+                    width,
+                });
+                pen_x += width as i64;
+                right = right.max(pen_x);
+            }
+        }
+        // the chars overhanging the first one's origin on the left make the line start before it
+        for glyph in &mut glyphs {
+            glyph.pen_x -= left;
+            glyph.x -= left;
+        }
+        LineLayout {
+            width: (right - left) as usize,
+            height: (ascent + descent).max(1),
+            baseline: ascent,
+            glyphs,
+        }
+    }
+
+    /// Draws the line's chars into the pixels, with the line's upper left corner at left, top (the parts outside of the pixels are left out).
+    /// Overlapping chars are blended, as are the chars drawn over visible pixels
+    pub(crate) fn draw_line(
+        &self,
+        line: &LineLayout,
+        pixels: &mut PixelsCollection<u8>,
+        left: i64,
+        top: i64,
+    ) {
+        let mut draw_pixel = |x: i64, y: i64, src: &[u8]| {
+            if x < 0 || y < 0 || x >= pixels.width as i64 || y >= pixels.height as i64 {
+                return;
+            }
+            let dst = (y as usize * pixels.width + x as usize) * 4;
+            let dst = &mut pixels.bytes[dst..dst + 4];
+            if dst[3] == 0 {
+                dst.copy_from_slice(src);
+            } else if src[3] > 0 {
+                blend_pixel(BlendMode::SourceOver, src, dst, 255);
+            }
+        };
+        for glyph in &line.glyphs {
+            let x = left + glyph.x;
+            match glyph.char {
+                Some(char) => {
+                    let rows = char.pixels.height.min(line.height - glyph.y);
+                    for y in 0..rows {
+                        for w in 0..glyph.width {
+                            let src = (y * char.pixels.width + w) * 4;
+                            draw_pixel(
+                                x + w as i64,
+                                top + (glyph.y + y) as i64,
+                                &char.pixels.bytes[src..src + 4],
+                            );
                         }
                     }
-                    // increments resulting pixels string's width with the current char's total width (occurrs once per each char)
-                    //if string_y == 1 {
-                    if string_y == 0 {
-                        vec_width += char_total_width as usize;
-                    }
                 }
-                // in case a char was not found, don't continue to next char, instead put widest_char_width wide matching bgra pixels
-                else {
-                    for _ in 0..add_limited!(
-                        widest_char_width as i32,
-                        char_spacing,
-                        widest_char_width as i32
-                    ) {
-                        vec.extend_from_slice(&[self.bgra.0, self.bgra.1, self.bgra.2, 255]);
-                    }
-                    // increments resulting pixels string's width with the current char's total width (occurrs once per each char)
-                    //if string_y == 1 {
-                    if string_y == 0 {
-                        vec_width += add_limited!(
-                            widest_char_width as i32,
-                            char_spacing,
-                            widest_char_width as i32
-                        ) as usize;
+                None => {
+                    let block = [self.bgra.0, self.bgra.1, self.bgra.2, 255];
+                    for y in 0..line.height {
+                        for w in 0..glyph.width {
+                            draw_pixel(x + w as i64, top + y as i64, &block);
+                        }
                     }
-                    continue;
                 }
             }
-            // when the last character's current row bytes end, go back to the first character and start with the next row
         }
+    }
+
+    /// Returns the size create_pixels_string would give to the string's pixels (with the same char_spacing), without creating them
+    pub fn measure(&self, string: &str, char_spacing: isize) -> TextMetrics {
+        let line = self.layout_line(string, char_spacing, None);
+        TextMetrics {
+            width: line.width,
+            height: line.height,
+            baseline: line.baseline,
+            x_offsets: line.glyphs.iter().map(|g| g.pen_x as usize).collect(),
+        }
+    }
+
+    /// Creates a single line of text laying its chars out on a shared baseline (using their metrics), spaced by char_spacing pixels more than their advance
+    /// and moved closer or further apart by the kerning of each pair of chars.
+    /// A negative char_spacing removes that many columns from each char, starting from the last one on the right.
+    /// The chars missing from the collection are drawn as blocks of the collection's color. For more lines see text_layout
+    pub fn create_pixels_string(&self, string: &str, char_spacing: isize) -> PixelsString {
+        let line = self.layout_line(string, char_spacing, None);
+        let mut pixels = PixelsCollection::<u8>::create(
+            line.width,
+            line.height,
+            vec![0; line.width * line.height * 4],
+        )
+        .unwrap();
+        self.draw_line(&line, &mut pixels, 0, 0);
         PixelsString {
             bgra: self.bgra,
-            pixels: PixelsCollection::<u8>::create(vec_width, tallest_char_height, vec).unwrap(),
+            pixels,
         }
     }
 }
 
+/// Size of a line of text, as returned by CharsCollection::measure
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextMetrics {
+    pub width: usize,
+    pub height: usize,
+    /// Rows from the top to the baseline the chars sit on
+    pub baseline: usize,
+    /// Column of each char's origin, one per char of the string
+    pub x_offsets: Vec<usize>,
+}
+
+/// A line of text's size, its baseline's row and where each of its chars goes
+pub(crate) struct LineLayout<'a> {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) baseline: usize,
+    pub(crate) glyphs: Vec<PlacedChar<'a>>,
+}
+
+/// A char of a LineLayout: the text's char and the PixelsChar drawn for it (None if it's a tab or is missing from the collection),
+/// its origin on the baseline and advance, and the upper left corner and width of the pixels to draw
+pub(crate) struct PlacedChar<'a> {
+    pub(crate) text_char: char,
+    pub(crate) char: Option<&'a PixelsChar<u8>>,
+    pub(crate) pen_x: i64,
+    pub(crate) advance: i64,
+    pub(crate) x: i64,
+    pub(crate) y: usize,
+    pub(crate) width: usize,
+}
+
 /// A PixelsCollection obtained from the sum of some PixelsChar bytes, used to create a string chars to be exported or displayed
 #[derive(Clone)]
 pub struct PixelsString {
@@ -1252,6 +1588,7 @@ impl CharsHashmap for std::collections::HashMap<String, char> {
     /// # Examples
     ///
     /// ```no_run
+    /// use pixel_caster::pixels_string::CharsHashmap;
     /// use std::collections::HashMap;
     ///
     /// let mut chars = HashMap::new();
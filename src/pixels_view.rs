@@ -0,0 +1,468 @@
+use crate::{PixelCasterError, PixelValues, PixelsCollection};
+
+/// Borrowed rectangle of a PixelsCollection's pixels, its rows are `stride` values apart in the borrowed bytes.
+/// The bounds are checked once, when the view is created, so that reading its pixels can't index past the end
+pub struct PixelsView<'a, T: PixelValues<T>> {
+    /// Values from the first pixel of the first row to the last pixel of the last row
+    bytes: &'a [T],
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<T: PixelValues<T>> Clone for PixelsView<'_, T> {
+    fn clone(&self) -> Self {
+        PixelsView {
+            bytes: self.bytes,
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+        }
+    }
+}
+
+/// Mutably borrowed rectangle of a PixelsCollection's pixels, see PixelsView
+pub struct PixelsViewMut<'a, T: PixelValues<T>> {
+    bytes: &'a mut [T],
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+/// Returns the range of the `bytes` (rows `stride` values long) covered by the given area, which must be inside them
+fn area_range<T: PixelValues<T>>(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    stride: usize,
+) -> std::ops::Range<usize> {
+    // an empty area can start right after the last pixel, none of its values are ever read
+    if width == 0 || height == 0 {
+        return 0..0;
+    }
+    let units_per_pixel = <T>::units_per_pixel() as usize;
+    let start = y * stride + x * units_per_pixel;
+    start..start + (height - 1) * stride + width * units_per_pixel
+}
+
+impl<T: PixelValues<T>> PixelsCollection<T> {
+    /// Returns a view of all the pixels
+    pub fn view(&self) -> PixelsView<'_, T> {
+        let stride = self.width * <T>::units_per_pixel() as usize;
+        PixelsView {
+            bytes: &self.bytes[area_range::<T>(0, 0, self.width, self.height, stride)],
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+            stride,
+        }
+    }
+
+    /// Returns a mutable view of all the pixels
+    pub fn view_mut(&mut self) -> PixelsViewMut<'_, T> {
+        let stride = self.width * <T>::units_per_pixel() as usize;
+        let range = area_range::<T>(0, 0, self.width, self.height, stride);
+        PixelsViewMut {
+            bytes: &mut self.bytes[range],
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+            stride,
+        }
+    }
+
+    /// Returns a view of the width x height area starting at the x, y pixel, if it's entirely inside the collection
+    pub fn sub_view(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelsView<'_, T>, PixelCasterError> {
+        self.view().into_sub_view(x, y, width, height)
+    }
+
+    /// Returns a mutable view of the width x height area starting at the x, y pixel, if it's entirely inside the collection
+    pub fn sub_view_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelsViewMut<'_, T>, PixelCasterError> {
+        self.view_mut().into_sub_view_mut(x, y, width, height)
+    }
+}
+
+impl<'a, T: PixelValues<T>> PixelsView<'a, T> {
+    /// Returns a view of all the pixels of `bytes`, which hold `height` rows of pixels one after the other
+    pub(crate) fn from_rows(
+        bytes: &'a [T],
+        height: usize,
+    ) -> Result<PixelsView<'a, T>, PixelCasterError> {
+        let units_per_pixel = <T>::units_per_pixel() as usize;
+        let width = bytes.len() / height.max(1) / units_per_pixel;
+        if width * height * units_per_pixel != bytes.len() {
+            return Err(PixelCasterError::DimensionMismatch {
+                expected: width * height * units_per_pixel,
+                actual: bytes.len(),
+            });
+        }
+        Ok(PixelsView {
+            bytes,
+            x: 0,
+            y: 0,
+            width,
+            height,
+            stride: width * units_per_pixel,
+        })
+    }
+
+    /// X position of the view's upper left corner in the PixelsCollection it borrows from
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    /// Y position of the view's upper left corner in the PixelsCollection it borrows from
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Values between the starts of two consecutive rows (the borrowed PixelsCollection's row length)
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the x, y pixel's values (u8 : B, G, R, A, u32 : 0xAARRGGBB), None if it's outside of the view
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a [T]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let units_per_pixel = <T>::units_per_pixel() as usize;
+        let i = y * self.stride + x * units_per_pixel;
+        Some(&self.bytes[i..i + units_per_pixel])
+    }
+
+    /// Returns an iterator over the view's rows, each made of width * units_per_pixel values
+    pub fn rows(&self) -> Rows<'a, T> {
+        Rows {
+            rest: self.bytes,
+            remaining: self.height,
+            stride: self.stride,
+            row_length: self.width * <T>::units_per_pixel() as usize,
+        }
+    }
+
+    /// Returns a view of the width x height area starting at the view's x, y pixel, if it's entirely inside the view
+    pub fn sub_view(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelsView<'a, T>, PixelCasterError> {
+        self.clone().into_sub_view(x, y, width, height)
+    }
+
+    fn into_sub_view(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelsView<'a, T>, PixelCasterError> {
+        PixelCasterError::check_bounds(x as i64, y as i64, width, height, self.width, self.height)?;
+        Ok(PixelsView {
+            bytes: &self.bytes[area_range::<T>(x, y, width, height, self.stride)],
+            x: self.x + x,
+            y: self.y + y,
+            width,
+            height,
+            stride: self.stride,
+        })
+    }
+
+    /// Copies the view's pixels into a new PixelsCollection
+    pub fn to_owned(&self) -> PixelsCollection<T>
+    where
+        T: Copy,
+    {
+        let mut bytes =
+            Vec::with_capacity(self.width * self.height * <T>::units_per_pixel() as usize);
+        self.rows().for_each(|row| bytes.extend_from_slice(row));
+        PixelsCollection::create(self.width, self.height, bytes).unwrap()
+    }
+}
+
+impl<'a, T: PixelValues<T>> PixelsViewMut<'a, T> {
+    /// X position of the view's upper left corner in the PixelsCollection it borrows from
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    /// Y position of the view's upper left corner in the PixelsCollection it borrows from
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Values between the starts of two consecutive rows (the borrowed PixelsCollection's row length)
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns a read-only view of the same area
+    pub fn as_view(&self) -> PixelsView<'_, T> {
+        PixelsView {
+            bytes: self.bytes,
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+        }
+    }
+
+    /// Returns the x, y pixel's values, None if it's outside of the view
+    pub fn get(&self, x: usize, y: usize) -> Option<&[T]> {
+        self.as_view().get(x, y)
+    }
+
+    /// Returns the x, y pixel's values to be modified, None if it's outside of the view
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut [T]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let units_per_pixel = <T>::units_per_pixel() as usize;
+        let i = y * self.stride + x * units_per_pixel;
+        Some(&mut self.bytes[i..i + units_per_pixel])
+    }
+
+    /// Returns an iterator over the view's rows
+    pub fn rows(&self) -> Rows<'_, T> {
+        self.as_view().rows()
+    }
+
+    /// Returns an iterator over the view's rows, to be modified
+    pub fn rows_mut(&mut self) -> RowsMut<'_, T> {
+        RowsMut {
+            rest: self.bytes,
+            remaining: self.height,
+            stride: self.stride,
+            row_length: self.width * <T>::units_per_pixel() as usize,
+        }
+    }
+
+    /// Returns a view of the width x height area starting at the view's x, y pixel, if it's entirely inside the view
+    pub fn sub_view(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelsView<'_, T>, PixelCasterError> {
+        self.as_view().into_sub_view(x, y, width, height)
+    }
+
+    /// Returns a mutable view of the width x height area starting at the view's x, y pixel, if it's entirely inside the view
+    pub fn sub_view_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelsViewMut<'_, T>, PixelCasterError> {
+        PixelsViewMut {
+            bytes: &mut *self.bytes,
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+        }
+        .into_sub_view_mut(x, y, width, height)
+    }
+
+    fn into_sub_view_mut(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelsViewMut<'a, T>, PixelCasterError> {
+        PixelCasterError::check_bounds(x as i64, y as i64, width, height, self.width, self.height)?;
+        let range = area_range::<T>(x, y, width, height, self.stride);
+        Ok(PixelsViewMut {
+            bytes: &mut self.bytes[range],
+            x: self.x + x,
+            y: self.y + y,
+            width,
+            height,
+            stride: self.stride,
+        })
+    }
+
+    /// Copies the view's pixels into a new PixelsCollection
+    pub fn to_owned(&self) -> PixelsCollection<T>
+    where
+        T: Copy,
+    {
+        self.as_view().to_owned()
+    }
+}
+
+/// Iterator over the rows of a PixelsView
+pub struct Rows<'a, T> {
+    rest: &'a [T],
+    remaining: usize,
+    stride: usize,
+    row_length: usize,
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        // the last row is not followed by the stride's padding
+        let (row, rest) = self.rest.split_at(self.stride.min(self.rest.len()));
+        self.rest = rest;
+        Some(&row[..self.row_length])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
+
+/// Iterator over the rows of a PixelsViewMut
+pub struct RowsMut<'a, T> {
+    rest: &'a mut [T],
+    remaining: usize,
+    stride: usize,
+    row_length: usize,
+}
+
+impl<'a, T> Iterator for RowsMut<'a, T> {
+    type Item = &'a mut [T];
+
+    fn next(&mut self) -> Option<&'a mut [T]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let rest = std::mem::take(&mut self.rest);
+        let (row, rest) = rest.split_at_mut(self.stride.min(rest.len()));
+        self.rest = rest;
+        Some(&mut row[..self.row_length])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for RowsMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x3 pixels, each one's BGRA being [x, y, x + y, 255]
+    fn collection() -> PixelsCollection<u8> {
+        let mut bytes = Vec::new();
+        for y in 0..3u8 {
+            for x in 0..4u8 {
+                bytes.extend_from_slice(&[x, y, x + y, 255]);
+            }
+        }
+        PixelsCollection::<u8>::create(4, 3, bytes).unwrap()
+    }
+
+    #[test]
+    fn views_and_sub_views() {
+        let collection = collection();
+        let view = collection.sub_view(1, 1, 3, 2).unwrap();
+        assert_eq!(
+            (view.x(), view.y(), view.width(), view.height()),
+            (1, 1, 3, 2)
+        );
+        assert_eq!(view.stride(), 16);
+        assert_eq!(view.get(0, 0), Some(&[1, 1, 2, 255][..]));
+        assert_eq!(view.get(2, 1), Some(&[3, 2, 5, 255][..]));
+        assert_eq!(view.get(3, 0), None);
+        assert_eq!(view.rows().len(), 2);
+        assert!(view.rows().all(|row| row.len() == 12));
+
+        let sub_view = view.sub_view(1, 1, 2, 1).unwrap();
+        assert_eq!((sub_view.x(), sub_view.y()), (2, 2));
+        let owned = sub_view.to_owned();
+        assert_eq!((owned.width, owned.height), (2, 1));
+        assert_eq!(owned.bytes, vec![2, 2, 4, 255, 3, 2, 5, 255]);
+
+        assert!(matches!(
+            view.sub_view(1, 0, 3, 1),
+            Err(PixelCasterError::OutOfBounds { .. })
+        ));
+        assert!(collection.sub_view(0, 3, 1, 1).is_err());
+        assert!(collection.sub_view(4, 3, 0, 0).is_ok());
+        assert_eq!(collection.sub_view(2, 0, 0, 3).unwrap().rows().count(), 3);
+        assert_eq!(collection.view().to_owned().bytes, collection.bytes);
+    }
+
+    #[test]
+    fn mutable_views() {
+        let mut collection = collection();
+        let mut view = collection.sub_view_mut(1, 0, 2, 3).unwrap();
+        view.get_mut(0, 0).unwrap().copy_from_slice(&[9, 9, 9, 9]);
+        view.sub_view_mut(1, 1, 1, 2)
+            .unwrap()
+            .rows_mut()
+            .for_each(|row| row.fill(7));
+        assert_eq!(view.get(0, 0), Some(&[9, 9, 9, 9][..]));
+        assert!(view.get_mut(2, 0).is_none());
+
+        assert_eq!(collection.bytes[4..8], [9, 9, 9, 9]);
+        assert_eq!(collection.bytes[8..12], [2, 0, 2, 255]);
+        assert_eq!(collection.bytes[24..28], [7, 7, 7, 7]);
+        assert_eq!(collection.bytes[40..44], [7, 7, 7, 7]);
+        assert_eq!(collection.bytes[44..48], [3, 2, 5, 255]);
+
+        let mut collection = PixelsCollection::<u32>::create(2, 2, vec![1, 2, 3, 4]).unwrap();
+        collection
+            .view_mut()
+            .rows_mut()
+            .for_each(|row| row.reverse());
+        assert_eq!(collection.bytes, vec![2, 1, 4, 3]);
+        assert_eq!(collection.view().get(1, 1), Some(&[3][..]));
+    }
+}
@@ -0,0 +1,305 @@
+use std::f32::consts::PI;
+
+use crate::pixels::ResizeSize;
+use crate::PixelsCollection;
+
+/// How the pixels are sampled by PixelsCollection::create_new_resized_with_filter.
+/// Apart from Nearest, the filters work on premultiplied Alpha, so that the colors of the transparent pixels
+/// don't bleed into the visible ones (e.g.: no dark fringes around the edges of glyphs on a transparent black background)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResizeFilter {
+    /// Each pixel takes the color of the closest source one, keeps hard edges (pixel art, glyphs made for a specific size)
+    Nearest,
+    /// Linear interpolation between the 2x2 closest source pixels (or the average of the covered ones when downscaling)
+    Bilinear,
+    /// Catmull-Rom cubic interpolation between the 4x4 closest source pixels, sharper than Bilinear
+    Bicubic,
+    /// Windowed sinc over the 6x6 closest source pixels, the sharpest, may slightly ring around hard edges
+    Lanczos3,
+    /// Average of the source pixels each pixel covers, the best for downscaling (as good as Nearest for upscaling)
+    Box,
+}
+
+impl ResizeFilter {
+    /// Distance from the sampled point beyond which the source pixels don't contribute (at a 1:1 scale)
+    fn support(&self) -> f32 {
+        match self {
+            ResizeFilter::Nearest | ResizeFilter::Box => 0.5,
+            ResizeFilter::Bilinear => 1.0,
+            ResizeFilter::Bicubic => 2.0,
+            ResizeFilter::Lanczos3 => 3.0,
+        }
+    }
+
+    /// Weight of a source pixel at the given distance from the sampled point
+    fn weight(&self, distance: f32) -> f32 {
+        let d = distance.abs();
+        match self {
+            ResizeFilter::Nearest | ResizeFilter::Box => {
+                if d <= 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Bilinear => (1.0 - d).max(0.0),
+            // Catmull-Rom (a = -0.5)
+            ResizeFilter::Bicubic => {
+                if d < 1.0 {
+                    1.5 * d * d * d - 2.5 * d * d + 1.0
+                } else if d < 2.0 {
+                    -0.5 * d * d * d + 2.5 * d * d - 4.0 * d + 2.0
+                } else {
+                    0.0
+                }
+            }
+            ResizeFilter::Lanczos3 => {
+                if d < 1e-6 {
+                    1.0
+                } else if d < 3.0 {
+                    let pd = PI * d;
+                    3.0 * pd.sin() * (pd / 3.0).sin() / (pd * pd)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+impl PixelsCollection<u8> {
+    /// Returns a new PixelsCollection from the provided one, scaled to the provided ResizeSize (either the width or the height keeping the aspect ratio, or both)
+    /// taking the closest source pixel (ResizeFilter::Nearest). The new sizes are at least 1 pixel
+    pub fn create_new_resized(&self, resize_size: ResizeSize) -> PixelsCollection<u8> {
+        self.create_new_resized_with_filter(resize_size, ResizeFilter::Nearest)
+    }
+
+    /// Same as create_new_resized, sampling the pixels with the given ResizeFilter
+    pub fn create_new_resized_with_filter(
+        &self,
+        resize_size: ResizeSize,
+        filter: ResizeFilter,
+    ) -> PixelsCollection<u8> {
+        let (new_width, new_height) = match resize_size {
+            ResizeSize::Width(w) => (w, (w * self.height) / self.width.max(1)),
+            ResizeSize::Height(h) => ((h * self.width) / self.height.max(1), h),
+            ResizeSize::WidthAndHeight(w, h) => (w, h),
+        };
+        let (new_width, new_height) = (new_width.max(1), new_height.max(1));
+
+        if self.width == 0 || self.height == 0 {
+            return PixelsCollection::create(
+                new_width,
+                new_height,
+                vec![0; new_width * new_height * 4],
+            )
+            .unwrap();
+        }
+        let bytes = match filter {
+            ResizeFilter::Nearest => self.resized_nearest(new_width, new_height),
+            _ => self.resized_filtered(new_width, new_height, filter),
+        };
+        PixelsCollection::create(new_width, new_height, bytes).unwrap()
+    }
+
+    fn resized_nearest(&self, new_width: usize, new_height: usize) -> Vec<u8> {
+        let x_ratio: f32 = self.width as f32 / new_width as f32;
+        let y_ratio: f32 = self.height as f32 / new_height as f32;
+
+        let mut bytes_resized = Vec::<u8>::with_capacity(new_width * new_height * 4);
+        for fy in 0..new_height {
+            let y = ((fy as f32 * y_ratio) as usize).min(self.height - 1);
+            for fx in 0..new_width {
+                let x = ((fx as f32 * x_ratio) as usize).min(self.width - 1);
+                let pixel_needed = self.row_length * y + 4 * x;
+                bytes_resized.extend_from_slice(&self.bytes[pixel_needed..pixel_needed + 4]);
+            }
+        }
+        bytes_resized
+    }
+
+    /// Separable resampling, first the rows then the columns, of the premultiplied pixels
+    fn resized_filtered(
+        &self,
+        new_width: usize,
+        new_height: usize,
+        filter: ResizeFilter,
+    ) -> Vec<u8> {
+        let premultiplied: Vec<f32> = self
+            .bytes
+            .chunks_exact(4)
+            .flat_map(|bgra| {
+                let a = bgra[3] as f32;
+                [
+                    bgra[0] as f32 * a / 255.0,
+                    bgra[1] as f32 * a / 255.0,
+                    bgra[2] as f32 * a / 255.0,
+                    a,
+                ]
+            })
+            .collect();
+
+        let x_weights = contributions(self.width, new_width, filter);
+        let mut horizontal = vec![0f32; new_width * self.height * 4];
+        for y in 0..self.height {
+            let src_row = &premultiplied[y * self.width * 4..(y + 1) * self.width * 4];
+            let dst_row = &mut horizontal[y * new_width * 4..(y + 1) * new_width * 4];
+            for (dst, (first, weights)) in dst_row.chunks_exact_mut(4).zip(&x_weights) {
+                for (i, w) in weights.iter().enumerate() {
+                    let src = &src_row[(first + i) * 4..(first + i) * 4 + 4];
+                    for c in 0..4 {
+                        dst[c] += src[c] * w;
+                    }
+                }
+            }
+        }
+
+        let y_weights = contributions(self.height, new_height, filter);
+        let mut bytes_resized = Vec::<u8>::with_capacity(new_width * new_height * 4);
+        for (first, weights) in &y_weights {
+            for x in 0..new_width {
+                let mut bgra = [0f32; 4];
+                for (i, w) in weights.iter().enumerate() {
+                    let src = &horizontal[((first + i) * new_width + x) * 4..][..4];
+                    for c in 0..4 {
+                        bgra[c] += src[c] * w;
+                    }
+                }
+                // the filters with negative lobes can overshoot
+                let a = bgra[3].clamp(0.0, 255.0);
+                if a < 0.5 {
+                    bytes_resized.extend_from_slice(&[0, 0, 0, 0]);
+                    continue;
+                }
+                for c in &bgra[..3] {
+                    bytes_resized.push((c.clamp(0.0, a) * 255.0 / a).round() as u8);
+                }
+                bytes_resized.push(a.round() as u8);
+            }
+        }
+        bytes_resized
+    }
+}
+
+/// For each of the `dst_len` pixels of a row (or column), the first of the `src_len` source pixels it samples and their normalized weights
+fn contributions(src_len: usize, dst_len: usize, filter: ResizeFilter) -> Vec<(usize, Vec<f32>)> {
+    let ratio = src_len as f32 / dst_len as f32;
+    // when downscaling the filter is stretched so that every source pixel contributes
+    let filter_scale = ratio.max(1.0);
+    let support = filter.support() * filter_scale;
+
+    (0..dst_len)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * ratio;
+            let first = ((center - support).floor().max(0.0) as usize).min(src_len - 1);
+            let last = ((center + support).ceil() as usize).clamp(first + 1, src_len);
+            let mut weights: Vec<f32> = (first..last)
+                .map(|j| filter.weight((j as f32 + 0.5 - center) / filter_scale))
+                .collect();
+            let sum: f32 = weights.iter().sum();
+            if sum.abs() < f32::EPSILON {
+                // no source pixel in range (e.g.: Box exactly between two pixels), take the closest one
+                let closest = ((center as usize).min(src_len - 1)).max(first);
+                weights = (first..last).map(|j| (j == closest) as u8 as f32).collect();
+            } else {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            }
+            (first, weights)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILTERS: [ResizeFilter; 5] = [
+        ResizeFilter::Nearest,
+        ResizeFilter::Bilinear,
+        ResizeFilter::Bicubic,
+        ResizeFilter::Lanczos3,
+        ResizeFilter::Box,
+    ];
+
+    fn pixels(width: usize, height: usize, bgra: &[[u8; 4]]) -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(width, height, bgra.concat()).unwrap()
+    }
+
+    #[test]
+    fn sizes_and_uniform_colors() {
+        let uniform = pixels(3, 2, &[[10, 200, 30, 255]; 6]);
+        for filter in FILTERS {
+            for (resize_size, size) in [
+                (ResizeSize::WidthAndHeight(5, 7), (5, 7)),
+                (ResizeSize::WidthAndHeight(1, 1), (1, 1)),
+                (ResizeSize::Width(6), (6, 4)),
+                (ResizeSize::Height(1), (1, 1)),
+                (ResizeSize::WidthAndHeight(0, 0), (1, 1)),
+            ] {
+                let resized = uniform.create_new_resized_with_filter(resize_size, filter);
+                assert_eq!((resized.width, resized.height), size);
+                assert!(resized
+                    .bytes
+                    .chunks_exact(4)
+                    .all(|c| c == [10, 200, 30, 255]));
+            }
+        }
+    }
+
+    #[test]
+    fn filters() {
+        let (black, white) = ([0, 0, 0, 255], [255, 255, 255, 255]);
+        let nearest =
+            pixels(2, 1, &[black, white]).create_new_resized(ResizeSize::WidthAndHeight(4, 1));
+        assert_eq!(nearest.bytes, [black, black, white, white].concat());
+
+        let averaged = pixels(2, 1, &[black, white])
+            .create_new_resized_with_filter(ResizeSize::WidthAndHeight(1, 1), ResizeFilter::Box);
+        assert_eq!(averaged.bytes, [128, 128, 128, 255]);
+
+        let interpolated = pixels(2, 1, &[black, white]).create_new_resized_with_filter(
+            ResizeSize::WidthAndHeight(4, 1),
+            ResizeFilter::Bilinear,
+        );
+        assert_eq!(
+            interpolated.bytes,
+            [black, [64, 64, 64, 255], [191, 191, 191, 255], white].concat()
+        );
+    }
+
+    #[test]
+    fn premultiplied_filtering() {
+        // an opaque white glyph's edge next to transparent black pixels stays white, only its Alpha fades
+        let edge = pixels(
+            2,
+            2,
+            &[
+                [255, 255, 255, 255],
+                [0, 0, 0, 0],
+                [255, 255, 255, 255],
+                [0, 0, 0, 0],
+            ],
+        );
+        for filter in [
+            ResizeFilter::Bilinear,
+            ResizeFilter::Bicubic,
+            ResizeFilter::Lanczos3,
+            ResizeFilter::Box,
+        ] {
+            let resized =
+                edge.create_new_resized_with_filter(ResizeSize::WidthAndHeight(1, 1), filter);
+            assert_eq!(resized.bytes, [255, 255, 255, 128], "{:?}", filter);
+
+            let resized =
+                edge.create_new_resized_with_filter(ResizeSize::WidthAndHeight(5, 3), filter);
+            for bgra in resized.bytes.chunks_exact(4) {
+                assert!(
+                    bgra[3] == 0 || bgra[..3] == [255, 255, 255],
+                    "{:?} {:?}",
+                    filter,
+                    bgra
+                );
+            }
+        }
+    }
+}
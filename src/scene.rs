@@ -0,0 +1,422 @@
+use crate::{
+    BlendMode, PixelCasterError, PixelsCollection, PixelsSendMode, Screen, ScreenArea,
+    ScreenBackend,
+};
+
+/// Rectangle as x0, y0, x1, y1 with exclusive ends, in scene coordinates
+type Rect = (i64, i64, i64, i64);
+
+/// Pixels placed in a Scene
+#[derive(Clone)]
+pub struct Sprite {
+    pub pixels: PixelsCollection<u8>,
+    /// Position of the upper left corner, relative to the scene's one (it can be partly or fully outside of the scene)
+    pub x: i32,
+    pub y: i32,
+    /// Sprites with a higher z_index are drawn over those with a lower one, those with the same z_index in the order they were added
+    pub z_index: i32,
+    /// Scales the Alpha of all the pixels (255 : as they are, 0 : invisible)
+    pub opacity: u8,
+    pub visible: bool,
+}
+
+impl Sprite {
+    /// Creates a visible, fully opaque sprite at x, y, with z_index 0
+    pub fn new(pixels: PixelsCollection<u8>, x: i32, y: i32) -> Sprite {
+        Sprite {
+            pixels,
+            x,
+            y,
+            z_index: 0,
+            opacity: 255,
+            visible: true,
+        }
+    }
+
+    /// Area it covers when drawn, None if it's not drawn
+    fn drawn_rect(&self) -> Option<Rect> {
+        (self.visible && self.opacity > 0 && self.pixels.width > 0 && self.pixels.height > 0).then(
+            || {
+                let (x, y) = (self.x as i64, self.y as i64);
+                (
+                    x,
+                    y,
+                    x + self.pixels.width as i64,
+                    y + self.pixels.height as i64,
+                )
+            },
+        )
+    }
+}
+
+/// Identifies a sprite added to a Scene
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpriteId(usize);
+
+struct Entry {
+    id: SpriteId,
+    sprite: Sprite,
+    // where the sprite was drawn by the last render
+    rendered: Option<Rect>,
+    // whether the sprite could have changed since the last render
+    touched: bool,
+}
+
+/// Sprites composited in memory over a background, to send them to the screen all at once.
+/// Only the damaged region (where the sprites, or the background, changed since the last render) is composited again and sent
+pub struct Scene {
+    background: PixelsCollection<u8>,
+    // what was on the screen under a background that's not fully opaque, captured by the first present that blends
+    underlay: Option<PixelsCollection<u8>>,
+    buffer: PixelsCollection<u8>,
+    entries: Vec<Entry>,
+    next_id: usize,
+    damage: Option<Rect>,
+}
+
+impl Scene {
+    /// Creates a scene of the background's size, without sprites. The whole scene is damaged until the first render
+    pub fn new(background: PixelsCollection<u8>) -> Scene {
+        Scene {
+            damage: Some((0, 0, background.width as i64, background.height as i64)),
+            buffer: background.clone(),
+            background,
+            underlay: None,
+            entries: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Creates a scene whose background is what's currently on the screen in the Screen's set pixels area
+    pub fn from_screen<B: ScreenBackend>(
+        screen: &mut Screen<u8, B>,
+    ) -> Result<Scene, PixelCasterError> {
+        screen.scan_area()?;
+        Ok(Scene::new(screen.get_pixels_collection().clone()))
+    }
+
+    pub fn width(&self) -> usize {
+        self.background.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.background.height
+    }
+
+    /// Returns the composited pixels, as of the last render
+    pub fn pixels(&self) -> &PixelsCollection<u8> {
+        &self.buffer
+    }
+
+    /// Replaces the background, which must have the scene's size
+    pub fn set_background(
+        &mut self,
+        background: PixelsCollection<u8>,
+    ) -> Result<(), PixelCasterError> {
+        if background.width != self.width() || background.height != self.height() {
+            return Err(PixelCasterError::DimensionMismatch {
+                expected: self.background.bytes.len(),
+                actual: background.bytes.len(),
+            });
+        }
+        self.background = background;
+        self.add_damage(Some((0, 0, self.width() as i64, self.height() as i64)));
+        Ok(())
+    }
+
+    /// Adds the sprite over those already added with its same z_index
+    pub fn add(&mut self, sprite: Sprite) -> SpriteId {
+        let id = SpriteId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry {
+            id,
+            sprite,
+            rendered: None,
+            touched: true,
+        });
+        id
+    }
+
+    /// Removes the sprite, returning it, None if it's not in the scene
+    pub fn remove(&mut self, id: SpriteId) -> Option<Sprite> {
+        let i = self.entries.iter().position(|entry| entry.id == id)?;
+        let entry = self.entries.remove(i);
+        self.add_damage(entry.rendered);
+        Some(entry.sprite)
+    }
+
+    pub fn sprite(&self, id: SpriteId) -> Option<&Sprite> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| &entry.sprite)
+    }
+
+    /// Returns the sprite to change it (e.g.: move it, hide it, alter its pixels), its old and new areas are damaged
+    pub fn sprite_mut(&mut self, id: SpriteId) -> Option<&mut Sprite> {
+        let i = self.entries.iter().position(|entry| entry.id == id)?;
+        self.add_damage(self.entries[i].rendered);
+        let entry = &mut self.entries[i];
+        entry.touched = true;
+        Some(&mut entry.sprite)
+    }
+
+    /// Returns the topmost drawn sprite having a visible pixel at x, y (scene coordinates), as of the current sprites' state
+    pub fn hit_test(&self, x: i32, y: i32) -> Option<SpriteId> {
+        self.drawing_order()
+            .into_iter()
+            .rev()
+            .find(|entry| {
+                entry.sprite.drawn_rect().is_some()
+                    && x >= entry.sprite.x
+                    && y >= entry.sprite.y
+                    && entry
+                        .sprite
+                        .pixels
+                        .view()
+                        .get((x - entry.sprite.x) as usize, (y - entry.sprite.y) as usize)
+                        .is_some_and(|bgra| bgra[3] > 0)
+            })
+            .map(|entry| entry.id)
+    }
+
+    /// Composites the damaged region again, returning it (relative to the scene's upper left corner), None if nothing changed
+    pub fn render(&mut self) -> Option<ScreenArea> {
+        for entry in self.entries.iter_mut().filter(|entry| entry.touched) {
+            entry.touched = false;
+            entry.rendered = entry.sprite.drawn_rect();
+            let rendered = entry.rendered;
+            self.damage = union(self.damage, rendered);
+        }
+        let (x0, y0, x1, y1) = intersection(
+            self.damage.take()?,
+            (0, 0, self.width() as i64, self.height() as i64),
+        )?;
+        let (width, height) = ((x1 - x0) as usize, (y1 - y0) as usize);
+
+        let background = self
+            .background
+            .sub_view(x0 as usize, y0 as usize, width, height)
+            .unwrap();
+        let mut region = match &self.underlay {
+            Some(underlay) => {
+                let mut region = underlay
+                    .sub_view(x0 as usize, y0 as usize, width, height)
+                    .unwrap()
+                    .to_owned();
+                region.composite(background, 0, 0, BlendMode::SourceOver, 255);
+                region
+            }
+            None => background.to_owned(),
+        };
+        for entry in self.drawing_order() {
+            if let Some(rect) = entry.sprite.drawn_rect() {
+                if intersection(rect, (x0, y0, x1, y1)).is_some() {
+                    region.composite(
+                        entry.sprite.pixels.view(),
+                        (rect.0 - x0) as i32,
+                        (rect.1 - y0) as i32,
+                        BlendMode::SourceOver,
+                        entry.sprite.opacity,
+                    );
+                }
+            }
+        }
+        let mut dst = self
+            .buffer
+            .sub_view_mut(x0 as usize, y0 as usize, width, height)
+            .unwrap();
+        for (src_row, dst_row) in region.view().rows().zip(dst.rows_mut()) {
+            dst_row.copy_from_slice(src_row);
+        }
+        Some(ScreenArea::new(
+            x0 as i32,
+            y0 as i32,
+            width as u32,
+            height as u32,
+        ))
+    }
+
+    /// Renders the scene and sends the damaged region to its place in the Screen's set pixels area (which must have the scene's size)
+    /// with the Screen's PixelsSendMode, in a single send. Returns the region sent, None if nothing changed.
+    /// The region must cover what was sent before for the sprites to be erased from their old places: with a blending PixelsSendMode
+    /// (AlphaEnabled or CustomAlpha) and a background that's not fully opaque (e.g.: not made by from_screen),
+    /// the first present captures what's on the screen under the scene and composites the background over it from then on
+    pub fn present<B: ScreenBackend>(
+        &mut self,
+        screen: &Screen<u8, B>,
+    ) -> Result<Option<ScreenArea>, PixelCasterError> {
+        let area = screen.screen_area;
+        if area.width as usize != self.width() || area.height as usize != self.height() {
+            return Err(PixelCasterError::DimensionMismatch {
+                expected: self.buffer.bytes.len(),
+                actual: area.width as usize * area.height as usize * 4,
+            });
+        }
+        let blending = matches!(
+            screen.pixels_send_mode,
+            PixelsSendMode::AlphaEnabled | PixelsSendMode::CustomAlpha(_)
+        );
+        if blending
+            && self.underlay.is_none()
+            && self
+                .background
+                .bytes
+                .chunks_exact(4)
+                .any(|bgra| bgra[3] < 255)
+        {
+            let mut underlay = self.background.clone();
+            screen.get_backend().capture_rect(
+                &mut underlay.bytes,
+                area.upperleftcorner_x,
+                area.upperleftcorner_y,
+                area.width,
+                area.height,
+            )?;
+            self.underlay = Some(underlay);
+            self.add_damage(Some((0, 0, self.width() as i64, self.height() as i64)));
+        }
+        let Some(damaged) = self.render() else {
+            return Ok(None);
+        };
+        let region = self
+            .buffer
+            .sub_view(
+                damaged.upperleftcorner_x as usize,
+                damaged.upperleftcorner_y as usize,
+                damaged.width as usize,
+                damaged.height as usize,
+            )?
+            .to_owned();
+        screen.get_backend().present_rect(
+            &region.bytes,
+            area.upperleftcorner_x + damaged.upperleftcorner_x,
+            area.upperleftcorner_y + damaged.upperleftcorner_y,
+            damaged.width,
+            damaged.height,
+            screen.pixels_send_mode,
+        )?;
+        Ok(Some(damaged))
+    }
+
+    /// The entries from the bottom one to the topmost one
+    fn drawing_order(&self) -> Vec<&Entry> {
+        let mut ordered: Vec<&Entry> = self.entries.iter().collect();
+        // stable, those with the same z_index stay in the order they were added
+        ordered.sort_by_key(|entry| entry.sprite.z_index);
+        ordered
+    }
+
+    fn add_damage(&mut self, rect: Option<Rect>) {
+        self.damage = union(self.damage, rect);
+    }
+}
+
+fn union(a: Option<Rect>, b: Option<Rect>) -> Option<Rect> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some((a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))),
+        (a, b) => a.or(b),
+    }
+}
+
+fn intersection(a: Rect, b: Rect) -> Option<Rect> {
+    let i = (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3));
+    (i.0 < i.2 && i.1 < i.3).then_some(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VirtualScreen;
+
+    fn filled(width: usize, height: usize, bgra: [u8; 4]) -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(width, height, bgra.repeat(width * height)).unwrap()
+    }
+
+    #[test]
+    fn z_order_opacity_and_hit_testing() {
+        let mut scene = Scene::new(filled(5, 4, [0, 0, 0, 255]));
+        let red = scene.add(Sprite::new(filled(2, 2, [0, 0, 255, 255]), 1, 1));
+        let mut green = Sprite::new(filled(2, 2, [0, 255, 0, 255]), 2, 2);
+        green.z_index = -1;
+        let green = scene.add(green);
+        let blue = scene.add(Sprite::new(filled(1, 1, [255, 0, 0, 255]), 4, 3));
+        scene.sprite_mut(blue).unwrap().opacity = 128;
+
+        assert_eq!(scene.render(), Some(ScreenArea::new(0, 0, 5, 4)));
+        assert_eq!(scene.render(), None);
+        let pixel = |scene: &Scene, x, y| scene.pixels().view().get(x, y).unwrap().to_vec();
+        // red is over green, despite being added before it
+        assert_eq!(pixel(&scene, 2, 2), [0, 0, 255, 255]);
+        assert_eq!(pixel(&scene, 3, 3), [0, 255, 0, 255]);
+        assert_eq!(pixel(&scene, 4, 3), [128, 0, 0, 255]);
+
+        assert_eq!(scene.hit_test(2, 2), Some(red));
+        assert_eq!(scene.hit_test(3, 2), Some(green));
+        assert_eq!(scene.hit_test(0, 0), None);
+        scene.sprite_mut(red).unwrap().visible = false;
+        assert_eq!(scene.hit_test(2, 2), Some(green));
+        assert!(scene.remove(green).is_some());
+        assert_eq!(scene.hit_test(2, 2), None);
+        assert!(scene.sprite(green).is_none());
+    }
+
+    #[test]
+    fn damaged_region_presenting() {
+        let virtual_screen = VirtualScreen::new(8, 6);
+        let screen = Screen::<u8, VirtualScreen>::with_backend(virtual_screen.clone(), 2, 1, 5, 4);
+        let mut scene = Scene::new(filled(5, 4, [50, 50, 50, 255]));
+        let marker = scene.add(Sprite::new(filled(1, 1, [0, 0, 255, 255]), 0, 0));
+        scene.add(Sprite::new(filled(2, 1, [0, 255, 0, 255]), 3, 3));
+        assert_eq!(
+            scene.present(&screen).unwrap(),
+            Some(ScreenArea::new(0, 0, 5, 4))
+        );
+        assert_eq!(virtual_screen.get_pixel(2, 1), Some([0, 0, 255, 255]));
+        assert_eq!(virtual_screen.get_pixel(5, 4), Some([0, 255, 0, 255]));
+        assert_eq!(scene.present(&screen).unwrap(), None);
+
+        // the marker moves: only its old and new places are sent
+        virtual_screen.framebuffer().bytes.fill(0);
+        scene.sprite_mut(marker).unwrap().x = 1;
+        assert_eq!(
+            scene.present(&screen).unwrap(),
+            Some(ScreenArea::new(0, 0, 2, 1))
+        );
+        assert_eq!(virtual_screen.get_pixel(2, 1), Some([50, 50, 50, 255]));
+        assert_eq!(virtual_screen.get_pixel(3, 1), Some([0, 0, 255, 255]));
+        assert_eq!(virtual_screen.get_pixel(4, 1), Some([0, 0, 0, 0]));
+        assert_eq!(virtual_screen.get_pixel(5, 4), Some([0, 0, 0, 0]));
+
+        let other_size = Screen::<u8, VirtualScreen>::with_backend(virtual_screen, 0, 0, 4, 4);
+        assert!(scene.present(&other_size).is_err());
+    }
+
+    #[test]
+    fn moving_over_transparent_background() {
+        let virtual_screen = VirtualScreen::new(8, 1);
+        virtual_screen
+            .framebuffer()
+            .bytes
+            .copy_from_slice(&[50, 50, 50, 255].repeat(8));
+        let mut screen =
+            Screen::<u8, VirtualScreen>::with_backend(virtual_screen.clone(), 0, 0, 8, 1);
+        let mut scene = Scene::new(filled(8, 1, [0; 4]));
+        let red = scene.add(Sprite::new(filled(1, 1, [0, 0, 255, 255]), 0, 0));
+        scene.present(&screen).unwrap();
+        assert_eq!(virtual_screen.get_pixel(0, 0), Some([0, 0, 255, 255]));
+
+        // the sprite's old place gets back what was on the screen under the scene
+        scene.sprite_mut(red).unwrap().x = 5;
+        scene.present(&screen).unwrap();
+        assert_eq!(virtual_screen.get_pixel(0, 0), Some([50, 50, 50, 255]));
+        assert_eq!(virtual_screen.get_pixel(5, 0), Some([0, 0, 255, 255]));
+
+        // the Screen's PixelsSendMode is used: the sprite's color is hidden
+        screen.set_pixels_send_mode(PixelsSendMode::AlphaDisabledHideBGR(0, 0, 255));
+        scene.sprite_mut(red).unwrap().x = 2;
+        scene.present(&screen).unwrap();
+        assert_eq!(virtual_screen.get_pixel(2, 0), Some([50, 50, 50, 255]));
+        assert_eq!(virtual_screen.get_pixel(5, 0), Some([50, 50, 50, 255]));
+    }
+}
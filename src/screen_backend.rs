@@ -0,0 +1,71 @@
+use crate::{PixelCasterError, PixelsSendMode};
+
+/// Backend used by `Screen<T>` when none is specified
+#[cfg(windows)]
+pub type DefaultBackend = crate::gdi_backend::GdiBackend;
+/// Backend used by `Screen<T>` when none is specified, the X server set by $DISPLAY
+#[cfg(all(unix, feature = "x11"))]
+pub type DefaultBackend = crate::x11_backend::X11Backend;
+/// Backend used by `Screen<T>` when none is specified, an in-memory screen where there's no GDI (nor X11, if its feature is not enabled)
+#[cfg(not(any(windows, all(unix, feature = "x11"))))]
+pub type DefaultBackend = crate::virtual_screen::VirtualScreen;
+
+/// Operations that a platform (or an in-memory buffer) must provide to let a `Screen` get/send color bytes from/to it.
+///
+/// Every method works on BGRA ordered bytes (4 bytes per pixel, row by row, without padding),
+/// `Screen<u32>` reinterprets its Vec<u32> as such bytes before passing it to the backend.
+pub trait ScreenBackend {
+    /// Prepares the backend to work with areas of area_width x area_height pixels.
+    /// `is_static` determines if the backend will be kept after use (owned by a Screen instance) or if it's made for a single run
+    fn open(area_width: u32, area_height: u32, is_static: bool) -> Result<Self, PixelCasterError>
+    where
+        Self: Sized;
+
+    /// Copies the BGRA bytes of the given screen area into `dst`, which must already have the necessary length to host the values
+    fn capture_rect(
+        &self,
+        dst: &mut [u8],
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Result<(), PixelCasterError>;
+
+    /// Sends the BGRA bytes of `src` to the given screen area, treating them as defined by the provided PixelsSendMode
+    fn present_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> Result<(), PixelCasterError>;
+
+    /// Sends the BGRA bytes of `src`, which Blue Green Red are already premultiplied by their Alpha, to the given screen area,
+    /// blending them with its content as AlphaBlend does. `source_constant_alpha` scales the whole area's opacity (255 : per-pixel Alpha only).
+    /// present_rect's PixelsSendMode::AlphaEnabled and CustomAlpha premultiply the bytes and then go through here
+    fn present_premultiplied_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        source_constant_alpha: u8,
+    ) -> Result<(), PixelCasterError>;
+
+    /// Copies the pixels from a given area of the screen and pastes them onto another given area of the screen
+    fn copy_rect(
+        &self,
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+    ) -> Result<(), PixelCasterError>;
+
+    /// Returns the area covered by the whole screen (e.g.: the entire virtual screen, not just a monitor)
+    fn screen_bounds(&self) -> crate::ScreenArea;
+}
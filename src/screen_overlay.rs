@@ -0,0 +1,479 @@
+use crate::{
+    DefaultBackend, PixelCasterError, PixelValues, PixelsCollection, PixelsSendMode, ScreenBackend,
+    VirtualScreen,
+};
+
+/// Rectangle as x0, y0, x1, y1 with exclusive ends, in screen coordinates
+type Rect = (i64, i64, i64, i64);
+
+/// Pixels drawn over the screen (e.g.: a PixelsString's pixels) that can be redrawn, moved and removed,
+/// as it saves the screen's pixels under its area before drawing there and puts them back when they're uncovered.
+/// The saved pixels are put back when it's dropped too
+pub struct ScreenOverlay<B: ScreenBackend = DefaultBackend> {
+    backend: B,
+    x: i32,
+    y: i32,
+    pixels: PixelsCollection<u8>,
+    pixels_send_mode: PixelsSendMode,
+    // the screen's original pixels under the overlay, Some while it's shown
+    under: Option<PixelsCollection<u8>>,
+    // the area size the backend was opened for, when the overlay opened it
+    opened_size: Option<(usize, usize)>,
+}
+
+impl<B: ScreenBackend> ScreenOverlay<B> {
+    /// Prepares an overlay of the pixels, with their upper left corner at x, y, through a newly opened ScreenBackend.
+    /// It's not drawn until show or update
+    pub fn new(
+        pixels: PixelsCollection<u8>,
+        x: i32,
+        y: i32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> Result<ScreenOverlay<B>, PixelCasterError> {
+        let opened_size = (pixels.width, pixels.height);
+        let mut overlay = Self::with_backend(
+            B::open(pixels.width as u32, pixels.height as u32, true)?,
+            pixels,
+            x,
+            y,
+            pixels_send_mode,
+        );
+        overlay.opened_size = Some(opened_size);
+        Ok(overlay)
+    }
+
+    /// Prepares an overlay of the pixels, with their upper left corner at x, y, that will be drawn through the provided ScreenBackend,
+    /// which must work with areas of any size the overlay's pixels will have
+    pub fn with_backend(
+        backend: B,
+        pixels: PixelsCollection<u8>,
+        x: i32,
+        y: i32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> ScreenOverlay<B> {
+        ScreenOverlay {
+            backend,
+            x,
+            y,
+            pixels,
+            pixels_send_mode,
+            under: None,
+            opened_size: None,
+        }
+    }
+
+    /// Returns a reference to its ScreenBackend
+    pub fn get_backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the position of the overlay's upper left corner
+    pub fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// Returns the pixels the overlay draws
+    pub fn pixels(&self) -> &PixelsCollection<u8> {
+        &self.pixels
+    }
+
+    /// Returns whether the overlay is drawn on the screen
+    pub fn is_shown(&self) -> bool {
+        self.under.is_some()
+    }
+
+    /// Draws the overlay over the screen's original pixels, saving them the first time
+    pub fn show(&mut self) -> Result<(), PixelCasterError> {
+        self.redraw(self.x, self.y)
+    }
+
+    /// Replaces the overlay's pixels (which can have a different size) and draws them over the screen's original pixels,
+    /// restoring those that the previous pixels covered and the new ones don't.
+    /// A ScreenBackend opened by new is opened again for the new size, if it changes
+    pub fn update(&mut self, pixels: PixelsCollection<u8>) -> Result<(), PixelCasterError> {
+        let size = (pixels.width, pixels.height);
+        if self
+            .opened_size
+            .is_some_and(|opened_size| opened_size != size)
+        {
+            self.backend = B::open(size.0 as u32, size.1 as u32, true)?;
+            self.opened_size = Some(size);
+        }
+        self.pixels = pixels;
+        self.redraw(self.x, self.y)
+    }
+
+    /// Moves the overlay's upper left corner to x, y. If it's shown, it's drawn there and the screen's pixels it uncovers are restored
+    pub fn move_to(&mut self, x: i32, y: i32) -> Result<(), PixelCasterError> {
+        if self.is_shown() {
+            return self.redraw(x, y);
+        }
+        self.x = x;
+        self.y = y;
+        Ok(())
+    }
+
+    /// Puts the screen's original pixels back in the overlay's area, it can be shown again later
+    pub fn clear(&mut self) -> Result<(), PixelCasterError> {
+        if let Some(under) = self.under.take() {
+            self.present_opaque(&under, self.x, self.y)?;
+        }
+        Ok(())
+    }
+
+    /// Draws the overlay at x, y in a single send, blending its pixels with the screen's original ones in memory,
+    /// then restores the parts of the previous area that are left uncovered
+    fn redraw(&mut self, x: i32, y: i32) -> Result<(), PixelCasterError> {
+        let (width, height) = (self.pixels.width, self.pixels.height);
+        let rect = rect_of(x, y, &self.pixels);
+        let mut under =
+            PixelsCollection::<u8>::create(width, height, <u8>::initialize_vec(width, height))?;
+        self.backend
+            .capture_rect(&mut under.bytes, x, y, width as u32, height as u32)?;
+        if let Some(previous) = &self.under {
+            // where the overlay is drawn, the screen's original pixels are the saved ones
+            copy_intersection(
+                &mut under,
+                rect,
+                previous,
+                rect_of(self.x, self.y, previous),
+            );
+        }
+
+        // blended as the backend would do it, by an in-memory screen holding the original pixels
+        let composite = VirtualScreen::from_pixels(under.clone());
+        composite.present_rect(
+            &self.pixels.bytes,
+            0,
+            0,
+            width as u32,
+            height as u32,
+            self.pixels_send_mode,
+        )?;
+        self.present_opaque(&composite.to_pixels_collection(), x, y)?;
+
+        if let Some(previous) = self.under.take() {
+            let previous_rect = rect_of(self.x, self.y, &previous);
+            for part in subtract(previous_rect, rect) {
+                let uncovered = previous
+                    .sub_view(
+                        (part.0 - previous_rect.0) as usize,
+                        (part.1 - previous_rect.1) as usize,
+                        (part.2 - part.0) as usize,
+                        (part.3 - part.1) as usize,
+                    )?
+                    .to_owned();
+                self.present_opaque(&uncovered, part.0 as i32, part.1 as i32)?;
+            }
+        }
+        self.under = Some(under);
+        self.x = x;
+        self.y = y;
+        Ok(())
+    }
+
+    fn present_opaque(
+        &self,
+        pixels: &PixelsCollection<u8>,
+        x: i32,
+        y: i32,
+    ) -> Result<(), PixelCasterError> {
+        self.backend.present_rect(
+            &pixels.bytes,
+            x,
+            y,
+            pixels.width as u32,
+            pixels.height as u32,
+            PixelsSendMode::AlphaDisabled,
+        )
+    }
+}
+
+impl<B: ScreenBackend> Drop for ScreenOverlay<B> {
+    /// Puts the screen's original pixels back, call clear before dropping it to handle the errors
+    fn drop(&mut self) {
+        let _ = self.clear();
+    }
+}
+
+fn rect_of(x: i32, y: i32, pixels: &PixelsCollection<u8>) -> Rect {
+    let (x, y) = (x as i64, y as i64);
+    (x, y, x + pixels.width as i64, y + pixels.height as i64)
+}
+
+fn intersection(a: Rect, b: Rect) -> Option<Rect> {
+    let i = (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3));
+    (i.0 < i.2 && i.1 < i.3).then_some(i)
+}
+
+/// Returns the parts of `a` outside of `b` (at most 4 rectangles)
+fn subtract(a: Rect, b: Rect) -> Vec<Rect> {
+    let Some(i) = intersection(a, b) else {
+        return vec![a];
+    };
+    [
+        (a.0, a.1, a.2, i.1),
+        (a.0, i.3, a.2, a.3),
+        (a.0, i.1, i.0, i.3),
+        (i.2, i.1, a.2, i.3),
+    ]
+    .into_iter()
+    .filter(|r| r.0 < r.2 && r.1 < r.3)
+    .collect()
+}
+
+/// Copies the pixels of `src` (placed at src_rect) onto those of `dst` (placed at dst_rect) where the two rectangles intersect
+fn copy_intersection(
+    dst: &mut PixelsCollection<u8>,
+    dst_rect: Rect,
+    src: &PixelsCollection<u8>,
+    src_rect: Rect,
+) {
+    let Some(i) = intersection(dst_rect, src_rect) else {
+        return;
+    };
+    let (width, height) = ((i.2 - i.0) as usize, (i.3 - i.1) as usize);
+    // both inside their pixels, as they're inside the intersection
+    let src = src
+        .sub_view(
+            (i.0 - src_rect.0) as usize,
+            (i.1 - src_rect.1) as usize,
+            width,
+            height,
+        )
+        .unwrap();
+    let mut dst = dst
+        .sub_view_mut(
+            (i.0 - dst_rect.0) as usize,
+            (i.1 - dst_rect.1) as usize,
+            width,
+            height,
+        )
+        .unwrap();
+    for (src_row, dst_row) in src.rows().zip(dst.rows_mut()) {
+        dst_row.copy_from_slice(src_row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 6x4 screen whose pixels all differ
+    fn screen() -> VirtualScreen {
+        VirtualScreen::from_pixels(
+            PixelsCollection::<u8>::create(
+                6,
+                4,
+                (0..24u8).flat_map(|i| [i, 10, 20, 255]).collect(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn filled(width: usize, height: usize, bgra: [u8; 4]) -> PixelsCollection<u8> {
+        PixelsCollection::<u8>::create(width, height, bgra.repeat(width * height)).unwrap()
+    }
+
+    #[test]
+    fn show_update_clear() {
+        let virtual_screen = screen();
+        let original = virtual_screen.to_pixels_collection().bytes;
+        let mut overlay = ScreenOverlay::with_backend(
+            virtual_screen.clone(),
+            filled(2, 2, [0, 0, 200, 128]),
+            1,
+            1,
+            PixelsSendMode::AlphaEnabled,
+        );
+        assert_eq!(virtual_screen.get_pixel(1, 1), Some([7, 10, 20, 255]));
+        overlay.show().unwrap();
+        let blended = virtual_screen.get_pixel(1, 1).unwrap();
+        assert_eq!(blended[2], 110);
+
+        // redrawn over the original pixels, not over the previous drawing
+        overlay.show().unwrap();
+        assert_eq!(virtual_screen.get_pixel(1, 1), Some(blended));
+        overlay.update(filled(1, 1, [0, 255, 0, 255])).unwrap();
+        assert_eq!(virtual_screen.get_pixel(1, 1), Some([0, 255, 0, 255]));
+        assert_eq!(virtual_screen.get_pixel(2, 2), Some([14, 10, 20, 255]));
+
+        overlay.clear().unwrap();
+        assert!(!overlay.is_shown());
+        assert_eq!(virtual_screen.to_pixels_collection().bytes, original);
+
+        overlay.show().unwrap();
+        assert_ne!(virtual_screen.to_pixels_collection().bytes, original);
+        drop(overlay);
+        assert_eq!(virtual_screen.to_pixels_collection().bytes, original);
+    }
+
+    #[test]
+    fn moving() {
+        let virtual_screen = screen();
+        let original = virtual_screen.to_pixels_collection();
+        let mut overlay = ScreenOverlay::with_backend(
+            virtual_screen.clone(),
+            filled(3, 2, [1, 2, 3, 255]),
+            0,
+            0,
+            PixelsSendMode::AlphaEnabled,
+        );
+        // not shown, only the position changes
+        overlay.move_to(1, 0).unwrap();
+        assert_eq!(virtual_screen.to_pixels_collection().bytes, original.bytes);
+        overlay.show().unwrap();
+
+        overlay.move_to(2, 1).unwrap();
+        assert_eq!(overlay.position(), (2, 1));
+        let current = virtual_screen.to_pixels_collection();
+        for y in 0..4 {
+            for x in 0..6 {
+                let expected = if (2..5).contains(&x) && (1..3).contains(&y) {
+                    [1, 2, 3, 255]
+                } else {
+                    original.view().get(x, y).unwrap().try_into().unwrap()
+                };
+                assert_eq!(current.view().get(x, y).unwrap(), expected, "{x}, {y}");
+            }
+        }
+
+        // partly outside of the screen
+        overlay.move_to(4, -1).unwrap();
+        assert_eq!(virtual_screen.get_pixel(5, 0), Some([1, 2, 3, 255]));
+        assert_eq!(virtual_screen.get_pixel(3, 1), Some([9, 10, 20, 255]));
+        overlay.clear().unwrap();
+        assert_eq!(virtual_screen.to_pixels_collection().bytes, original.bytes);
+    }
+
+    /// Captures only areas up to the size it was opened for, as the GDI one does with its bitmap
+    struct FixedSizeBackend {
+        screen: VirtualScreen,
+        width: u32,
+        height: u32,
+    }
+
+    impl ScreenBackend for FixedSizeBackend {
+        fn open(width: u32, height: u32, _is_static: bool) -> Result<Self, PixelCasterError> {
+            Ok(FixedSizeBackend {
+                screen: VirtualScreen::thread_default(),
+                width,
+                height,
+            })
+        }
+
+        fn capture_rect(
+            &self,
+            dst: &mut [u8],
+            src_ul_x: i32,
+            src_ul_y: i32,
+            area_width: u32,
+            area_height: u32,
+        ) -> Result<(), PixelCasterError> {
+            if area_width > self.width || area_height > self.height {
+                return Err(PixelCasterError::backend("area larger than the opened one"));
+            }
+            self.screen
+                .capture_rect(dst, src_ul_x, src_ul_y, area_width, area_height)
+        }
+
+        fn present_rect(
+            &self,
+            src: &[u8],
+            dst_ul_x: i32,
+            dst_ul_y: i32,
+            area_width: u32,
+            area_height: u32,
+            pixels_send_mode: PixelsSendMode,
+        ) -> Result<(), PixelCasterError> {
+            self.screen.present_rect(
+                src,
+                dst_ul_x,
+                dst_ul_y,
+                area_width,
+                area_height,
+                pixels_send_mode,
+            )
+        }
+
+        fn present_premultiplied_rect(
+            &self,
+            src: &[u8],
+            dst_ul_x: i32,
+            dst_ul_y: i32,
+            area_width: u32,
+            area_height: u32,
+            source_constant_alpha: u8,
+        ) -> Result<(), PixelCasterError> {
+            self.screen.present_premultiplied_rect(
+                src,
+                dst_ul_x,
+                dst_ul_y,
+                area_width,
+                area_height,
+                source_constant_alpha,
+            )
+        }
+
+        fn copy_rect(
+            &self,
+            src_ul_x: i32,
+            src_ul_y: i32,
+            area_width: u32,
+            area_height: u32,
+            dst_ul_x: i32,
+            dst_ul_y: i32,
+        ) -> Result<(), PixelCasterError> {
+            self.screen.copy_rect(
+                src_ul_x,
+                src_ul_y,
+                area_width,
+                area_height,
+                dst_ul_x,
+                dst_ul_y,
+            )
+        }
+
+        fn screen_bounds(&self) -> crate::ScreenArea {
+            self.screen.screen_bounds()
+        }
+    }
+
+    #[test]
+    fn growing_reopens_backend() {
+        VirtualScreen::reset_thread_default(6, 4);
+        let virtual_screen = VirtualScreen::thread_default();
+        virtual_screen
+            .framebuffer()
+            .bytes
+            .copy_from_slice(&screen().to_pixels_collection().bytes);
+        let original = virtual_screen.to_pixels_collection().bytes;
+        let mut overlay = ScreenOverlay::<FixedSizeBackend>::new(
+            filled(1, 1, [0, 0, 255, 255]),
+            1,
+            1,
+            PixelsSendMode::AlphaEnabled,
+        )
+        .unwrap();
+        overlay.show().unwrap();
+        overlay.update(filled(3, 2, [0, 255, 0, 255])).unwrap();
+        assert_eq!(overlay.get_backend().width, 3);
+        assert_eq!(virtual_screen.get_pixel(3, 2), Some([0, 255, 0, 255]));
+        overlay.update(filled(1, 1, [255, 0, 0, 255])).unwrap();
+        assert_eq!(virtual_screen.get_pixel(3, 2), Some([15, 10, 20, 255]));
+
+        drop(overlay);
+
+        // a backend that was provided is kept as it is
+        let mut overlay = ScreenOverlay::with_backend(
+            FixedSizeBackend::open(1, 1, true).unwrap(),
+            filled(1, 1, [0, 0, 255, 255]),
+            0,
+            0,
+            PixelsSendMode::AlphaEnabled,
+        );
+        assert!(overlay.update(filled(2, 1, [0, 0, 255, 255])).is_err());
+        overlay.clear().unwrap();
+        assert_eq!(virtual_screen.to_pixels_collection().bytes, original);
+    }
+}
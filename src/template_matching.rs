@@ -0,0 +1,301 @@
+use crate::{bgra_management::BgraMatcher, PixelCasterError, PixelsCollection, PixelsView};
+
+/// How the pixels of the needle are compared with those of each position of the haystack by find_template
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MatchMethod {
+    /// Every compared Blue Green Red must be equal, the score of the matches is always 1
+    Exact,
+    /// Every compared Blue Green Red can differ by up to the given value,
+    /// the score is 1 - the average difference / 255 (1 : identical)
+    Tolerance(u8),
+    /// Normalized cross-correlation of the compared Blue Green Red values, which doesn't change with the brightness or the contrast.
+    /// The positions scoring at least the given value (-1 : inverted, 0 : unrelated, 1 : identical) are matches.
+    /// Uniform areas only match uniform needles (score 1)
+    NormalizedCrossCorrelation(f32),
+}
+
+/// Options of find_template
+#[derive(Clone, Copy, Debug)]
+pub struct TemplateMatchOptions {
+    pub method: MatchMethod,
+    /// Only the needle's pixels passing it are compared (e.g.: bgra_management::bytes_matchers::visible ignores those whose Alpha is 0), None : all of them
+    pub needle_bgra_matcher: Option<BgraMatcher>,
+    /// Maximum number of matches to return, the best scoring ones. None : all of them
+    pub max_results: Option<usize>,
+}
+
+impl Default for TemplateMatchOptions {
+    /// Exact matches of all the needle's pixels, all of them
+    fn default() -> TemplateMatchOptions {
+        TemplateMatchOptions {
+            method: MatchMethod::Exact,
+            needle_bgra_matcher: None,
+            max_results: None,
+        }
+    }
+}
+
+/// Position (upper left corner) in the haystack of a match and its score
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TemplateMatch {
+    pub x: usize,
+    pub y: usize,
+    pub score: f32,
+}
+
+impl PixelsCollection<u8> {
+    /// Returns the positions where the needle is found in these pixels, the best scoring first (the first from the top left when they score the same).
+    /// The Alpha is not compared, as the screen captures are always fully opaque, use the needle_bgra_matcher to ignore the needle's invisible pixels.
+    /// Returns a NoMatchingPixels error if none of the needle's pixels are compared
+    pub fn find_template(
+        &self,
+        needle: &PixelsCollection<u8>,
+        opts: TemplateMatchOptions,
+    ) -> Result<Vec<TemplateMatch>, PixelCasterError> {
+        self.view().find_template(needle, opts)
+    }
+}
+
+impl PixelsView<'_, u8> {
+    /// As PixelsCollection::find_template, searching only inside this view.
+    /// The positions are relative to the whole PixelsCollection
+    pub fn find_template(
+        &self,
+        needle: &PixelsCollection<u8>,
+        opts: TemplateMatchOptions,
+    ) -> Result<Vec<TemplateMatch>, PixelCasterError> {
+        // offsets and Blue Green Red of the compared needle pixels
+        let compared: Vec<(usize, usize, &[u8])> = needle
+            .bytes
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, bgra)| {
+                opts.needle_bgra_matcher
+                    .is_none_or(|matcher| matcher(bgra[0], bgra[1], bgra[2], bgra[3]))
+            })
+            .map(|(i, bgra)| (i % needle.width, i / needle.width, &bgra[..3]))
+            .collect();
+        if compared.is_empty() {
+            return Err(PixelCasterError::NoMatchingPixels);
+        }
+        let mut matches = Vec::new();
+        if needle.width > self.width() || needle.height > self.height() {
+            return Ok(matches);
+        }
+
+        let rows: Vec<&[u8]> = self.rows().collect();
+        let haystack_bgr = |x: usize, y: usize, dx: usize, dy: usize| {
+            let i = (x + dx) * 4;
+            &rows[y + dy][i..i + 3]
+        };
+        let needle_stats = ChannelStats::of(compared.iter().map(|(_, _, bgr)| *bgr));
+
+        for y in 0..=self.height() - needle.height {
+            for x in 0..=self.width() - needle.width {
+                let score = match opts.method {
+                    MatchMethod::Exact => compared
+                        .iter()
+                        .all(|(dx, dy, bgr)| haystack_bgr(x, y, *dx, *dy) == *bgr)
+                        .then_some(1.0),
+                    MatchMethod::Tolerance(tolerance) => {
+                        let mut difference = 0u64;
+                        compared
+                            .iter()
+                            .all(|(dx, dy, bgr)| {
+                                haystack_bgr(x, y, *dx, *dy)
+                                    .iter()
+                                    .zip(bgr.iter())
+                                    .all(|(h, n)| {
+                                        let d = h.abs_diff(*n);
+                                        difference += d as u64;
+                                        d <= tolerance
+                                    })
+                            })
+                            .then(|| 1.0 - difference as f32 / (compared.len() * 3 * 255) as f32)
+                    }
+                    MatchMethod::NormalizedCrossCorrelation(min_score) => {
+                        let haystack_stats = ChannelStats::of(
+                            compared
+                                .iter()
+                                .map(|(dx, dy, _)| haystack_bgr(x, y, *dx, *dy)),
+                        );
+                        let mut covariance = 0.0;
+                        for (dx, dy, bgr) in &compared {
+                            for (h, n) in haystack_bgr(x, y, *dx, *dy).iter().zip(bgr.iter()) {
+                                covariance += (*h as f32 - haystack_stats.mean)
+                                    * (*n as f32 - needle_stats.mean);
+                            }
+                        }
+                        let score = match (needle_stats.deviation, haystack_stats.deviation) {
+                            (n, h) if n == 0.0 && h == 0.0 => 1.0,
+                            (n, h) if n == 0.0 || h == 0.0 => 0.0,
+                            (n, h) => (covariance / (n * h)).clamp(-1.0, 1.0),
+                        };
+                        (score >= min_score).then_some(score)
+                    }
+                };
+                if let Some(score) = score {
+                    matches.push(TemplateMatch {
+                        x: self.x() + x,
+                        y: self.y() + y,
+                        score,
+                    });
+                    // the exact matches all score the same, the search can stop at the first ones
+                    if opts.method == MatchMethod::Exact && Some(matches.len()) == opts.max_results
+                    {
+                        return Ok(matches);
+                    }
+                }
+            }
+        }
+
+        // stable, the matches scoring the same stay ordered from the top left
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        if let Some(max_results) = opts.max_results {
+            matches.truncate(max_results);
+        }
+        Ok(matches)
+    }
+}
+
+/// Mean and square root of the sum of the squared differences from the mean of some channels' values
+struct ChannelStats {
+    mean: f32,
+    deviation: f32,
+}
+
+impl ChannelStats {
+    fn of<'a>(bgrs: impl Iterator<Item = &'a [u8]> + Clone) -> ChannelStats {
+        let (sum, count) = bgrs
+            .clone()
+            .flatten()
+            .fold((0.0, 0usize), |(sum, count), v| {
+                (sum + *v as f32, count + 1)
+            });
+        let mean = sum / count as f32;
+        let deviation = bgrs
+            .flatten()
+            .map(|v| (*v as f32 - mean).powi(2))
+            .sum::<f32>()
+            .sqrt();
+        ChannelStats { mean, deviation }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bgra_management::bytes_matchers;
+
+    /// 4x3 haystack of greys whose value is 10 * their index, with the needle copied at 2, 1
+    fn haystack_and_needle() -> (PixelsCollection<u8>, PixelsCollection<u8>) {
+        let haystack = PixelsCollection::<u8>::create(
+            4,
+            3,
+            (0..12u8)
+                .flat_map(|i| [i * 10, i * 10, i * 10, 255])
+                .collect(),
+        )
+        .unwrap();
+        let needle = haystack.sub_view(2, 1, 2, 2).unwrap().to_owned();
+        (haystack, needle)
+    }
+
+    fn positions(matches: &[TemplateMatch]) -> Vec<(usize, usize)> {
+        matches.iter().map(|m| (m.x, m.y)).collect()
+    }
+
+    #[test]
+    fn exact_and_tolerance() {
+        let (haystack, mut needle) = haystack_and_needle();
+        let found = haystack
+            .find_template(&needle, TemplateMatchOptions::default())
+            .unwrap();
+        assert_eq!(
+            found,
+            [TemplateMatch {
+                x: 2,
+                y: 1,
+                score: 1.0
+            }]
+        );
+
+        needle.bytes[0] += 3;
+        let found = haystack
+            .find_template(&needle, TemplateMatchOptions::default())
+            .unwrap();
+        assert!(found.is_empty());
+        let tolerance = TemplateMatchOptions {
+            method: MatchMethod::Tolerance(13),
+            ..Default::default()
+        };
+        let found = haystack.find_template(&needle, tolerance).unwrap();
+        // only the needle's Blue of the first pixel differs, by 3, from the 2, 1 position, at 1, 1 they all differ by 10 or 13
+        assert_eq!(positions(&found), [(2, 1), (1, 1)]);
+        assert!((found[0].score - (1.0 - 3.0 / (4.0 * 3.0 * 255.0))).abs() < 1e-6);
+
+        // the invisible needle pixels are ignored
+        needle.bytes[3] = 0;
+        let visible_only = TemplateMatchOptions {
+            needle_bgra_matcher: Some(bytes_matchers::visible),
+            ..Default::default()
+        };
+        let found = haystack.find_template(&needle, visible_only).unwrap();
+        assert_eq!(positions(&found), [(2, 1)]);
+        needle
+            .bytes
+            .chunks_exact_mut(4)
+            .for_each(|bgra| bgra[3] = 0);
+        assert!(matches!(
+            haystack.find_template(&needle, visible_only),
+            Err(PixelCasterError::NoMatchingPixels)
+        ));
+    }
+
+    #[test]
+    fn cross_correlation_and_limits() {
+        let (haystack, needle) = haystack_and_needle();
+        // the haystack is a gradient, every position correlates perfectly with a brighter copy of the needle
+        let mut brighter = needle.clone();
+        brighter
+            .bytes
+            .iter_mut()
+            .for_each(|v| *v = v.saturating_add(40));
+        let ncc = TemplateMatchOptions {
+            method: MatchMethod::NormalizedCrossCorrelation(0.99),
+            ..Default::default()
+        };
+        let found = haystack.find_template(&brighter, ncc).unwrap();
+        assert_eq!(found.len(), 6);
+        assert!(found.iter().all(|m| (m.score - 1.0).abs() < 1e-4));
+
+        let mut inverted = needle.clone();
+        inverted
+            .bytes
+            .chunks_exact_mut(4)
+            .for_each(|bgra| bgra[..3].iter_mut().for_each(|v| *v = 255 - *v));
+        let found = haystack.find_template(&inverted, ncc).unwrap();
+        assert!(found.is_empty());
+
+        let limited = TemplateMatchOptions {
+            max_results: Some(2),
+            ..ncc
+        };
+        assert_eq!(haystack.find_template(&brighter, limited).unwrap().len(), 2);
+
+        // searching inside a view, bigger needles are never found
+        let view = haystack.sub_view(1, 0, 3, 3).unwrap();
+        assert_eq!(
+            positions(
+                &view
+                    .find_template(&needle, TemplateMatchOptions::default())
+                    .unwrap()
+            ),
+            [(2, 1)]
+        );
+        assert!(needle
+            .find_template(&haystack, TemplateMatchOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+}
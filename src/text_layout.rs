@@ -0,0 +1,391 @@
+use std::ops::Range;
+
+use crate::{
+    pixels_string::{CharsCollection, LineLayout, PixelsString, BGRA},
+    PixelsCollection,
+};
+
+/// Where the lines go horizontally, in the width left by the padding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    /// Widens the spaces of each line to make it fill the width, except for the last line of each paragraph (which is aligned to the left)
+    Justify,
+}
+
+/// Builder of a multi-line PixelsString, made by CharsCollection::text_layout
+#[derive(Clone)]
+pub struct TextLayout<'a> {
+    chars: &'a CharsCollection<u8>,
+    text: &'a str,
+    char_spacing: isize,
+    max_width: Option<usize>,
+    align: TextAlign,
+    line_spacing: isize,
+    tab_width: Option<usize>,
+    // top, right, bottom, left
+    padding: [usize; 4],
+    background: Option<BGRA<u8>>,
+}
+
+/// Where a char of the text was placed in a TextBlock's pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphRect {
+    pub char: char,
+    /// Index of the char in the text (counting chars, not bytes)
+    pub index: usize,
+    pub line: usize,
+    /// Upper left corner and size of the area from the char's origin to the next one's, as tall as the line
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Multi-line text made by a TextLayout, with the rectangles of its chars
+#[derive(Clone)]
+pub struct TextBlock {
+    pub string: PixelsString,
+    /// The chars of the text in order, without the line breaks ('\n', '\r\n' or '\r') and the spaces where the lines were wrapped
+    pub glyphs: Vec<GlyphRect>,
+}
+
+impl TextBlock {
+    /// Returns the char placed at the given coordinate of the pixels, if any
+    pub fn glyph_at(&self, x: usize, y: usize) -> Option<&GlyphRect> {
+        self.glyphs
+            .iter()
+            .find(|g| x >= g.x && x < g.x + g.width && y >= g.y && y < g.y + g.height)
+    }
+}
+
+impl CharsCollection<u8> {
+    /// Returns a TextLayout to turn the text (with its '\n', '\r\n' or '\r' line breaks and '\t') into lines of chars of this collection
+    pub fn text_layout<'a>(&'a self, text: &'a str) -> TextLayout<'a> {
+        TextLayout {
+            chars: self,
+            text,
+            char_spacing: 0,
+            max_width: None,
+            align: TextAlign::Left,
+            line_spacing: 0,
+            tab_width: None,
+            padding: [0; 4],
+            background: None,
+        }
+    }
+}
+
+impl<'a> TextLayout<'a> {
+    /// Pixels added to (or removed from) each char's advance, as create_pixels_string's one. Default: 0
+    pub fn char_spacing(mut self, char_spacing: isize) -> Self {
+        self.char_spacing = char_spacing;
+        self
+    }
+
+    /// Wraps the lines at the spaces (or between chars, for words that are too long) to keep the pixels at most this wide, padding included.
+    /// The pixels are then this wide. Default: no wrapping, as wide as the longest line
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Default: TextAlign::Left
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Pixels added to (or removed from) the distance between a line's top and the next one's, which is the chars' tallest line height. Default: 0
+    pub fn line_spacing(mut self, line_spacing: isize) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    /// Distance between the tab stops a '\t' moves the next char to, from the start of the line. Default: 4 times the width of a space
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = Some(tab_width);
+        self
+    }
+
+    /// Empty pixels around the text. Default: 0
+    pub fn padding(mut self, top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        self.padding = [top, right, bottom, left];
+        self
+    }
+
+    /// Color of the pixels under the text. Default: fully transparent
+    pub fn background(mut self, bgra: BGRA<u8>) -> Self {
+        self.background = Some(bgra);
+        self
+    }
+
+    /// Lays the text out and draws it
+    pub fn build(&self) -> TextBlock {
+        let [top, right, bottom, left] = self.padding;
+        let text: Vec<char> = self.text.chars().collect();
+        let lines: Vec<(Range<usize>, bool, LineLayout)> = self
+            .wrap(&text)
+            .into_iter()
+            .map(|(range, paragraph_end)| {
+                let line = self.layout(&text[range.clone()]);
+                (range, paragraph_end, line)
+            })
+            .collect();
+
+        let widest_line = lines.iter().map(|(_, _, l)| l.width).max().unwrap_or(0);
+        let width = self.max_width.unwrap_or(0).max(widest_line + left + right);
+        let content_width = width - left - right;
+        let line_height = lines.first().map_or(1, |(_, _, l)| l.height);
+        let line_step = (line_height as isize + self.line_spacing).max(1) as usize;
+        let height = top + bottom + line_step * (lines.len().max(1) - 1) + line_height;
+
+        let background = self.background.map_or([0; 4], |b| [b.0, b.1, b.2, b.3]);
+        let mut pixels =
+            PixelsCollection::<u8>::create(width, height, background.repeat(width * height))
+                .unwrap();
+        let mut glyphs = Vec::new();
+        for (i, (range, paragraph_end, mut line)) in lines.into_iter().enumerate() {
+            let extra = content_width - line.width;
+            let offset = match self.align {
+                TextAlign::Left => 0,
+                TextAlign::Center => extra / 2,
+                TextAlign::Right => extra,
+                TextAlign::Justify => {
+                    if !paragraph_end {
+                        justify(&mut line, extra);
+                    }
+                    0
+                }
+            };
+            let (line_x, line_y) = (left + offset, top + i * line_step);
+            self.chars
+                .draw_line(&line, &mut pixels, line_x as i64, line_y as i64);
+            glyphs.extend(
+                line.glyphs
+                    .iter()
+                    .zip(range)
+                    .map(|(glyph, index)| GlyphRect {
+                        char: glyph.text_char,
+                        index,
+                        line: i,
+                        x: line_x + glyph.pen_x as usize,
+                        y: line_y,
+                        width: glyph.advance.max(0) as usize,
+                        height: line_height,
+                    }),
+            );
+        }
+
+        TextBlock {
+            string: PixelsString {
+                bgra: self.chars.bgra,
+                pixels,
+            },
+            glyphs,
+        }
+    }
+
+    fn layout(&self, chars: &[char]) -> LineLayout<'a> {
+        let tab_width = self.tab_width.unwrap_or_else(|| {
+            let space = self.chars.chars.iter().find(|c| c.char == ' ');
+            4 * space.map_or(1, |c| c.metrics.advance.max(1) as usize)
+        });
+        self.chars.layout_line(
+            &chars.iter().collect::<String>(),
+            self.char_spacing,
+            Some(tab_width),
+        )
+    }
+
+    fn fits(&self, chars: &[char]) -> bool {
+        let [_, right, _, left] = self.padding;
+        self.max_width
+            .is_none_or(|max_width| self.layout(chars).width + left + right <= max_width)
+    }
+
+    /// Splits the text into lines (ranges of its chars) at its line breaks and, with a max_width, where they get too wide, telling if they end a paragraph.
+    /// The spaces where the lines are wrapped are left out
+    fn wrap(&self, text: &[char]) -> Vec<(Range<usize>, bool)> {
+        let mut lines = Vec::new();
+        let mut paragraph_start = 0;
+        while paragraph_start <= text.len() {
+            let paragraph_end = text[paragraph_start..]
+                .iter()
+                .position(|c| *c == '\n' || *c == '\r')
+                .map_or(text.len(), |i| paragraph_start + i);
+            let mut start = paragraph_start;
+            loop {
+                // the end of the longest part that fits (ignoring its trailing spaces), and the end of its last space
+                let (mut end, mut last_space) = (start, None);
+                while end < paragraph_end && self.fits(trim_end(&text[start..=end])) {
+                    end += 1;
+                    if text[end - 1] == ' ' {
+                        last_space = Some(end);
+                    }
+                }
+                if end == paragraph_end {
+                    lines.push((start..start + trim_end(&text[start..end]).len(), true));
+                    break;
+                }
+                // the next char doesn't fit: wraps at the last space, or else before that char (keeping at least one per line)
+                if text[end] != ' ' {
+                    end = last_space.unwrap_or(end.max(start + 1));
+                }
+                lines.push((start..start + trim_end(&text[start..end]).len(), false));
+                start = end;
+                while start < paragraph_end && text[start] == ' ' {
+                    start += 1;
+                }
+                if start == paragraph_end {
+                    break;
+                }
+            }
+            // skips the line break, a "\r\n" being a single one
+            paragraph_start = paragraph_end + 1;
+            if text[paragraph_end..].starts_with(&['\r', '\n']) {
+                paragraph_start += 1;
+            }
+        }
+        lines
+    }
+}
+
+fn trim_end(chars: &[char]) -> &[char] {
+    let end = chars.iter().rposition(|c| *c != ' ').map_or(0, |i| i + 1);
+    &chars[..end]
+}
+
+/// Spreads the extra pixels among the line's spaces, moving the chars after each of them
+fn justify(line: &mut LineLayout, extra: usize) {
+    let spaces = line.glyphs.iter().filter(|g| g.text_char == ' ').count();
+    if spaces == 0 {
+        return;
+    }
+    let mut spaces_before = 0;
+    for glyph in &mut line.glyphs {
+        let shift = (extra * spaces_before / spaces) as i64;
+        glyph.pen_x += shift;
+        glyph.x += shift;
+        if glyph.text_char == ' ' {
+            spaces_before += 1;
+            glyph.advance += (extra * spaces_before / spaces) as i64 - shift;
+        }
+    }
+    line.width += extra;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::pixels_string::PixelsChar;
+
+    const RED: [u8; 4] = [0, 0, 255, 255];
+
+    // 2 pixels wide chars, 1 pixel wide space, all 3 pixels tall
+    fn chars() -> CharsCollection<u8> {
+        let char = |c: char, width: usize, bgra: [u8; 4]| {
+            PixelsChar::create(c, &c.to_string(), width, 3, bgra.repeat(width * 3)).unwrap()
+        };
+        CharsCollection {
+            chars: "abcdefgh"
+                .chars()
+                .map(|c| char(c, 2, RED))
+                .chain([char(' ', 1, [0; 4])])
+                .collect(),
+            path: String::new(),
+            bgra: BGRA(0, 0, 255, 255),
+            kerning: HashMap::new(),
+        }
+    }
+
+    fn line_chars(block: &TextBlock, line: usize) -> String {
+        block
+            .glyphs
+            .iter()
+            .filter(|g| g.line == line)
+            .map(|g| g.char)
+            .collect()
+    }
+
+    #[test]
+    fn wrapping_and_alignment() {
+        let chars = chars();
+        // "ab cd" is 9 pixels wide, "ab cd ef" 16
+        let block = chars
+            .text_layout("ab cd ef\ngh  abcdefgh")
+            .max_width(11)
+            .padding(1, 1, 1, 1)
+            .line_spacing(1)
+            .build();
+        assert_eq!(line_chars(&block, 0), "ab cd");
+        assert_eq!(line_chars(&block, 1), "ef");
+        assert_eq!(line_chars(&block, 2), "gh");
+        // too long for a line, split between its chars
+        assert_eq!(line_chars(&block, 3), "abcd");
+        assert_eq!(line_chars(&block, 4), "efgh");
+        let pixels = &block.string.pixels;
+        assert_eq!((pixels.width, pixels.height), (11, 1 + 5 * 4 - 1 + 1));
+
+        // indexes skip the '\n' and the spaces where the lines were wrapped
+        let e = block.glyph_at(2, 5).unwrap();
+        assert_eq!((e.char, e.index, e.line, e.x, e.y), ('e', 6, 1, 1, 5));
+        assert!(block.glyph_at(0, 0).is_none());
+
+        let right = chars.text_layout("ab\nabc").align(TextAlign::Right).build();
+        assert_eq!(right.string.pixels.width, 6);
+        assert_eq!((right.glyphs[0].x, right.glyphs[2].x), (2, 0));
+        let center = chars
+            .text_layout("ab\nabc")
+            .align(TextAlign::Center)
+            .build();
+        assert_eq!(center.glyphs[0].x, 1);
+    }
+
+    #[test]
+    fn justify_tabs_and_background() {
+        let chars = chars();
+        let block = chars
+            .text_layout("a b c d")
+            .max_width(10)
+            .align(TextAlign::Justify)
+            .build();
+        // "a b c" is 8 pixels wide, its 2 spaces get 1 more pixel each
+        let x: Vec<usize> = block.glyphs.iter().map(|g| g.x).collect();
+        assert_eq!(x, [0, 2, 4, 6, 8, 0]);
+        // the last line of the paragraph stays on the left
+        assert_eq!((block.glyphs[5].char, block.glyphs[5].line), ('d', 1));
+
+        let block = chars
+            .text_layout("a\tb\n\tc")
+            .tab_width(5)
+            .background(BGRA(255, 0, 0, 255))
+            .build();
+        assert_eq!(block.glyphs[2].x, 5);
+        assert_eq!(block.glyphs[4].x, 5);
+        let pixels = &block.string.pixels;
+        assert_eq!((pixels.width, pixels.height), (7, 6));
+        // the text over the background, which fills the rest
+        assert_eq!(pixels.bytes[..4], RED);
+        assert_eq!(pixels.bytes[8..12], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn line_breaks() {
+        let chars = chars();
+        let block = chars.text_layout("ab\r\ncd\ref\n\r\ngh\r").build();
+        let lines: Vec<String> = (0..5).map(|line| line_chars(&block, line)).collect();
+        assert_eq!(lines, ["ab", "cd", "ef", "", "gh"]);
+        assert_eq!(block.string.pixels.height, 6 * 3);
+        // no '\r' drawn or counted as a char, the indexes skip the whole break
+        assert!(block.glyphs.iter().all(|g| g.char != '\r'));
+        let g = block.glyphs.iter().find(|g| g.char == 'g').unwrap();
+        assert_eq!((g.index, g.line), (12, 4));
+        assert_eq!(block.string.pixels.bytes[3], 255);
+        assert_eq!(block.string.pixels.width, 4);
+    }
+}
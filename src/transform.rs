@@ -0,0 +1,358 @@
+use crate::{PixelValues, PixelsCollection};
+
+/// Size of the canvas of the pixels returned by PixelsCollection::create_new_rotated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationCanvas {
+    /// The canvas grows to fit all of the rotated pixels, the uncovered corners are transparent
+    Expanded,
+    /// The canvas keeps the original size, the rotated pixels ending up outside of it are cropped away
+    Cropped,
+}
+
+/// 2D affine transform mapping the (x, y) coordinates of a source to x' = a * x + c * y + e, y' = b * x + d * y + f.
+/// The y axis points down, as on the screen, so a positive rotation is clockwise
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AffineTransform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl AffineTransform {
+    /// Leaves the coordinates as they are
+    pub const IDENTITY: AffineTransform = AffineTransform {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    /// Moves the coordinates by tx, ty
+    pub fn translation(tx: f32, ty: f32) -> AffineTransform {
+        AffineTransform {
+            e: tx,
+            f: ty,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Scales the coordinates from the origin
+    pub fn scale(sx: f32, sy: f32) -> AffineTransform {
+        AffineTransform {
+            a: sx,
+            d: sy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotates the coordinates clockwise around the origin
+    pub fn rotation(degrees: f32) -> AffineTransform {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        AffineTransform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Skews the coordinates, x by the tangent of the x angle times y, y by the tangent of the y angle times x
+    pub fn skew(x_degrees: f32, y_degrees: f32) -> AffineTransform {
+        AffineTransform {
+            b: y_degrees.to_radians().tan(),
+            c: x_degrees.to_radians().tan(),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Returns the transform applying this one first and then the `next` one
+    pub fn then(&self, next: &AffineTransform) -> AffineTransform {
+        AffineTransform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    /// Returns the transform reverting this one, None if it has none (e.g.: it scales by 0)
+    pub fn inverse(&self) -> Option<AffineTransform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+        Some(AffineTransform {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    /// Returns the transformed coordinates
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+}
+
+impl<T: PixelValues<T> + Copy> PixelsCollection<T> {
+    /// Mirrors the pixels left to right
+    pub fn flip_horizontal(&mut self) {
+        let units = self.units_per_pixel as usize;
+        if self.width == 0 {
+            return;
+        }
+        for row in self.bytes.chunks_exact_mut(self.width * units) {
+            for x in 0..self.width / 2 {
+                for u in 0..units {
+                    row.swap(x * units + u, (self.width - 1 - x) * units + u);
+                }
+            }
+        }
+    }
+
+    /// Mirrors the pixels top to bottom
+    pub fn flip_vertical(&mut self) {
+        let row_length = self.width * self.units_per_pixel as usize;
+        for y in 0..self.height / 2 {
+            let (top, bottom) = self.bytes.split_at_mut((self.height - 1 - y) * row_length);
+            top[y * row_length..(y + 1) * row_length].swap_with_slice(&mut bottom[..row_length]);
+        }
+    }
+
+    /// Swaps rows and columns (mirrors the pixels along the diagonal from the upper left corner), width and height are swapped
+    pub fn transpose(&mut self) {
+        let units = self.units_per_pixel as usize;
+        let mut transposed = Vec::with_capacity(self.bytes.len());
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let i = (y * self.width + x) * units;
+                transposed.extend_from_slice(&self.bytes[i..i + units]);
+            }
+        }
+        self.bytes = transposed;
+        self.set_size(self.height, self.width);
+    }
+
+    /// Rotates the pixels by 90 degrees clockwise, width and height are swapped
+    pub fn rotate_90(&mut self) {
+        self.transpose();
+        self.flip_horizontal();
+    }
+
+    /// Rotates the pixels by 180 degrees
+    pub fn rotate_180(&mut self) {
+        self.flip_horizontal();
+        self.flip_vertical();
+    }
+
+    /// Rotates the pixels by 270 degrees clockwise (90 counterclockwise), width and height are swapped
+    pub fn rotate_270(&mut self) {
+        self.transpose();
+        self.flip_vertical();
+    }
+
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.row_length = width * self.units_per_pixel as usize;
+    }
+}
+
+impl PixelsCollection<u8> {
+    /// Returns a new PixelsCollection with the pixels rotated clockwise by the given angle around their center, sampled bilinearly.
+    /// The RotationCanvas defines whether the new pixels are big enough to contain all of the rotated ones or keep the original size
+    pub fn create_new_rotated(&self, degrees: f32, canvas: RotationCanvas) -> PixelsCollection<u8> {
+        let rotation = AffineTransform::rotation(degrees);
+        let (width, height) = match canvas {
+            RotationCanvas::Cropped => (self.width, self.height),
+            RotationCanvas::Expanded => {
+                let (w, h) = (self.width as f32, self.height as f32);
+                // the tolerance keeps multiples of 90 degrees from adding a row/column because of the sin/cos imprecision
+                let fit = |v: f32| ((v - 1e-3).ceil().max(1.0)) as usize;
+                (
+                    fit(w * rotation.a.abs() + h * rotation.b.abs()),
+                    fit(w * rotation.b.abs() + h * rotation.a.abs()),
+                )
+            }
+        };
+        let transform =
+            AffineTransform::translation(-(self.width as f32) / 2.0, -(self.height as f32) / 2.0)
+                .then(&rotation)
+                .then(&AffineTransform::translation(
+                    width as f32 / 2.0,
+                    height as f32 / 2.0,
+                ));
+        self.create_new_warped(&transform, width, height)
+    }
+
+    /// Returns a new PixelsCollection of the given size where these pixels are drawn moved by the AffineTransform,
+    /// sampled bilinearly on premultiplied Alpha. Where no pixels end up the result is transparent,
+    /// as it is everywhere if the transform has no inverse (e.g.: it scales by 0)
+    pub fn create_new_warped(
+        &self,
+        transform: &AffineTransform,
+        width: usize,
+        height: usize,
+    ) -> PixelsCollection<u8> {
+        let mut bytes = vec![0u8; width * height * 4];
+        if let Some(inverse) = transform.inverse() {
+            for (i, dst) in bytes.chunks_exact_mut(4).enumerate() {
+                // pixels are sampled at their center
+                let (x, y) = inverse.apply((i % width) as f32 + 0.5, (i / width) as f32 + 0.5);
+                dst.copy_from_slice(&self.sample_bilinear(x - 0.5, y - 0.5));
+            }
+        }
+        PixelsCollection::create(width, height, bytes).unwrap()
+    }
+
+    /// Interpolates the 2x2 pixels around x, y (pixel coordinates, the pixels outside count as transparent)
+    fn sample_bilinear(&self, x: f32, y: f32) -> [u8; 4] {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let mut premultiplied = [0f32; 4];
+        for (dx, dy, weight) in [
+            (0, 0, (1.0 - fx) * (1.0 - fy)),
+            (1, 0, fx * (1.0 - fy)),
+            (0, 1, (1.0 - fx) * fy),
+            (1, 1, fx * fy),
+        ] {
+            let (px, py) = (x0 as i64 + dx, y0 as i64 + dy);
+            if weight <= 0.0
+                || px < 0
+                || py < 0
+                || px >= self.width as i64
+                || py >= self.height as i64
+            {
+                continue;
+            }
+            let i = (py as usize * self.width + px as usize) * 4;
+            let bgra = &self.bytes[i..i + 4];
+            let alpha = bgra[3] as f32 * weight;
+            for c in 0..3 {
+                premultiplied[c] += bgra[c] as f32 * alpha;
+            }
+            premultiplied[3] += alpha;
+        }
+        let a = premultiplied[3];
+        if a < 0.5 {
+            return [0, 0, 0, 0];
+        }
+        [
+            (premultiplied[0] / a).round().min(255.0) as u8,
+            (premultiplied[1] / a).round().min(255.0) as u8,
+            (premultiplied[2] / a).round().min(255.0) as u8,
+            a.round().min(255.0) as u8,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3x2 pixels whose Blue is their index
+    fn numbered() -> PixelsCollection<u8> {
+        let bytes = (0..6).flat_map(|i| [i, 0, 0, 255]).collect();
+        PixelsCollection::<u8>::create(3, 2, bytes).unwrap()
+    }
+
+    fn blues(pixels: &PixelsCollection<u8>) -> Vec<u8> {
+        pixels.bytes.chunks_exact(4).map(|bgra| bgra[0]).collect()
+    }
+
+    #[test]
+    fn flips_and_right_angles() {
+        let mut pixels = numbered();
+        pixels.flip_horizontal();
+        assert_eq!(blues(&pixels), [2, 1, 0, 5, 4, 3]);
+        pixels = numbered();
+        pixels.flip_vertical();
+        assert_eq!(blues(&pixels), [3, 4, 5, 0, 1, 2]);
+
+        pixels = numbered();
+        pixels.transpose();
+        assert_eq!((pixels.width, pixels.height, pixels.row_length), (2, 3, 8));
+        assert_eq!(blues(&pixels), [0, 3, 1, 4, 2, 5]);
+        pixels = numbered();
+        pixels.rotate_90();
+        assert_eq!(blues(&pixels), [3, 0, 4, 1, 5, 2]);
+        pixels = numbered();
+        pixels.rotate_180();
+        assert_eq!(blues(&pixels), [5, 4, 3, 2, 1, 0]);
+        pixels = numbered();
+        pixels.rotate_270();
+        assert_eq!(blues(&pixels), [2, 5, 1, 4, 0, 3]);
+
+        let mut values = PixelsCollection::<u32>::create(3, 2, (0..6).collect()).unwrap();
+        values.rotate_90();
+        assert_eq!((values.width, values.height, values.row_length), (2, 3, 2));
+        assert_eq!(values.bytes, [3, 0, 4, 1, 5, 2]);
+    }
+
+    #[test]
+    fn arbitrary_rotations() {
+        let pixels = numbered();
+        let mut rotated = pixels.clone();
+        rotated.rotate_90();
+        assert!(pixels.create_new_rotated(90.0, RotationCanvas::Expanded) == rotated);
+        assert!(pixels.create_new_rotated(0.0, RotationCanvas::Cropped) == pixels);
+        rotated.rotate_90();
+        assert!(pixels.create_new_rotated(-180.0, RotationCanvas::Cropped) == rotated);
+
+        let square = PixelsCollection::<u8>::create(10, 10, vec![255; 10 * 10 * 4]).unwrap();
+        let expanded = square.create_new_rotated(45.0, RotationCanvas::Expanded);
+        assert_eq!((expanded.width, expanded.height), (15, 15));
+        // the corners are left transparent, the center is covered, the edges are blended with the transparency
+        assert_eq!(expanded.bytes[..4], [0, 0, 0, 0]);
+        let center = (7 * 15 + 7) * 4;
+        assert_eq!(expanded.bytes[center..center + 4], [255, 255, 255, 255]);
+        assert!(expanded
+            .bytes
+            .chunks_exact(4)
+            .all(|bgra| bgra[3] == 0 || bgra[..3] == [255, 255, 255]));
+        let cropped = square.create_new_rotated(45.0, RotationCanvas::Cropped);
+        assert_eq!((cropped.width, cropped.height), (10, 10));
+    }
+
+    #[test]
+    fn warps() {
+        let pixels = numbered();
+        let moved = pixels.create_new_warped(&AffineTransform::translation(1.0, 1.0), 3, 2);
+        assert_eq!(
+            moved
+                .bytes
+                .chunks_exact(4)
+                .map(|bgra| bgra[3])
+                .collect::<Vec<_>>(),
+            [0, 0, 0, 0, 255, 255]
+        );
+        assert_eq!(blues(&moved)[4..], [0, 1]);
+
+        let doubled = pixels.create_new_warped(&AffineTransform::scale(2.0, 2.0), 6, 4);
+        assert_eq!(blues(&doubled)[..6], [0, 0, 1, 1, 2, 2]);
+
+        let transform = AffineTransform::rotation(30.0)
+            .then(&AffineTransform::skew(10.0, 0.0))
+            .then(&AffineTransform::translation(3.0, -2.0));
+        let (x, y) = transform.apply(4.0, 5.0);
+        let (x, y) = transform.inverse().unwrap().apply(x, y);
+        assert!((x - 4.0).abs() < 1e-4 && (y - 5.0).abs() < 1e-4);
+
+        let vanished = pixels.create_new_warped(&AffineTransform::scale(0.0, 1.0), 3, 2);
+        assert!(vanished.bytes.iter().all(|&b| b == 0));
+    }
+}
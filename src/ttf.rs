@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use ab_glyph::{point, Font, FontRef, GlyphId, PxScale, ScaleFont};
+
+use crate::{
+    pixels_string::{CharsCollection, CharsHashmap, GlyphMetrics, PixelsChar, BGRA, CHARS},
+    PixelCasterError, PixelsCollection,
+};
+
+/// A glyph's coverage (0 - 1) row by row, with its position relative to the pen on the baseline
+struct Coverage {
+    char: char,
+    left: i32,
+    top: i32,
+    width: usize,
+    height: usize,
+    advance: i32,
+    values: Vec<f32>,
+}
+
+impl CharsCollection<u8> {
+    /// Creates a new collection rendering the chars of a TrueType/OpenType font (.ttf/.otf bytes) px_size pixels tall, anti-aliased,
+    /// in the given color (the coverage of each pixel scaling its Alpha). A ' ' is added if the charset does not contain it.
+    /// Every char is as tall as the tallest line (from the highest ascender to the lowest descender) with its baseline on the same row,
+    /// and as wide as its drawn pixels, its metrics holding the font's advance and bearings (rounded to whole pixels), with the font's kerning
+    pub fn from_ttf(
+        font_bytes: &[u8],
+        px_size: f32,
+        charset: &str,
+        bgra: BGRA<u8>,
+    ) -> Result<CharsCollection<u8>, PixelCasterError> {
+        let font = FontRef::try_from_slice(font_bytes).map_err(PixelCasterError::decode)?;
+        let scale = PxScale::from(px_size);
+        let scaled_font = font.as_scaled(scale);
+
+        let mut charset: Vec<char> = charset.chars().collect();
+        if !charset.contains(&' ') {
+            charset.push(' ');
+        }
+
+        let mut glyphs = Vec::with_capacity(charset.len());
+        // the line's top and bottom rows relative to the baseline
+        let (mut top, mut bottom) = (
+            -scaled_font.ascent().ceil() as i32,
+            -scaled_font.descent().floor() as i32,
+        );
+        for char in charset {
+            let id = font.glyph_id(char);
+            // the glyph 0 is the font's "missing glyph" box
+            if id == GlyphId(0) && !char.is_whitespace() {
+                return Err(PixelCasterError::GlyphNotFound(char));
+            }
+            let advance = scaled_font.h_advance(id).round() as i32;
+            let coverage =
+                match font.outline_glyph(id.with_scale_and_position(scale, point(0.0, 0.0))) {
+                    Some(outlined) => {
+                        let bounds = outlined.px_bounds();
+                        let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+                        let mut values = vec![0.0; width * height];
+                        outlined.draw(|x, y, c| values[y as usize * width + x as usize] = c);
+                        Coverage {
+                            char,
+                            left: bounds.min.x as i32,
+                            top: bounds.min.y as i32,
+                            width,
+                            height,
+                            advance,
+                            values,
+                        }
+                    }
+                    // e.g.: ' ', nothing to draw but the advance
+                    None => Coverage {
+                        char,
+                        left: 0,
+                        top: 0,
+                        width: advance.max(0) as usize,
+                        height: 0,
+                        advance,
+                        values: Vec::new(),
+                    },
+                };
+            top = top.min(coverage.top);
+            bottom = bottom.max(coverage.top + coverage.height as i32);
+            glyphs.push(coverage);
+        }
+
+        let line_height = (bottom - top).max(1) as usize;
+        let chars = glyphs
+            .into_iter()
+            .map(|glyph| {
+                let mut bytes = vec![0; glyph.width * line_height * 4];
+                let first_row = (glyph.top - top) as usize;
+                for (i, c) in glyph.values.iter().enumerate() {
+                    let alpha = (c.clamp(0.0, 1.0) * bgra.3 as f32).round() as u8;
+                    if alpha > 0 {
+                        let (x, y) = (i % glyph.width, first_row + i / glyph.width);
+                        let index = (y * glyph.width + x) * 4;
+                        bytes[index..index + 4].copy_from_slice(&[bgra.0, bgra.1, bgra.2, alpha]);
+                    }
+                }
+                Ok(PixelsChar {
+                    char: glyph.char,
+                    char_name: CHARS
+                        .get_char_name_by_char(glyph.char)
+                        .unwrap_or_else(|| glyph.char.to_string()),
+                    pixels: PixelsCollection::<u8>::create(glyph.width, line_height, bytes)?,
+                    metrics: GlyphMetrics::new(
+                        (-top) as usize,
+                        glyph.advance,
+                        glyph.left,
+                        glyph.width,
+                    ),
+                })
+            })
+            .collect::<Result<Vec<_>, PixelCasterError>>()?;
+
+        let mut chars_collection = CharsCollection {
+            chars,
+            path: "".to_string(),
+            bgra,
+            kerning: HashMap::new(),
+        };
+        chars_collection.kerning_from_ttf(font_bytes, px_size)?;
+        Ok(chars_collection)
+    }
+
+    /// Sets the kerning of every pair of the collection's chars to the one of the font (.ttf/.otf bytes, from its "kern" table) at px_size pixels,
+    /// rounded to whole pixels. Returns how many pairs have a kerning
+    pub fn kerning_from_ttf(
+        &mut self,
+        font_bytes: &[u8],
+        px_size: f32,
+    ) -> Result<usize, PixelCasterError> {
+        let font = FontRef::try_from_slice(font_bytes).map_err(PixelCasterError::decode)?;
+        let scaled_font = font.as_scaled(PxScale::from(px_size));
+        let ids: Vec<(char, GlyphId)> = self
+            .chars
+            .iter()
+            .map(|c| (c.char, font.glyph_id(c.char)))
+            .collect();
+        self.kerning.clear();
+        for (first, first_id) in &ids {
+            for (second, second_id) in &ids {
+                let adjustment = scaled_font.kern(*first_id, *second_id).round() as i32;
+                if adjustment != 0 {
+                    self.kerning.insert((*first, *second), adjustment);
+                }
+            }
+        }
+        Ok(self.kerning.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // DejaVu Sans reduced to the printable ASCII chars, with its kern table (see tests/fixtures/README.md)
+    const FONT_BYTES: &[u8] = include_bytes!("../tests/fixtures/DejaVuSans-ascii.ttf");
+
+    #[test]
+    fn from_ttf_metrics() {
+        let chars =
+            CharsCollection::from_ttf(FONT_BYTES, 32.0, "Hgp,", BGRA(0, 0, 255, 255)).unwrap();
+        assert_eq!(
+            chars.chars.iter().map(|c| c.char).collect::<String>(),
+            "Hgp, "
+        );
+        let char = |c: char| chars.chars.iter().find(|pc| pc.char == c).unwrap();
+
+        // same height and baseline for all
+        let (h, g) = (char('H'), char('g'));
+        assert!(chars.chars.iter().all(
+            |c| c.pixels.height == h.pixels.height && c.metrics.baseline == h.metrics.baseline
+        ));
+        let last_visible_row = |c: &PixelsChar<u8>| {
+            (0..c.pixels.height)
+                .rev()
+                .find(|y| {
+                    (0..c.pixels.width)
+                        .any(|x| c.pixels.bytes[(y * c.pixels.width + x) * 4 + 3] > 0)
+                })
+                .unwrap()
+        };
+        // 'H' sits on the baseline, 'g' goes below it
+        assert_eq!(last_visible_row(h) + 1, h.metrics.baseline);
+        assert!(last_visible_row(g) >= g.metrics.baseline + 3);
+
+        // bearings and width make up the advance
+        for c in &chars.chars {
+            assert_eq!(
+                c.metrics.left_bearing + c.pixels.width as i32 + c.metrics.right_bearing,
+                c.metrics.advance
+            );
+        }
+        assert!(h.metrics.left_bearing > 0);
+        let space = char(' ');
+        assert!(space.metrics.advance > 0);
+        assert!(space.pixels.bytes.iter().all(|&b| b == 0));
+
+        // anti-aliased in the given color
+        assert!(h
+            .pixels
+            .bytes
+            .chunks_exact(4)
+            .all(|bgra| bgra[3] == 0 || bgra[..3] == [0, 0, 255]));
+        assert!(g
+            .pixels
+            .bytes
+            .chunks_exact(4)
+            .any(|bgra| bgra[3] > 0 && bgra[3] < 255));
+    }
+
+    #[test]
+    fn ttf_kerning() {
+        let mut chars =
+            CharsCollection::from_ttf(FONT_BYTES, 40.0, "AVTo", BGRA(0, 0, 0, 255)).unwrap();
+        assert!(chars.kerning[&('A', 'V')] < 0);
+        assert!(chars.kerning[&('T', 'o')] < 0);
+
+        let kerned_width = chars.create_pixels_string("To", 0).pixels.width;
+        chars.kerning.clear();
+        assert!(kerned_width < chars.create_pixels_string("To", 0).pixels.width);
+    }
+
+    #[test]
+    fn from_ttf_errors() {
+        assert!(matches!(
+            CharsCollection::from_ttf(&[0, 1, 2, 3], 12.0, "a", BGRA(0, 0, 0, 255)),
+            Err(PixelCasterError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn from_ttf_missing_glyph() {
+        assert!(matches!(
+            CharsCollection::from_ttf(FONT_BYTES, 12.0, "a\u{e000}", BGRA(0, 0, 0, 255)),
+            Err(PixelCasterError::GlyphNotFound('\u{e000}'))
+        ));
+    }
+}
@@ -0,0 +1,497 @@
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{
+    check_vec_length, compositing, PixelCasterError, PixelValues, PixelsCollection, PixelsSendMode,
+    ScreenArea, ScreenBackend,
+};
+
+/// Width of the framebuffer that each thread gets by default
+pub const VIRTUAL_SCREEN_DEFAULT_WIDTH: usize = 1920;
+/// Height of the framebuffer that each thread gets by default
+pub const VIRTUAL_SCREEN_DEFAULT_HEIGHT: usize = 1080;
+/// BGRA every pixel of a new framebuffer is set to (fully opaque black)
+pub const VIRTUAL_SCREEN_BACKGROUND: (u8, u8, u8, u8) = (0, 0, 0, 255);
+
+thread_local! {
+    /// Framebuffer used by the VirtualScreen instances obtained through ScreenBackend::open (e.g.: by Screen::update_area_custom),
+    /// each thread has its own so that tests running in parallel don't interfere with each other
+    static DEFAULT_FRAMEBUFFER: RefCell<Option<Arc<Mutex<PixelsCollection<u8>>>>> = const { RefCell::new(None) };
+}
+
+/// In-memory screen, holds a framebuffer of BGRA bytes to/from which the pixels are sent/captured,
+/// applying the same blending rules the Windows API applies to the real screen for each PixelsSendMode.
+/// Clones share the same framebuffer.
+#[derive(Clone)]
+pub struct VirtualScreen {
+    framebuffer: Arc<Mutex<PixelsCollection<u8>>>,
+}
+
+impl VirtualScreen {
+    /// Creates a new screen of the given size, with its own framebuffer filled with VIRTUAL_SCREEN_BACKGROUND
+    pub fn new(width: usize, height: usize) -> VirtualScreen {
+        let mut bytes = <u8>::initialize_vec(width, height);
+        bytes.chunks_exact_mut(4).for_each(|c| {
+            c.copy_from_slice(&[
+                VIRTUAL_SCREEN_BACKGROUND.0,
+                VIRTUAL_SCREEN_BACKGROUND.1,
+                VIRTUAL_SCREEN_BACKGROUND.2,
+                VIRTUAL_SCREEN_BACKGROUND.3,
+            ])
+        });
+        Self::from_pixels(PixelsCollection::<u8>::create(width, height, bytes).unwrap())
+    }
+
+    /// Creates a new screen which framebuffer is the provided PixelsCollection
+    pub fn from_pixels(pixels: PixelsCollection<u8>) -> VirtualScreen {
+        VirtualScreen {
+            framebuffer: Arc::new(Mutex::new(pixels)),
+        }
+    }
+
+    /// Returns a screen sharing the current thread's default framebuffer (VIRTUAL_SCREEN_DEFAULT_WIDTH x VIRTUAL_SCREEN_DEFAULT_HEIGHT),
+    /// the one used when a Screen is not given a specific VirtualScreen
+    pub fn thread_default() -> VirtualScreen {
+        DEFAULT_FRAMEBUFFER.with(|fb| VirtualScreen {
+            framebuffer: fb
+                .borrow_mut()
+                .get_or_insert_with(|| {
+                    Self::new(VIRTUAL_SCREEN_DEFAULT_WIDTH, VIRTUAL_SCREEN_DEFAULT_HEIGHT)
+                        .framebuffer
+                })
+                .clone(),
+        })
+    }
+
+    /// Replaces the current thread's default framebuffer with a new one of the given size, filled with VIRTUAL_SCREEN_BACKGROUND
+    pub fn reset_thread_default(width: usize, height: usize) {
+        let new_framebuffer = Self::new(width, height).framebuffer;
+        DEFAULT_FRAMEBUFFER.with(|fb| *fb.borrow_mut() = Some(new_framebuffer));
+    }
+
+    /// Locks and returns the framebuffer, to inspect or alterate it directly
+    pub fn framebuffer(&self) -> MutexGuard<'_, PixelsCollection<u8>> {
+        self.framebuffer.lock().unwrap()
+    }
+
+    /// Returns a copy of the framebuffer
+    pub fn to_pixels_collection(&self) -> PixelsCollection<u8> {
+        self.framebuffer().clone()
+    }
+
+    /// Returns the BGRA of the pixel at the given coordinate, None if it's outside of the screen
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<[u8; 4]> {
+        let framebuffer = self.framebuffer();
+        if x < 0 || y < 0 || x as usize >= framebuffer.width || y as usize >= framebuffer.height {
+            return None;
+        }
+        let i = framebuffer.coord_to_index(x as usize, y as usize);
+        Some([
+            framebuffer.bytes[i],
+            framebuffer.bytes[i + 1],
+            framebuffer.bytes[i + 2],
+            framebuffer.bytes[i + 3],
+        ])
+    }
+
+    /// Same as Screen::update_area_custom, but always on the current thread's default VirtualScreen whatever the DefaultBackend is,
+    /// for the tests which don't need a real display
+    #[cfg(test)]
+    pub(crate) fn update_area_custom<T: PixelValues<T>>(
+        vec: &[T],
+        screen_area_upperleftcorner_x: i32,
+        screen_area_upperleftcorner_y: i32,
+        area_width: u32,
+        area_height: u32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> Result<(), PixelCasterError> {
+        Self::thread_default().present_rect(
+            <T>::as_bgra_bytes(vec),
+            screen_area_upperleftcorner_x,
+            screen_area_upperleftcorner_y,
+            area_width,
+            area_height,
+            pixels_send_mode,
+        )
+    }
+
+    /// Exports the framebuffer into the given path in .png file format (BGRA will become RGBA)
+    #[cfg(feature = "pixels_string")]
+    pub fn save_png(&self, png_path: &str) -> Result<(), PixelCasterError> {
+        use crate::bgra_management::SwitchBytes;
+        let framebuffer = self.framebuffer();
+        image::save_buffer_with_format(
+            png_path,
+            &<u8>::swap_blue_with_red(&framebuffer.bytes),
+            framebuffer.width as u32,
+            framebuffer.height as u32,
+            image::ColorType::Rgba8,
+            image::ImageFormat::Png,
+        )
+        .map_err(PixelCasterError::encode)
+    }
+
+    /// Calls `f` for each pixel of the given area that is inside a fb_width x fb_height framebuffer,
+    /// with the index of the pixel in the framebuffer and the index of the same pixel in an area_width x area_height BGRA Vec
+    fn for_each_visible_pixel<F: FnMut(usize, usize)>(
+        fb_width: usize,
+        fb_height: usize,
+        ul_x: i32,
+        ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        mut f: F,
+    ) {
+        for ay in 0..area_height as i64 {
+            let y = ul_y as i64 + ay;
+            if y < 0 || y >= fb_height as i64 {
+                continue;
+            }
+            for ax in 0..area_width as i64 {
+                let x = ul_x as i64 + ax;
+                if x < 0 || x >= fb_width as i64 {
+                    continue;
+                }
+                f(
+                    ((y * fb_width as i64 + x) * 4) as usize,
+                    ((ay * area_width as i64 + ax) * 4) as usize,
+                );
+            }
+        }
+    }
+
+    /// Bit-block transfer, the Alpha is not used so every pixel gets full opacity.
+    /// The pixels matching the BGR to hide (if any) are left untouched, as TransparentBlt does
+    fn bit_block_transfer(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        hide_bgr: Option<(u8, u8, u8)>,
+    ) -> Result<(), PixelCasterError> {
+        check_vec_length::<u8>(src.len(), area_width, area_height)?;
+        let mut framebuffer = self.framebuffer();
+        let (fb_width, fb_height) = (framebuffer.width, framebuffer.height);
+        let fb = &mut framebuffer.bytes;
+        Self::for_each_visible_pixel(
+            fb_width,
+            fb_height,
+            dst_ul_x,
+            dst_ul_y,
+            area_width,
+            area_height,
+            |fb_i, i| {
+                if hide_bgr != Some((src[i], src[i + 1], src[i + 2])) {
+                    fb[fb_i..fb_i + 3].copy_from_slice(&src[i..i + 3]);
+                    fb[fb_i + 3] = 255;
+                }
+            },
+        );
+        Ok(())
+    }
+}
+
+impl ScreenBackend for VirtualScreen {
+    /// Returns a screen sharing the current thread's default framebuffer, the requested area size is not needed to prepare it
+    fn open(
+        _area_width: u32,
+        _area_height: u32,
+        _is_static: bool,
+    ) -> Result<VirtualScreen, PixelCasterError> {
+        Ok(Self::thread_default())
+    }
+
+    /// Pixels outside of the framebuffer are captured as fully transparent black
+    fn capture_rect(
+        &self,
+        dst: &mut [u8],
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Result<(), PixelCasterError> {
+        check_vec_length::<u8>(dst.len(), area_width, area_height)?;
+        dst.fill(0);
+        let framebuffer = self.framebuffer();
+        Self::for_each_visible_pixel(
+            framebuffer.width,
+            framebuffer.height,
+            src_ul_x,
+            src_ul_y,
+            area_width,
+            area_height,
+            |fb_i, i| dst[i..i + 4].copy_from_slice(&framebuffer.bytes[fb_i..fb_i + 4]),
+        );
+        Ok(())
+    }
+
+    fn present_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> Result<(), PixelCasterError> {
+        match pixels_send_mode {
+            // the Windows API gets the bytes adjusted to premultiplied alpha and blends them per-pixel
+            PixelsSendMode::AlphaEnabled => self.present_premultiplied_rect(
+                &<u8>::create_adjusted_vec(src),
+                dst_ul_x,
+                dst_ul_y,
+                area_width,
+                area_height,
+                255,
+            ),
+            PixelsSendMode::CustomAlpha(custom_alpha @ 0..=254) => self.present_premultiplied_rect(
+                &<u8>::create_adjusted_vec(src),
+                dst_ul_x,
+                dst_ul_y,
+                area_width,
+                area_height,
+                custom_alpha,
+            ),
+            PixelsSendMode::AlphaDisabled | PixelsSendMode::CustomAlpha(255) => {
+                self.bit_block_transfer(src, dst_ul_x, dst_ul_y, area_width, area_height, None)
+            }
+            PixelsSendMode::AlphaDisabledHideBGR(hide_b, hide_g, hide_r) => self
+                .bit_block_transfer(
+                    src,
+                    dst_ul_x,
+                    dst_ul_y,
+                    area_width,
+                    area_height,
+                    Some((hide_b, hide_g, hide_r)),
+                ),
+        }
+    }
+
+    fn present_premultiplied_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        source_constant_alpha: u8,
+    ) -> Result<(), PixelCasterError> {
+        check_vec_length::<u8>(src.len(), area_width, area_height)?;
+        let mut framebuffer = self.framebuffer();
+        let (fb_width, fb_height) = (framebuffer.width, framebuffer.height);
+        let fb = &mut framebuffer.bytes;
+        Self::for_each_visible_pixel(
+            fb_width,
+            fb_height,
+            dst_ul_x,
+            dst_ul_y,
+            area_width,
+            area_height,
+            |fb_i, i| {
+                compositing::blend_premultiplied(
+                    &mut fb[fb_i..fb_i + 4],
+                    &src[i..i + 4],
+                    source_constant_alpha,
+                )
+            },
+        );
+        Ok(())
+    }
+
+    fn copy_rect(
+        &self,
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+    ) -> Result<(), PixelCasterError> {
+        // captured first, so that overlapping source and destination areas are copied correctly
+        let mut captured = <u8>::initialize_vec(area_width as usize, area_height as usize);
+        self.capture_rect(&mut captured, src_ul_x, src_ul_y, area_width, area_height)?;
+        let mut framebuffer = self.framebuffer();
+        let (fb_width, fb_height) = (framebuffer.width, framebuffer.height);
+        let fb = &mut framebuffer.bytes;
+        Self::for_each_visible_pixel(
+            fb_width,
+            fb_height,
+            dst_ul_x,
+            dst_ul_y,
+            area_width,
+            area_height,
+            |fb_i, i| fb[fb_i..fb_i + 4].copy_from_slice(&captured[i..i + 4]),
+        );
+        Ok(())
+    }
+
+    fn screen_bounds(&self) -> ScreenArea {
+        let framebuffer = self.framebuffer();
+        ScreenArea::new(0, 0, framebuffer.width as u32, framebuffer.height as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PremultipliedPixels, Screen};
+
+    fn screen_with_background(b: u8, g: u8, r: u8) -> VirtualScreen {
+        let virtual_screen = VirtualScreen::new(4, 4);
+        virtual_screen
+            .framebuffer()
+            .bytes
+            .chunks_exact_mut(4)
+            .for_each(|c| c.copy_from_slice(&[b, g, r, 255]));
+        virtual_screen
+    }
+
+    #[test]
+    fn send_modes() {
+        let virtual_screen = screen_with_background(100, 100, 100);
+        let send = |bytes: &[u8], mode: PixelsSendMode| {
+            virtual_screen.framebuffer().bytes[..8]
+                .copy_from_slice(&[100, 100, 100, 255, 100, 100, 100, 255]);
+            virtual_screen
+                .present_rect(bytes, 0, 0, 2, 1, mode)
+                .unwrap();
+            (
+                virtual_screen.get_pixel(0, 0).unwrap(),
+                virtual_screen.get_pixel(1, 0).unwrap(),
+            )
+        };
+        let bytes = [100, 0, 50, 128, 10, 20, 30, 0];
+
+        assert_eq!(
+            send(&bytes, PixelsSendMode::AlphaDisabled),
+            ([100, 0, 50, 255], [10, 20, 30, 255])
+        );
+        assert_eq!(
+            send(&bytes, PixelsSendMode::AlphaDisabledHideBGR(10, 20, 30)),
+            ([100, 0, 50, 255], [100, 100, 100, 255])
+        );
+        // the half transparent pixel is blended over the background, the fully transparent one leaves it untouched
+        assert_eq!(
+            send(&bytes, PixelsSendMode::AlphaEnabled),
+            ([100, 50, 75, 255], [100, 100, 100, 255])
+        );
+        assert_eq!(
+            send(
+                &[200, 0, 50, 255, 10, 20, 30, 255],
+                PixelsSendMode::CustomAlpha(51)
+            ),
+            ([120, 80, 90, 255], [82, 84, 86, 255])
+        );
+        assert_eq!(
+            send(&bytes, PixelsSendMode::CustomAlpha(255)),
+            ([100, 0, 50, 255], [10, 20, 30, 255])
+        );
+    }
+
+    #[test]
+    fn clipping_and_capture_outside() {
+        let virtual_screen = screen_with_background(0, 0, 0);
+        let mut screen =
+            Screen::<u8, VirtualScreen>::with_backend(virtual_screen.clone(), 3, 3, 2, 2);
+        screen.get_bytes_mut().fill(255);
+        screen.update_area().unwrap();
+        assert_eq!(virtual_screen.get_pixel(3, 3), Some([255, 255, 255, 255]));
+        assert_eq!(virtual_screen.get_pixel(4, 4), None);
+
+        screen.scan_area().unwrap();
+        assert_eq!(screen.get_bytes()[..4], [255, 255, 255, 255]);
+        assert_eq!(screen.get_bytes()[4..], [0; 12]);
+    }
+
+    #[test]
+    fn copy_overlapping_areas() {
+        let virtual_screen = screen_with_background(0, 0, 0);
+        for (x, byte) in virtual_screen.framebuffer().bytes[..16]
+            .chunks_exact_mut(4)
+            .enumerate()
+        {
+            byte[0] = x as u8;
+        }
+        virtual_screen.copy_rect(0, 0, 3, 1, 1, 0).unwrap();
+        let first_row: Vec<u8> = (0..4)
+            .map(|x| virtual_screen.get_pixel(x, 0).unwrap()[0])
+            .collect();
+        assert_eq!(first_row, [0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn default_screen_is_shared_within_the_thread() {
+        VirtualScreen::reset_thread_default(8, 8);
+        let vec = vec![0xFF000001u32; 4];
+        Screen::<u32, VirtualScreen>::new(2, 2, 2, 2)
+            .update_area_from_vec(&vec)
+            .unwrap();
+        let mut scanned = vec![0u32; 4];
+        Screen::<u32, VirtualScreen>::new(2, 2, 2, 2)
+            .scan_area_onto_vec(&mut scanned)
+            .unwrap();
+        assert_eq!(scanned, vec);
+        assert_eq!(VirtualScreen::thread_default().screen_bounds().width(), 8);
+    }
+
+    #[test]
+    fn premultiplied_send() {
+        let virtual_screen =
+            VirtualScreen::from_pixels(PixelsCollection::<u8>::create(2, 1, vec![0; 8]).unwrap());
+        let straight =
+            PixelsCollection::<u8>::create(2, 1, vec![100, 0, 50, 128, 10, 20, 30, 255]).unwrap();
+        let premultiplied = PremultipliedPixels::from_straight(straight.clone());
+        let mut screen =
+            Screen::<u8, VirtualScreen>::with_backend(virtual_screen.clone(), 0, 0, 2, 1);
+        screen
+            .update_area_from_premultiplied(&premultiplied, 255)
+            .unwrap();
+        let sent_premultiplied = virtual_screen.to_pixels_collection().bytes;
+
+        // the same as sending the straight pixels with AlphaEnabled
+        virtual_screen.framebuffer().bytes.fill(0);
+        virtual_screen
+            .present_rect(&straight.bytes, 0, 0, 2, 1, PixelsSendMode::AlphaEnabled)
+            .unwrap();
+        assert_eq!(
+            sent_premultiplied,
+            virtual_screen.to_pixels_collection().bytes
+        );
+        assert_eq!(sent_premultiplied, [50, 0, 25, 128, 10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn short_buffers() {
+        let virtual_screen = screen_with_background(1, 2, 3);
+        for pixels_send_mode in [
+            PixelsSendMode::AlphaEnabled,
+            PixelsSendMode::AlphaDisabled,
+            PixelsSendMode::AlphaDisabledHideBGR(0, 0, 0),
+        ] {
+            assert!(matches!(
+                virtual_screen.present_rect(&[255; 12], 0, 0, 2, 2, pixels_send_mode),
+                Err(PixelCasterError::DimensionMismatch {
+                    expected: 16,
+                    actual: 12
+                })
+            ));
+        }
+        assert!(matches!(
+            virtual_screen.present_premultiplied_rect(&[255; 4], 0, 0, 2, 1, 255),
+            Err(PixelCasterError::DimensionMismatch { .. })
+        ));
+        assert!(virtual_screen
+            .capture_rect(&mut [0; 4], 0, 0, 2, 1)
+            .is_err());
+        // nothing was sent
+        assert!(virtual_screen
+            .framebuffer()
+            .bytes
+            .chunks_exact(4)
+            .all(|c| c == [1, 2, 3, 255]));
+    }
+}
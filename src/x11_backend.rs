@@ -0,0 +1,698 @@
+use std::sync::{Arc, Mutex};
+
+use x11rb::{
+    connection::{Connection, RequestConnection},
+    protocol::{
+        shape, shm,
+        xproto::{self, ConnectionExt as _},
+    },
+    rust_connection::RustConnection,
+};
+
+use crate::{
+    PixelCasterError, PixelValues, PixelsCollection, PixelsSendMode, ScreenArea, ScreenBackend,
+    VirtualScreen,
+};
+
+/// Connection to an X server, shared by every X11Backend obtained through ScreenBackend::open (e.g.: by Screen::update_area_custom),
+/// so that the overlay window and the XShm segment outlive the single operations
+static SHARED_BACKEND: Mutex<Option<X11Backend>> = Mutex::new(None);
+
+/// Stuff used to work with an X server (X11), clones share the same connection.
+///
+/// The pixels are captured from the root window. They are sent either to an override-redirect ARGB overlay window covering the
+/// root window, which lets a compositing manager blend them with what's below (real translucency), or, when there's no
+/// compositing manager, straight onto the root window after blending them in software with its current content.
+/// XShm is used to move the bytes when the X server supports it, plain GetImage/PutImage requests otherwise.
+#[derive(Clone)]
+pub struct X11Backend {
+    inner: Arc<X11Connection>,
+}
+
+struct X11Connection {
+    connection: RustConnection,
+    root: xproto::Window,
+    root_depth: u8,
+    root_width: u16,
+    root_height: u16,
+    /// Graphics context used to draw onto the root window, over its children too
+    root_gc: xproto::Gcontext,
+    /// None if the X server doesn't support XShm 1.2 (e.g.: when it's not on the same host)
+    shm_segment: Option<Mutex<ShmSegment>>,
+    /// None if there's no compositing manager or if the overlay has been disabled
+    overlay: Option<Overlay>,
+}
+
+/// Override-redirect, input transparent, ARGB window covering the whole root window
+struct Overlay {
+    window: xproto::Window,
+    gc: xproto::Gcontext,
+    /// Premultiplied BGRA content of the window, the sent pixels are blended into it before being uploaded
+    content: VirtualScreen,
+    mapped: Mutex<bool>,
+}
+
+/// Memory shared with the X server, grown as bigger areas are requested
+struct ShmSegment {
+    seg: shm::Seg,
+    ptr: *mut u8,
+    size: usize,
+}
+unsafe impl Send for ShmSegment {}
+
+impl Drop for ShmSegment {
+    fn drop(&mut self) {
+        if self.size > 0 {
+            unsafe {
+                libc::munmap(self.ptr as *mut libc::c_void, self.size);
+            }
+        }
+    }
+}
+
+impl X11Backend {
+    /// Connects to the given X display (e.g.: ":0"), or to the one set by $DISPLAY if None.
+    /// The overlay window is used only if a compositing manager is running
+    pub fn connect(display_name: Option<&str>) -> Result<X11Backend, PixelCasterError> {
+        Self::connect_with_overlay(display_name, true)
+    }
+
+    /// Connects to the given X display (e.g.: ":0"), or to the one set by $DISPLAY if None.
+    /// If `use_overlay` is false (or there's no compositing manager) the pixels are always sent onto the root window
+    pub fn connect_with_overlay(
+        display_name: Option<&str>,
+        use_overlay: bool,
+    ) -> Result<X11Backend, PixelCasterError> {
+        let (connection, screen_num) =
+            x11rb::connect(display_name).map_err(PixelCasterError::backend)?;
+        let setup = connection.setup();
+        let screen = &setup.roots[screen_num];
+
+        // the bytes are exchanged as they are, so the pixels must be stored as BGRX/BGRA
+        let root_visual = screen
+            .allowed_depths
+            .iter()
+            .flat_map(|depth| &depth.visuals)
+            .find(|visual| visual.visual_id == screen.root_visual)
+            .ok_or_else(|| PixelCasterError::backend("root visual not found"))?;
+        let bits_per_pixel = setup
+            .pixmap_formats
+            .iter()
+            .find(|format| format.depth == screen.root_depth)
+            .map(|format| format.bits_per_pixel);
+        if setup.image_byte_order != xproto::ImageOrder::LSB_FIRST
+            || bits_per_pixel != Some(32)
+            || !Self::is_bgr_visual(root_visual)
+        {
+            return Err(PixelCasterError::backend(format!(
+                "unsupported pixel format: depth {}, {:?} bits per pixel, {:?} byte order",
+                screen.root_depth, bits_per_pixel, setup.image_byte_order
+            )));
+        }
+
+        let root = screen.root;
+        let root_depth = screen.root_depth;
+        let (root_width, root_height) = (screen.width_in_pixels, screen.height_in_pixels);
+        let argb_visual = screen
+            .allowed_depths
+            .iter()
+            .filter(|depth| depth.depth == 32)
+            .flat_map(|depth| &depth.visuals)
+            .find(|visual| {
+                visual.class == xproto::VisualClass::TRUE_COLOR && Self::is_bgr_visual(visual)
+            })
+            .map(|visual| visual.visual_id);
+        let compositing_manager_running =
+            Self::compositing_manager_running(&connection, screen_num)?;
+
+        let root_gc = connection
+            .generate_id()
+            .map_err(PixelCasterError::backend)?;
+        connection
+            .create_gc(
+                root_gc,
+                root,
+                &xproto::CreateGCAux::new()
+                    .subwindow_mode(xproto::SubwindowMode::INCLUDE_INFERIORS),
+            )
+            .map_err(PixelCasterError::backend)?;
+
+        let overlay = match argb_visual {
+            Some(argb_visual) if use_overlay && compositing_manager_running => Some(
+                Self::create_overlay(&connection, root, argb_visual, root_width, root_height)?,
+            ),
+            _ => None,
+        };
+
+        let shm_segment = if Self::shm_supported(&connection) {
+            Some(Mutex::new(ShmSegment {
+                seg: connection
+                    .generate_id()
+                    .map_err(PixelCasterError::backend)?,
+                ptr: std::ptr::null_mut(),
+                size: 0,
+            }))
+        } else {
+            None
+        };
+        connection.flush().map_err(PixelCasterError::backend)?;
+
+        Ok(X11Backend {
+            inner: Arc::new(X11Connection {
+                connection,
+                root,
+                root_depth,
+                root_width,
+                root_height,
+                root_gc,
+                shm_segment,
+                overlay,
+            }),
+        })
+    }
+
+    /// Returns a backend sharing the process-wide connection to the X display set by $DISPLAY, the one used when a Screen is not given a specific X11Backend
+    pub fn shared() -> Result<X11Backend, PixelCasterError> {
+        let mut shared = SHARED_BACKEND.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(backend) = shared.as_ref() {
+            return Ok(backend.clone());
+        }
+        let backend = Self::connect(None)?;
+        *shared = Some(backend.clone());
+        Ok(backend)
+    }
+
+    /// Returns true if the pixels are being sent to the ARGB overlay window, false if onto the root window
+    pub fn uses_overlay(&self) -> bool {
+        self.inner.overlay.is_some()
+    }
+
+    /// Returns true if the bytes are moved through XShm
+    pub fn uses_shm(&self) -> bool {
+        self.inner.shm_segment.is_some()
+    }
+
+    /// Removes everything that has been sent to the overlay window (if used), making it fully transparent again
+    pub fn clear_overlay(&self) -> Result<(), PixelCasterError> {
+        if let Some(overlay) = &self.inner.overlay {
+            let (width, height) = (self.inner.root_width, self.inner.root_height);
+            overlay.content.framebuffer().bytes.fill(0);
+            self.upload(
+                overlay.window,
+                overlay.gc,
+                32,
+                &overlay.content.framebuffer().bytes,
+                width as usize,
+                (0, 0, width, height),
+                (0, 0),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Uploads the `src_rect` (x, y, width, height) of the BGRA `buffer` (which rows are `buffer_width` pixels long) onto the drawable at `dst`
+    fn upload(
+        &self,
+        drawable: xproto::Drawable,
+        gc: xproto::Gcontext,
+        depth: u8,
+        buffer: &[u8],
+        buffer_width: usize,
+        src_rect: (i16, i16, u16, u16),
+        dst: (i16, i16),
+    ) -> Result<(), PixelCasterError> {
+        let (src_x, src_y, width, height) = src_rect;
+        let row_length = width as usize * 4;
+        let row = |r: usize| {
+            let i = ((src_y as usize + r) * buffer_width + src_x as usize) * 4;
+            &buffer[i..i + row_length]
+        };
+
+        let connection = &self.inner.connection;
+        match &self.inner.shm_segment {
+            Some(shm_segment) => {
+                let mut shm_segment = shm_segment.lock().unwrap_or_else(|e| e.into_inner());
+                self.ensure_shm_size(&mut shm_segment, row_length * height as usize)?;
+                let shared = unsafe {
+                    std::slice::from_raw_parts_mut(shm_segment.ptr, row_length * height as usize)
+                };
+                for (r, shared_row) in shared.chunks_exact_mut(row_length).enumerate() {
+                    shared_row.copy_from_slice(row(r));
+                }
+                // checked, so that the X server is done reading the segment before it gets reused
+                shm::put_image(
+                    connection,
+                    drawable,
+                    gc,
+                    width,
+                    height,
+                    0,
+                    0,
+                    width,
+                    height,
+                    dst.0,
+                    dst.1,
+                    depth,
+                    xproto::ImageFormat::Z_PIXMAP.into(),
+                    false,
+                    shm_segment.seg,
+                    0,
+                )
+                .map_err(PixelCasterError::backend)?
+                .check()
+                .map_err(PixelCasterError::backend)?;
+            }
+            None => {
+                // split in as many requests as needed to not exceed the maximum request length
+                let rows_per_request =
+                    ((connection.maximum_request_bytes() - 64) / row_length).max(1);
+                let mut data =
+                    Vec::with_capacity(rows_per_request.min(height as usize) * row_length);
+                let mut first_row = 0;
+                while first_row < height as usize {
+                    let rows = rows_per_request.min(height as usize - first_row);
+                    data.clear();
+                    (first_row..first_row + rows).for_each(|r| data.extend_from_slice(row(r)));
+                    connection
+                        .put_image(
+                            xproto::ImageFormat::Z_PIXMAP,
+                            drawable,
+                            gc,
+                            width,
+                            rows as u16,
+                            dst.0,
+                            dst.1 + first_row as i16,
+                            0,
+                            depth,
+                            &data,
+                        )
+                        .map_err(PixelCasterError::backend)?;
+                    first_row += rows;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Lets `blend` draw the area onto a VirtualScreen at the given upper left corner, which is the overlay's content
+    /// (with the area at its place) or, without the overlay, a copy of what the root window currently shows (with the area at 0, 0).
+    /// The result is then uploaded
+    fn blend_rect(
+        &self,
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        blend: impl FnOnce(&VirtualScreen, i32, i32) -> Result<(), PixelCasterError>,
+    ) -> Result<(), PixelCasterError> {
+        let Some((x, y, width, height)) =
+            self.visible_part(dst_ul_x, dst_ul_y, area_width, area_height)
+        else {
+            return Ok(());
+        };
+
+        match &self.inner.overlay {
+            // the overlay keeps the premultiplied result, which the compositing manager blends with what's below
+            Some(overlay) => {
+                blend(&overlay.content, dst_ul_x, dst_ul_y)?;
+                self.upload(
+                    overlay.window,
+                    overlay.gc,
+                    32,
+                    &overlay.content.framebuffer().bytes,
+                    self.inner.root_width as usize,
+                    (x, y, width, height),
+                    (x, y),
+                )?;
+                let mut mapped = overlay.mapped.lock().unwrap_or_else(|e| e.into_inner());
+                if !*mapped {
+                    self.inner
+                        .connection
+                        .map_window(overlay.window)
+                        .map_err(PixelCasterError::backend)?;
+                    *mapped = true;
+                }
+            }
+            // without a compositing manager the blending is done on what the root window currently shows
+            None => {
+                let mut current = <u8>::initialize_vec(area_width as usize, area_height as usize);
+                self.capture_rect(&mut current, dst_ul_x, dst_ul_y, area_width, area_height)?;
+                let blended = VirtualScreen::from_pixels(PixelsCollection::<u8>::create(
+                    area_width as usize,
+                    area_height as usize,
+                    current,
+                )?);
+                blend(&blended, 0, 0)?;
+                self.upload(
+                    self.inner.root,
+                    self.inner.root_gc,
+                    self.inner.root_depth,
+                    &blended.framebuffer().bytes,
+                    area_width as usize,
+                    (
+                        (x as i32 - dst_ul_x) as i16,
+                        (y as i32 - dst_ul_y) as i16,
+                        width,
+                        height,
+                    ),
+                    (x, y),
+                )?;
+            }
+        }
+        self.inner
+            .connection
+            .flush()
+            .map_err(PixelCasterError::backend)
+    }
+
+    /// Replaces the shared memory segment with a bigger one if it can't host `size` bytes
+    fn ensure_shm_size(
+        &self,
+        shm_segment: &mut ShmSegment,
+        size: usize,
+    ) -> Result<(), PixelCasterError> {
+        if shm_segment.size >= size {
+            return Ok(());
+        }
+        let connection = &self.inner.connection;
+        if shm_segment.size > 0 {
+            shm::detach(connection, shm_segment.seg).map_err(PixelCasterError::backend)?;
+            unsafe {
+                libc::munmap(shm_segment.ptr as *mut libc::c_void, shm_segment.size);
+            }
+            shm_segment.size = 0;
+        }
+        let reply = shm::create_segment(connection, shm_segment.seg, size as u32, false)
+            .map_err(PixelCasterError::backend)?
+            .reply()
+            .map_err(PixelCasterError::backend)?;
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                std::os::fd::AsRawFd::as_raw_fd(&reply.shm_fd),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            shm::detach(connection, shm_segment.seg).map_err(PixelCasterError::backend)?;
+            return Err(PixelCasterError::backend(format!(
+                "could not map the XShm segment: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+        shm_segment.ptr = ptr as *mut u8;
+        shm_segment.size = size;
+        Ok(())
+    }
+
+    /// Returns the part (x, y, width, height) of the given area that is inside the root window, None if there's none
+    fn visible_part(
+        &self,
+        ul_x: i32,
+        ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Option<(i16, i16, u16, u16)> {
+        let x0 = (ul_x as i64).max(0);
+        let y0 = (ul_y as i64).max(0);
+        let x1 = (ul_x as i64 + area_width as i64).min(self.inner.root_width as i64);
+        let y1 = (ul_y as i64 + area_height as i64).min(self.inner.root_height as i64);
+        if x0 >= x1 || y0 >= y1 {
+            return None;
+        }
+        Some((x0 as i16, y0 as i16, (x1 - x0) as u16, (y1 - y0) as u16))
+    }
+
+    /// Blue in the lowest byte, Red in the third one
+    fn is_bgr_visual(visual: &xproto::Visualtype) -> bool {
+        visual.red_mask == 0xFF0000 && visual.green_mask == 0xFF00 && visual.blue_mask == 0xFF
+    }
+
+    /// A compositing manager owns the _NET_WM_CM_S<screen number> selection
+    fn compositing_manager_running(
+        connection: &RustConnection,
+        screen_num: usize,
+    ) -> Result<bool, PixelCasterError> {
+        let atom = connection
+            .intern_atom(false, format!("_NET_WM_CM_S{}", screen_num).as_bytes())
+            .map_err(PixelCasterError::backend)?
+            .reply()
+            .map_err(PixelCasterError::backend)?
+            .atom;
+        let owner = connection
+            .get_selection_owner(atom)
+            .map_err(PixelCasterError::backend)?
+            .reply()
+            .map_err(PixelCasterError::backend)?
+            .owner;
+        Ok(owner != x11rb::NONE)
+    }
+
+    /// ShmCreateSegment, which lets the server allocate the memory and pass its file descriptor, needs XShm 1.2
+    fn shm_supported(connection: &RustConnection) -> bool {
+        matches!(
+            connection.extension_information(shm::X11_EXTENSION_NAME),
+            Ok(Some(_))
+        ) && shm::query_version(connection)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|version| (version.major_version, version.minor_version) >= (1, 2))
+    }
+
+    fn create_overlay(
+        connection: &RustConnection,
+        root: xproto::Window,
+        argb_visual: xproto::Visualid,
+        width: u16,
+        height: u16,
+    ) -> Result<Overlay, PixelCasterError> {
+        let colormap = connection
+            .generate_id()
+            .map_err(PixelCasterError::backend)?;
+        connection
+            .create_colormap(xproto::ColormapAlloc::NONE, colormap, root, argb_visual)
+            .map_err(PixelCasterError::backend)?;
+        let window = connection
+            .generate_id()
+            .map_err(PixelCasterError::backend)?;
+        connection
+            .create_window(
+                32,
+                window,
+                root,
+                0,
+                0,
+                width,
+                height,
+                0,
+                xproto::WindowClass::INPUT_OUTPUT,
+                argb_visual,
+                &xproto::CreateWindowAux::new()
+                    .override_redirect(1)
+                    .background_pixel(0)
+                    .border_pixel(0)
+                    .colormap(colormap),
+            )
+            .map_err(PixelCasterError::backend)?;
+        // empty input region, so that the clicks go through the overlay
+        if matches!(
+            connection.extension_information(shape::X11_EXTENSION_NAME),
+            Ok(Some(_))
+        ) {
+            shape::rectangles(
+                connection,
+                shape::SO::SET,
+                shape::SK::INPUT,
+                xproto::ClipOrdering::UNSORTED,
+                window,
+                0,
+                0,
+                &[],
+            )
+            .map_err(PixelCasterError::backend)?;
+        }
+        let gc = connection
+            .generate_id()
+            .map_err(PixelCasterError::backend)?;
+        connection
+            .create_gc(gc, window, &xproto::CreateGCAux::new())
+            .map_err(PixelCasterError::backend)?;
+
+        Ok(Overlay {
+            window,
+            gc,
+            content: VirtualScreen::from_pixels(PixelsCollection::<u8>::create(
+                width as usize,
+                height as usize,
+                vec![0; width as usize * height as usize * 4],
+            )?),
+            mapped: Mutex::new(false),
+        })
+    }
+}
+
+impl ScreenBackend for X11Backend {
+    /// Returns a backend sharing the process-wide connection to the X server set by $DISPLAY
+    fn open(
+        _area_width: u32,
+        _area_height: u32,
+        _is_static: bool,
+    ) -> Result<X11Backend, PixelCasterError> {
+        Self::shared()
+    }
+
+    /// Pixels outside of the root window are captured as 0s
+    fn capture_rect(
+        &self,
+        dst: &mut [u8],
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+    ) -> Result<(), PixelCasterError> {
+        dst.fill(0);
+        let Some((x, y, width, height)) =
+            self.visible_part(src_ul_x, src_ul_y, area_width, area_height)
+        else {
+            return Ok(());
+        };
+        let row_length = width as usize * 4;
+        let mut copy_rows = |bytes: &[u8]| {
+            for (row, captured_row) in bytes.chunks_exact(row_length).enumerate() {
+                let i = (((y as i32 - src_ul_y) as usize + row) * area_width as usize
+                    + (x as i32 - src_ul_x) as usize)
+                    * 4;
+                if let Some(dst_row) = dst.get_mut(i..i + row_length) {
+                    dst_row.copy_from_slice(captured_row);
+                    // the root window has no Alpha channel, its pixels are fully opaque
+                    dst_row.chunks_exact_mut(4).for_each(|bgra| bgra[3] = 255);
+                }
+            }
+        };
+
+        let connection = &self.inner.connection;
+        match &self.inner.shm_segment {
+            Some(shm_segment) => {
+                let mut shm_segment = shm_segment.lock().unwrap_or_else(|e| e.into_inner());
+                self.ensure_shm_size(&mut shm_segment, row_length * height as usize)?;
+                shm::get_image(
+                    connection,
+                    self.inner.root,
+                    x,
+                    y,
+                    width,
+                    height,
+                    !0,
+                    xproto::ImageFormat::Z_PIXMAP.into(),
+                    shm_segment.seg,
+                    0,
+                )
+                .map_err(PixelCasterError::backend)?
+                .reply()
+                .map_err(PixelCasterError::backend)?;
+                copy_rows(unsafe {
+                    std::slice::from_raw_parts(shm_segment.ptr, row_length * height as usize)
+                });
+            }
+            None => {
+                let image = connection
+                    .get_image(
+                        xproto::ImageFormat::Z_PIXMAP,
+                        self.inner.root,
+                        x,
+                        y,
+                        width,
+                        height,
+                        !0,
+                    )
+                    .map_err(PixelCasterError::backend)?
+                    .reply()
+                    .map_err(PixelCasterError::backend)?;
+                copy_rows(&image.data);
+            }
+        }
+        Ok(())
+    }
+
+    /// The BGRA bytes are blended as defined by the PixelsSendMode, by the compositing manager when the overlay window is used
+    fn present_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        pixels_send_mode: PixelsSendMode,
+    ) -> Result<(), PixelCasterError> {
+        self.blend_rect(
+            dst_ul_x,
+            dst_ul_y,
+            area_width,
+            area_height,
+            |screen, ul_x, ul_y| {
+                screen.present_rect(src, ul_x, ul_y, area_width, area_height, pixels_send_mode)
+            },
+        )
+    }
+
+    fn present_premultiplied_rect(
+        &self,
+        src: &[u8],
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        source_constant_alpha: u8,
+    ) -> Result<(), PixelCasterError> {
+        self.blend_rect(
+            dst_ul_x,
+            dst_ul_y,
+            area_width,
+            area_height,
+            |screen, ul_x, ul_y| {
+                screen.present_premultiplied_rect(
+                    src,
+                    ul_x,
+                    ul_y,
+                    area_width,
+                    area_height,
+                    source_constant_alpha,
+                )
+            },
+        )
+    }
+
+    /// Captures the source area and sends it, fully opaque, to the destination one
+    fn copy_rect(
+        &self,
+        src_ul_x: i32,
+        src_ul_y: i32,
+        area_width: u32,
+        area_height: u32,
+        dst_ul_x: i32,
+        dst_ul_y: i32,
+    ) -> Result<(), PixelCasterError> {
+        let mut captured = <u8>::initialize_vec(area_width as usize, area_height as usize);
+        self.capture_rect(&mut captured, src_ul_x, src_ul_y, area_width, area_height)?;
+        self.present_rect(
+            &captured,
+            dst_ul_x,
+            dst_ul_y,
+            area_width,
+            area_height,
+            PixelsSendMode::AlphaDisabled,
+        )
+    }
+
+    fn screen_bounds(&self) -> ScreenArea {
+        ScreenArea::new(
+            0,
+            0,
+            self.inner.root_width as u32,
+            self.inner.root_height as u32,
+        )
+    }
+}
@@ -1,9 +1,19 @@
+//! With the `x11` feature these tests reach the real screen, run them under Xvfb: `xvfb-run cargo test --features x11 -- --ignored`
 use pixel_caster::bgra_management::{bytes_matchers, SwitchBytes};
 use pixel_caster::{pixels_string::*, PixelsCollection, *};
+use std::sync::Once;
+
+/// fonts/exports isn't tracked, the CharsCollections exported by png_to_char_collection are created once per run, before any test reads them
+static CHAR_COLLECTIONS_EXPORTED: Once = Once::new();
 
 #[test]
+#[cfg_attr(feature = "x11", ignore = "needs an X server")]
 /// Gets a PixelsCollection from a .png, then attempts to create a CharsCollection from it
 fn png_to_char_collection() {
+    CHAR_COLLECTIONS_EXPORTED.call_once(create_and_export_char_collections);
+}
+
+fn create_and_export_char_collections() {
     let image = PixelsCollection::<u8>::from_png(
         "fonts/exports/transparent_green_40px_chars_sample__transparent_background.png",
     )
@@ -28,7 +38,7 @@ fn png_to_char_collection() {
                 transparent_green_string_transparent_background_from_chars_sample_40px_green_whitebackground.pixels.width as u32,
                 transparent_green_string_transparent_background_from_chars_sample_40px_green_whitebackground.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled
-            );
+            ).unwrap();
             // export the string as .png
             // image::save_buffer_with_format(format!("{}{}", "fonts/exports/", "test_improvement.png"), &vec_u8_managing::<u8>::swap_blue_with_red(&string_from_string_png.pixels.bytes), string_from_string_png.pixels.width as u32, string_from_string_png.pixels.height as u32, image::ColorType::Rgba8, image::ImageFormat::Png).unwrap();
 
@@ -69,7 +79,8 @@ fn png_to_char_collection() {
                 string_from_string_png.pixels.width as u32,
                 string_from_string_png.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
 
             opaque_grey_scale_chars_white_background.export_as_pngs("fonts/exports/from_opaque_grey_scale_12px_chars_sample__white_background/opaque_grey_scale__white_background").unwrap();
         }
@@ -102,7 +113,8 @@ fn png_to_char_collection() {
                 string_from_string_png.pixels.width as u32,
                 string_from_string_png.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
 
             transparent_black_chars_transparent_background.export_as_pngs("fonts/exports/from_opaque_grey_scale_12px_chars_sample__white_background/transparent_black__transparent_background").unwrap();
         }
@@ -134,7 +146,8 @@ fn png_to_char_collection() {
                 string_from_string_png.pixels.width as u32,
                 string_from_string_png.pixels.height as u32,
                 PixelsSendMode::AlphaEnabled,
-            );
+            )
+            .unwrap();
 
             threshold_black_chars_transparent_background.export_as_pngs("fonts/exports/from_opaque_grey_scale_12px_chars_sample__white_background/threshold_black__transparent_background").unwrap();
 
@@ -149,7 +162,9 @@ fn png_to_char_collection() {
 
 /// Attempts to create a CharsCollection from a folder of .png, then creates and alterates its clones
 #[test]
+#[cfg_attr(feature = "x11", ignore = "needs an X server")]
 fn char_collection_alteration() {
+    CHAR_COLLECTIONS_EXPORTED.call_once(create_and_export_char_collections);
     let threshold_black_chars_transparent_background = CharsCollection::from_pngs_folder("fonts/exports/from_opaque_grey_scale_12px_chars_sample__white_background/threshold_black__transparent_background").unwrap();
 
     let pixels_string_black_transparent_background = threshold_black_chars_transparent_background
@@ -161,7 +176,8 @@ fn char_collection_alteration() {
         pixels_string_black_transparent_background.pixels.width as u32,
         pixels_string_black_transparent_background.pixels.height as u32,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 
     let mut threshold_black_chars_white_background =
         threshold_black_chars_transparent_background.clone();
@@ -180,7 +196,8 @@ fn char_collection_alteration() {
         pixels_string_black_white_background.pixels.width as u32,
         pixels_string_black_white_background.pixels.height as u32,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 
     threshold_black_chars_white_background.export_as_pngs("fonts/exports/from_threshold_black__transparent_background/threshold_black_chars__white_background").unwrap();
 
@@ -196,12 +213,14 @@ fn char_collection_alteration() {
         pixels_string_red_transparent_background.pixels.width as u32,
         pixels_string_red_transparent_background.pixels.height as u32,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 
     transparent_black_chars_transparent_background.export_as_pngs("fonts/exports/from_transparent_black__transparent_background/transparent_red_chars__transparent_background").unwrap();
 }
 
 #[test]
+#[cfg_attr(feature = "x11", ignore = "needs an X server")]
 fn copy_and_paste_pixels() {
     let screen_area_to_capture_upperleftcorner_x = 100;
     let screen_area_to_capture_upperleftcorner_y = 100;
@@ -210,7 +229,7 @@ fn copy_and_paste_pixels() {
 
     // u8 Screen variant
     let mut screen_u8: Screen<u8> = Screen::new(80, 2, pixels_width, pixels_height);
-    screen_u8.scan_area();
+    screen_u8.scan_area().unwrap();
 
     // send the bytes to the pixels of the requested size of an absolute position on the screen
     Screen::update_area_custom(
@@ -220,7 +239,8 @@ fn copy_and_paste_pixels() {
         pixels_width,
         pixels_height,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 
     println!("Each pixel's color is obtained by its BGRA values combination, in a Vector of u8 those 4 values occupy 1 position each, in a Vector of u32 those 4 values occupy together just one position.");
     println!("To contain the BGRA (Blue, Green, Red, Alpha) values of a single pixel a Vec<u8> would have a lenght of 4, a Vec<u32> would have a lenght of just 1");
@@ -241,7 +261,7 @@ fn copy_and_paste_pixels() {
 
     // u32 Screen variant (the variant can also be specified using the turbofish ::<>, as in this case)
     let mut screen_u32 = Screen::<u32>::new(80, 2, 4, 1);
-    screen_u32.scan_area();
+    screen_u32.scan_area().unwrap();
     let vec_u32_first_value = screen_u32.get_bytes()[0];
     let bgra = <u32>::u8_u32_casting(&[vec_u32_first_value]);
     println!(
@@ -256,6 +276,7 @@ fn copy_and_paste_pixels() {
 }
 
 #[test]
+#[cfg_attr(feature = "x11", ignore = "needs an X server")]
 fn copy_and_paste_pixels_slimmed() {
     let screen_area_to_capture_upperleftcorner_x = 100;
     let screen_area_to_capture_upperleftcorner_y = 100;
@@ -264,7 +285,7 @@ fn copy_and_paste_pixels_slimmed() {
 
     // u8 Screen variant
     let mut screen_u8: Screen<u8> = Screen::new(80, 2, pixels_width, pixels_height);
-    screen_u8.scan_area();
+    screen_u8.scan_area().unwrap();
 
     let mut screen_u8_dst: Screen<u8> = Screen::new(
         screen_area_to_capture_upperleftcorner_x + 100,
@@ -272,9 +293,13 @@ fn copy_and_paste_pixels_slimmed() {
         pixels_width,
         pixels_height,
     );
-    screen_u8_dst.update_area_from_vec(screen_u8.get_bytes());
-    screen_u8.scan_area();
-    screen_u8_dst.update_area_from_vec(screen_u8.get_bytes());
+    screen_u8_dst
+        .update_area_from_vec(screen_u8.get_bytes())
+        .unwrap();
+    screen_u8.scan_area().unwrap();
+    screen_u8_dst
+        .update_area_from_vec(screen_u8.get_bytes())
+        .unwrap();
 
     println!("Each pixel's color is obtained by its BGRA values combination, in a Vector of u8 those 4 values occupy 1 position each, in a Vector of u32 those 4 values occupy together just one position.");
     println!("To contain the BGRA (Blue, Green, Red, Alpha) values of a single pixel a Vec<u8> would have a lenght of 4, a Vec<u32> would have a lenght of just 1");
@@ -295,7 +320,7 @@ fn copy_and_paste_pixels_slimmed() {
 
     // u32 Screen variant (the variant can also be specified using the turbofish ::<>, as in this case)
     let mut screen_u32 = Screen::<u32>::new(80, 2, 4, 1);
-    screen_u32.scan_area();
+    screen_u32.scan_area().unwrap();
     let vec_u32_first_value = screen_u32.get_bytes()[0];
     let bgra = <u32>::u8_u32_casting(&[vec_u32_first_value]);
     println!(
@@ -310,6 +335,7 @@ fn copy_and_paste_pixels_slimmed() {
 }
 
 #[test]
+#[cfg_attr(feature = "x11", ignore = "needs an X server")]
 fn test_send_bytes_transparency() {
     let image_u8_bgra =
         PixelsCollection::<u8>::from_png("media/Logo_MK7_Transparent_Bg_ColorsWithHalfAlpha.png")
@@ -321,7 +347,8 @@ fn test_send_bytes_transparency() {
         image_u8_bgra.width as u32,
         image_u8_bgra.height as u32,
         PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
     Screen::update_area_custom(
         &image_u8_bgra.bytes,
         0,
@@ -329,7 +356,8 @@ fn test_send_bytes_transparency() {
         image_u8_bgra.width as u32,
         image_u8_bgra.height as u32,
         PixelsSendMode::AlphaDisabled,
-    );
+    )
+    .unwrap();
     Screen::update_area_custom(
         &image_u8_bgra.bytes,
         0,
@@ -337,7 +365,8 @@ fn test_send_bytes_transparency() {
         image_u8_bgra.width as u32,
         image_u8_bgra.height as u32,
         PixelsSendMode::AlphaDisabledHideBGR(0, 0, 255),
-    );
+    )
+    .unwrap();
     Screen::update_area_custom(
         &image_u8_bgra.bytes,
         0,
@@ -345,10 +374,12 @@ fn test_send_bytes_transparency() {
         image_u8_bgra.width as u32,
         image_u8_bgra.height as u32,
         PixelsSendMode::CustomAlpha(100),
-    );
+    )
+    .unwrap();
 }
 
 #[test]
+#[cfg_attr(feature = "x11", ignore = "needs an X server")]
 fn test_get_bytes() {
     let screen_area_upperleftcorner_x = 100;
     let screen_area_upperleftcorner_y = 100;
@@ -373,5 +404,6 @@ fn test_get_bytes() {
         area_width as u32,
         area_height as u32,
         pixel_caster::PixelsSendMode::AlphaEnabled,
-    );
+    )
+    .unwrap();
 }
@@ -1,4 +1,4 @@
-#![allow(dead_code, unused, unused_macros)]
+#![allow(dead_code, unused, unused_macros, non_local_definitions)]
 
 #[macro_use]
 extern crate eager;
@@ -0,0 +1,78 @@
+//! Needs an X server, e.g.: `Xvfb :99 -screen 0 800x600x24 & DISPLAY=:99 cargo test --features x11 --test x11_test -- --ignored`
+#![cfg(all(unix, feature = "x11"))]
+
+use pixel_caster::*;
+
+/// Sends the same pixels to the X server and to a VirtualScreen showing the same content, then compares what's captured from both
+#[test]
+#[ignore = "needs an X server"]
+fn x11_matches_virtual_screen() {
+    let x11 = X11Backend::connect_with_overlay(None, false).unwrap();
+    let bounds = x11.screen_bounds();
+    let (width, height) = (40, 30);
+    // partially outside of the root window, to check the clipping too
+    let (x, y) = (bounds.width() as i32 - 20, 10);
+
+    let background: Vec<u8> = (0..width * height * 4).map(|i| (i % 251) as u8).collect();
+    let sent: Vec<u8> = (0..width * height * 4)
+        .map(|i| (i * 7 % 256) as u8)
+        .collect();
+
+    for mode in [
+        PixelsSendMode::AlphaDisabled,
+        PixelsSendMode::AlphaEnabled,
+        PixelsSendMode::CustomAlpha(100),
+        PixelsSendMode::AlphaDisabledHideBGR(sent[0], sent[1], sent[2]),
+    ] {
+        x11.present_rect(
+            &background,
+            x,
+            y,
+            width,
+            height,
+            PixelsSendMode::AlphaDisabled,
+        )
+        .unwrap();
+        let mut expected = vec![0u8; sent.len()];
+        x11.capture_rect(&mut expected, x, y, width, height)
+            .unwrap();
+        let virtual_screen = VirtualScreen::from_pixels(
+            PixelsCollection::<u8>::create(width as usize, height as usize, expected.clone())
+                .unwrap(),
+        );
+        virtual_screen
+            .present_rect(&sent, 0, 0, width, height, mode)
+            .unwrap();
+        virtual_screen
+            .capture_rect(&mut expected, 0, 0, width, height)
+            .unwrap();
+
+        x11.present_rect(&sent, x, y, width, height, mode).unwrap();
+        let mut captured = vec![0u8; sent.len()];
+        x11.capture_rect(&mut captured, x, y, width, height)
+            .unwrap();
+
+        // the pixels outside of the root window are captured as 0s from both
+        for row in 0..height as usize {
+            let visible = (bounds.width() as i32 - x) as usize * 4;
+            let row = row * width as usize * 4;
+            assert_eq!(captured[row..row + visible], expected[row..row + visible]);
+            assert!(captured[row + visible..row + width as usize * 4]
+                .iter()
+                .all(|&b| b == 0));
+        }
+    }
+}
+
+#[test]
+#[ignore = "needs an X server"]
+fn x11_screen_round_trip() {
+    let mut screen = Screen::<u32, X11Backend>::new(5, 5, 3, 2);
+    screen.get_bytes_mut().copy_from_slice(&[
+        0xFF102030, 0xFF405060, 0xFF708090, 0xFFA0B0C0, 0xFFD0E0F0, 0xFF000000,
+    ]);
+    screen.update_area().unwrap();
+    let mut scanned = vec![0u32; 6];
+    screen.scan_area_onto_vec(&mut scanned).unwrap();
+    assert_eq!(&scanned, screen.get_bytes());
+}